@@ -0,0 +1,16 @@
+use std::process::Command;
+
+/// Records the compiler version for the user agent, which names the
+/// runtime a tool was built with.
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|output| output.split_whitespace().nth(1).map(str::to_string))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=RUSTC_VERSION={version}");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
@@ -0,0 +1,102 @@
+/// Converts a Nexus BBCode description into Markdown so it can be shown with
+/// the same renderer as the documentation. Presentation-only tags (size,
+/// color, font, alignment) are dropped; unknown tags are kept as text.
+pub fn to_markdown(bbcode: &str) -> String {
+    let text = decode_entities(&bbcode.replace("<br />", "\n").replace("<br/>", "\n").replace("<br>", "\n"));
+    let mut out = String::new();
+    let mut rest = text.as_str();
+    let mut link_stack: Vec<Option<String>> = Vec::new();
+
+    while let Some(start) = rest.find('[') {
+        push_text(&mut out, &rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        let (name, value) = match tag.split_once('=') {
+            Some((name, value)) => (name.to_ascii_lowercase(), Some(value.trim_matches('"').to_string())),
+            None => (tag.to_ascii_lowercase(), None),
+        };
+        rest = &rest[end + 1..];
+
+        match name.as_str() {
+            "b" | "/b" => out.push_str("**"),
+            "i" | "/i" => out.push('*'),
+            "s" | "/s" => out.push_str("~~"),
+            "url" => match value {
+                Some(url) => {
+                    out.push('[');
+                    link_stack.push(Some(url));
+                }
+                None => link_stack.push(None),
+            },
+            "/url" => {
+                if let Some(Some(url)) = link_stack.pop() {
+                    out.push_str(&format!("]({})", url));
+                }
+            }
+            "img" => {
+                if let Some(close) = find_ignore_case(rest, "[/img]") {
+                    out.push_str(&format!("\n\n![image]({})\n\n", rest[..close].trim()));
+                    rest = &rest[close + "[/img]".len()..];
+                }
+            }
+            "youtube" => {
+                if let Some(close) = find_ignore_case(rest, "[/youtube]") {
+                    let id = rest[..close].trim();
+                    out.push_str(&format!("[YouTube video](https://www.youtube.com/watch?v={})", id));
+                    rest = &rest[close + "[/youtube]".len()..];
+                }
+            }
+            "list" => out.push('\n'),
+            "/list" => out.push_str("\n\n"),
+            "*" => out.push_str("\n- "),
+            "line" | "hr" => out.push_str("\n\n---\n\n"),
+            "heading" => out.push_str("\n\n## "),
+            "/heading" => out.push_str("\n\n"),
+            "quote" | "/quote" | "code" | "/code" => out.push_str("\n\n"),
+            "u" | "/u" | "size" | "/size" | "color" | "/color" | "font" | "/font"
+            | "center" | "/center" | "left" | "/left" | "right" | "/right"
+            | "spoiler" | "/spoiler" => {}
+            _ => push_text(&mut out, &format!("[{}]", tag)),
+        }
+    }
+    push_text(&mut out, rest);
+
+    out
+}
+
+/// Collects the `[img]` URLs of a description, used for the picture gallery.
+pub fn image_urls(bbcode: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    let mut rest = bbcode;
+    while let Some(start) = find_ignore_case(rest, "[img]") {
+        rest = &rest[start + "[img]".len()..];
+        if let Some(end) = find_ignore_case(rest, "[/img]") {
+            urls.push(rest[..end].trim().to_string());
+            rest = &rest[end..];
+        }
+    }
+    urls
+}
+
+fn push_text(out: &mut String, text: &str) {
+    // Single line breaks are meaningful in descriptions, so make them hard breaks
+    out.push_str(&text.replace('\n', "  \n"));
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.to_ascii_lowercase().find(needle)
+}
+
+/// Decodes the HTML entities Nexus uses in summaries and descriptions.
+pub fn decode_entities(text: &str) -> String {
+    text.replace("&#39;", "'")
+        .replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
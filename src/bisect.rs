@@ -0,0 +1,170 @@
+use std::fs;
+use std::path::Path;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use crate::dependencies;
+use crate::journal;
+use crate::mod_info::{ModId, ModInfo};
+
+/// One answer given during a bisection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BisectStep {
+    /// Mods that were enabled for the test.
+    pub enabled: Vec<ModId>,
+    pub problem: bool,
+}
+
+/// A search for the mod causing a problem, by enabling half of the suspects
+/// at a time. Persisted so it survives restarting the manager between game
+/// runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BisectSession {
+    /// Mods that were enabled when the search started, restored at the end.
+    pub original: Vec<ModId>,
+    /// Mods that may still cause the problem, dependencies before the mods
+    /// needing them so every prefix brings its own requirements.
+    pub suspects: Vec<ModId>,
+    /// Mods enabled for the current test.
+    pub testing: Vec<ModId>,
+    pub steps: Vec<BisectStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BisectState {
+    Testing,
+    /// The problem comes with this mod.
+    Found(ModId),
+    /// The last suspect, tested on its own, doesn't cause the problem.
+    NotFound,
+}
+
+impl BisectSession {
+    /// Starts with every enabled mod as a suspect; the problem is assumed to
+    /// happen with all of them enabled.
+    pub fn start(mods: &[ModInfo]) -> Result<Self> {
+        let original: Vec<ModId> = mods.iter()
+            .filter(|mod_info| mod_info.enabled)
+            .map(|mod_info| mod_info.id)
+            .collect();
+        if original.len() < 2 {
+            bail!("Enable at least two mods to search for the one causing a problem");
+        }
+
+        let mut session = Self {
+            suspects: dependency_order(mods, &original),
+            original,
+            testing: Vec::new(),
+            steps: Vec::new(),
+        };
+        session.testing = session.next_test(mods);
+        Ok(session)
+    }
+
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        journal::write_atomic(path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+
+    /// A last suspect is only the culprit once the problem happened with it
+    /// as the only suspect enabled; otherwise it gets tested on its own.
+    pub fn state(&self) -> BisectState {
+        match self.suspects.as_slice() {
+            [] => BisectState::NotFound,
+            [culprit] if self.steps.last().is_some_and(|step| step.problem) => BisectState::Found(*culprit),
+            _ => BisectState::Testing,
+        }
+    }
+
+    /// Records whether the problem happened with `testing` enabled and
+    /// narrows the suspects to the half that explains it.
+    pub fn answer(&mut self, problem: bool, mods: &[ModInfo]) {
+        if self.state() != BisectState::Testing {
+            return;
+        }
+        let testing = std::mem::take(&mut self.testing);
+        self.suspects.retain(|suspect| testing.contains(suspect) == problem);
+        self.steps.push(BisectStep { enabled: testing, problem });
+        if self.state() == BisectState::Testing {
+            self.testing = self.next_test(mods);
+        }
+    }
+
+    /// Number of tests left in the worst case, counting the test of a last
+    /// suspect on its own.
+    pub fn steps_left(&self) -> usize {
+        if self.state() != BisectState::Testing {
+            return 0;
+        }
+        (usize::BITS - self.suspects.len().saturating_sub(1).leading_zeros()) as usize + 1
+    }
+
+    pub fn is_testing(&self, mod_info: &ModInfo) -> bool {
+        self.testing.contains(&mod_info.id)
+    }
+
+    pub fn was_enabled(&self, mod_info: &ModInfo) -> bool {
+        self.original.contains(&mod_info.id)
+    }
+
+    /// The first half of the suspects, along with everything they need.
+    /// Suspects cleared by earlier answers are enabled only as dependencies.
+    fn next_test(&self, mods: &[ModInfo]) -> Vec<ModId> {
+        let half = self.suspects.len().div_ceil(2);
+        let mut enabled: Vec<ModId> = self.suspects[..half].to_vec();
+        let mut index = 0;
+        while index < enabled.len() {
+            let required = mods.iter()
+                .find(|mod_info| mod_info.id == enabled[index])
+                .map(dependencies::requirements)
+                .unwrap_or_default();
+            for dependency in required {
+                let found = mods.iter()
+                    .filter(|mod_info| dependency.matches(mod_info))
+                    .map(|mod_info| mod_info.id)
+                    .find(|id| self.original.contains(id));
+                if let Some(id) = found {
+                    if !enabled.contains(&id) {
+                        enabled.push(id);
+                    }
+                }
+            }
+            index += 1;
+        }
+        enabled
+    }
+}
+
+/// `ids` ordered so each mod comes after the mods it requires.
+fn dependency_order(mods: &[ModInfo], ids: &[ModId]) -> Vec<ModId> {
+    fn visit(mods: &[ModInfo], ids: &[ModId], id: ModId, ordered: &mut Vec<ModId>, visiting: &mut Vec<ModId>) {
+        if ordered.contains(&id) || visiting.contains(&id) {
+            return;
+        }
+        visiting.push(id);
+        if let Some(mod_info) = mods.iter().find(|m| m.id == id) {
+            for dependency in dependencies::requirements(mod_info) {
+                let required = mods.iter()
+                    .filter(|m| dependency.matches(m))
+                    .map(|m| m.id)
+                    .find(|other| ids.contains(other));
+                if let Some(required) = required {
+                    visit(mods, ids, required, ordered, visiting);
+                }
+            }
+        }
+        visiting.pop();
+        ordered.push(id);
+    }
+
+    let mut ordered = Vec::new();
+    for &id in ids {
+        visit(mods, ids, id, &mut ordered, &mut Vec::new());
+    }
+    ordered
+}
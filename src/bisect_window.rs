@@ -0,0 +1,186 @@
+use gtk::prelude::*;
+use gtk::{Window, Box, Label, Button, Orientation};
+use gtk::glib::clone;
+use crate::bisect::{BisectSession, BisectState};
+use crate::launch_dialog::launch_game;
+use crate::mod_info::{ModId, ModInfo};
+use crate::mod_list::{list_mods, replace_mods, ModList};
+use crate::mod_manager::ModManager;
+use crate::settings::Settings;
+
+/// Guides the user through finding the mod behind a problem by testing
+/// half of the suspects at a time.
+pub fn show_bisect_window(parent: &impl IsA<Window>, mod_list: &ModList) {
+    let window = Window::builder()
+        .title("Find Problem Mod")
+        .transient_for(parent)
+        .default_width(520)
+        .default_height(420)
+        .build();
+
+    let main_box = Box::new(Orientation::Vertical, 12);
+    main_box.set_margin_start(12);
+    main_box.set_margin_end(12);
+    main_box.set_margin_top(12);
+    main_box.set_margin_bottom(12);
+
+    let content = Box::new(Orientation::Vertical, 12);
+    content.set_vexpand(true);
+    main_box.append(&content);
+
+    let status_label = Label::new(None);
+    status_label.set_xalign(0.0);
+    status_label.set_wrap(true);
+    main_box.append(&status_label);
+
+    window.set_child(Some(&main_box));
+    render(&content, &status_label, mod_list);
+    window.present();
+}
+
+fn render(content: &Box, status_label: &Label, mod_list: &ModList) {
+    while let Some(child) = content.first_child() {
+        content.remove(&child);
+    }
+
+    let mods = current_mods(mod_list);
+    let session = match ModManager::new(Settings::load()).and_then(|mod_manager| mod_manager.load_bisect()) {
+        Ok(session) => session,
+        Err(e) => {
+            status_label.set_text(&format!("Failed to load the search: {}", e));
+            None
+        }
+    };
+    let Some(session) = session else {
+        content.append(&wrapped_label(
+            "Finds the mod behind a crash or bug by enabling half of the suspects at a time. \
+             Enable the mods you play with and make sure the problem happens, then start. \
+             After each step, play until the problem would show up and answer.",
+        ));
+        let start_button = Button::with_label("Start");
+        start_button.add_css_class("suggested-action");
+        start_button.set_halign(gtk::Align::Start);
+        start_button.connect_clicked(clone!(@weak content, @weak status_label, @weak mod_list => move |_| {
+            update(&content, &status_label, &mod_list, |mod_manager, mods| mod_manager.start_bisect(mods).map(|_| ()));
+        }));
+        content.append(&start_button);
+        return;
+    };
+
+    let buttons = Box::new(Orientation::Horizontal, 6);
+    match session.state() {
+        BisectState::Testing => {
+            content.append(&wrapped_label(&format!(
+                "Step {}: {} of {} mods enabled. {} suspects left, at most {} more steps.",
+                session.steps.len() + 1,
+                session.testing.len(),
+                session.original.len(),
+                session.suspects.len(),
+                session.steps_left(),
+            )));
+            let names: Vec<String> = session.testing.iter().map(|&id| mod_name(&mods, id)).collect();
+            let enabled = wrapped_label(&format!("Enabled: {}", names.join(", ")));
+            enabled.add_css_class("dim-label");
+            content.append(&enabled);
+            content.append(&wrapped_label("Does the problem still happen?"));
+
+            let play_button = Button::with_label("Play");
+            play_button.connect_clicked(clone!(@weak content, @weak mod_list => move |_| {
+                if let Some(window) = content.root().and_downcast::<Window>() {
+                    launch_game(&window, &mod_list);
+                }
+            }));
+            let problem_button = Button::with_label("Still Happens");
+            problem_button.add_css_class("destructive-action");
+            problem_button.connect_clicked(clone!(@weak content, @weak status_label, @weak mod_list, @strong session => move |_| {
+                let mut session = session.clone();
+                update(&content, &status_label, &mod_list, move |mod_manager, mods| mod_manager.answer_bisect(&mut session, true, mods));
+            }));
+            let gone_button = Button::with_label("It's Gone");
+            gone_button.add_css_class("suggested-action");
+            gone_button.connect_clicked(clone!(@weak content, @weak status_label, @weak mod_list, @strong session => move |_| {
+                let mut session = session.clone();
+                update(&content, &status_label, &mod_list, move |mod_manager, mods| mod_manager.answer_bisect(&mut session, false, mods));
+            }));
+            buttons.append(&play_button);
+            buttons.append(&problem_button);
+            buttons.append(&gone_button);
+            buttons.append(&finish_button("Stop and Restore", content, status_label, mod_list, &session, None));
+        }
+        BisectState::Found(culprit) => {
+            let name = mod_name(&mods, culprit);
+            content.append(&wrapped_label(&format!(
+                "The problem comes with {}. Restore your mods, or restore them with {} left disabled.",
+                name, name
+            )));
+            let keep_disabled = finish_button(&format!("Restore Without {}", name), content, status_label, mod_list, &session, Some(culprit));
+            keep_disabled.add_css_class("suggested-action");
+            buttons.append(&keep_disabled);
+            buttons.append(&finish_button("Restore All", content, status_label, mod_list, &session, None));
+        }
+        BisectState::NotFound => {
+            content.append(&wrapped_label(
+                "No single mod explains the answers. The problem may need several mods together, or happen without mods.",
+            ));
+            buttons.append(&finish_button("Restore Mods", content, status_label, mod_list, &session, None));
+        }
+    }
+    content.append(&buttons);
+}
+
+fn finish_button(
+    label: &str,
+    content: &Box,
+    status_label: &Label,
+    mod_list: &ModList,
+    session: &BisectSession,
+    keep_disabled: Option<ModId>,
+) -> Button {
+    let button = Button::with_label(label);
+    button.connect_clicked(clone!(@weak content, @weak status_label, @weak mod_list, @strong session => move |_| {
+        let session = session.clone();
+        update(&content, &status_label, &mod_list, move |mod_manager, mods| {
+            mod_manager.finish_bisect(&session, keep_disabled, mods)
+        });
+    }));
+    button
+}
+
+/// Runs a step, then saves and shows the new state of the mods even if it
+/// failed part way.
+fn update(
+    content: &Box,
+    status_label: &Label,
+    mod_list: &ModList,
+    step: impl FnOnce(&ModManager, &mut [ModInfo]) -> anyhow::Result<()>,
+) {
+    let mut mods = current_mods(mod_list);
+    let result = ModManager::new(Settings::load()).and_then(|mod_manager| {
+        let result = step(&mod_manager, &mut mods);
+        let _ = mod_manager.save_mod_list(&mods);
+        result
+    });
+    replace_mods(mod_list, &mods);
+    match result {
+        Ok(()) => status_label.set_text(""),
+        Err(e) => status_label.set_text(&format!("Failed to toggle mods: {}", e)),
+    }
+    render(content, status_label, mod_list);
+}
+
+fn mod_name(mods: &[ModInfo], id: ModId) -> String {
+    mods.iter()
+        .find(|mod_info| mod_info.id == id)
+        .map_or_else(|| "a removed mod".to_string(), |mod_info| mod_info.name.clone())
+}
+
+fn current_mods(mod_list: &ModList) -> Vec<ModInfo> {
+    list_mods(mod_list)
+}
+
+fn wrapped_label(text: &str) -> Label {
+    let label = Label::new(Some(text));
+    label.set_xalign(0.0);
+    label.set_wrap(true);
+    label
+}
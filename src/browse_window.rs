@@ -0,0 +1,250 @@
+use gtk::prelude::*;
+use gtk::{Window, ScrolledWindow, Box, Label, Picture, Button, DropDown, FlowBox, Orientation};
+use gtk::glib::{self, clone};
+use std::rc::Rc;
+use std::cell::RefCell;
+use crate::install_dialog::show_install_dialog_with_ids;
+use crate::mod_list::ModList;
+use crate::mod_manager::ModManager;
+use crate::nexus_api::{ModCategory, ModFeed, NexusClient, NexusModInfo};
+use crate::settings::Settings;
+use crate::runtime;
+use crate::bbcode;
+
+const BY_CATEGORY: &str = "By Category";
+
+pub fn show_browse_window(parent: &impl IsA<Window>, mod_list: &ModList) {
+    let window = Window::builder()
+        .title("Browse Nexus Mods")
+        .transient_for(parent)
+        .default_width(960)
+        .default_height(720)
+        .build();
+
+    let main_box = Box::new(Orientation::Vertical, 12);
+    main_box.set_margin_start(12);
+    main_box.set_margin_end(12);
+    main_box.set_margin_top(12);
+    main_box.set_margin_bottom(12);
+
+    // Feed and category selection
+    let controls = Box::new(Orientation::Horizontal, 12);
+    let mut feed_names: Vec<&str> = ModFeed::ALL.iter().map(|feed| feed.label()).collect();
+    feed_names.push(BY_CATEGORY);
+    let feed_dropdown = DropDown::from_strings(&feed_names);
+    let category_dropdown = DropDown::from_strings(&[]);
+    category_dropdown.set_sensitive(false);
+    let refresh_button = Button::from_icon_name("view-refresh-symbolic");
+    let tracked_button = Button::with_label("Install Tracked Mods");
+    tracked_button.set_tooltip_text(Some("Install the mods you track on the Nexus website"));
+    tracked_button.set_hexpand(true);
+    tracked_button.set_halign(gtk::Align::End);
+    controls.append(&feed_dropdown);
+    controls.append(&category_dropdown);
+    controls.append(&refresh_button);
+    controls.append(&tracked_button);
+    main_box.append(&controls);
+
+    let status_label = Label::new(None);
+    status_label.set_xalign(0.0);
+    status_label.set_wrap(true);
+    main_box.append(&status_label);
+
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_vexpand(true);
+    let flow_box = FlowBox::new();
+    flow_box.set_selection_mode(gtk::SelectionMode::None);
+    flow_box.set_homogeneous(true);
+    flow_box.set_max_children_per_line(4);
+    flow_box.set_column_spacing(12);
+    flow_box.set_row_spacing(12);
+    flow_box.set_valign(gtk::Align::Start);
+    scrolled.set_child(Some(&flow_box));
+    main_box.append(&scrolled);
+
+    window.set_child(Some(&main_box));
+
+    let client = match ModManager::new(Settings::load()) {
+        Ok(mod_manager) => mod_manager.nexus_client().cloned(),
+        Err(_) => None,
+    };
+    let Some(client) = client else {
+        status_label.set_text("Set your Nexus API key in Settings to browse mods.");
+        feed_dropdown.set_sensitive(false);
+        refresh_button.set_sensitive(false);
+        tracked_button.set_sensitive(false);
+        window.present();
+        return;
+    };
+
+    let categories: Rc<RefCell<Vec<ModCategory>>> = Rc::new(RefCell::new(Vec::new()));
+
+    // Load the category list once, it only changes when Nexus adds categories
+    let receiver = runtime::spawn(clone!(@strong client => async move {
+        client.get_game_info().await
+    }));
+    glib::spawn_future_local(clone!(@weak category_dropdown, @strong categories => async move {
+        if let Ok(Ok(game_info)) = receiver.recv().await {
+            let names: Vec<&str> = game_info.categories.iter().map(|c| c.name.as_str()).collect();
+            category_dropdown.set_model(Some(&gtk::StringList::new(&names)));
+            *categories.borrow_mut() = game_info.categories;
+        }
+    }));
+
+    let load = Rc::new(clone!(@weak feed_dropdown, @weak category_dropdown, @weak flow_box, @weak status_label, @weak window, @weak mod_list, @strong client, @strong categories => move || {
+        let selected = feed_dropdown.selected() as usize;
+        let category = if selected == ModFeed::ALL.len() {
+            match categories.borrow().get(category_dropdown.selected() as usize) {
+                Some(category) => Some(category.category_id),
+                None => {
+                    status_label.set_text("Select a category");
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        let feed = ModFeed::ALL.get(selected).copied().unwrap_or(ModFeed::LatestAdded);
+
+        while let Some(child) = flow_box.first_child() {
+            flow_box.remove(&child);
+        }
+        status_label.set_text("Loading mods...");
+
+        let receiver = runtime::spawn(clone!(@strong client => async move {
+            match category {
+                Some(category_id) => client.get_mods_in_category(category_id).await,
+                None => client.get_mod_feed(feed).await,
+            }
+        }));
+
+        glib::spawn_future_local(clone!(@weak flow_box, @weak status_label, @weak window, @weak mod_list, @strong client => async move {
+            match receiver.recv().await {
+                Ok(Ok(mods)) => {
+                    status_label.set_text(&format!("{} mods", mods.len()));
+                    for mod_info in &mods {
+                        flow_box.insert(&mod_card(mod_info, &client, &window, &mod_list), -1);
+                    }
+                },
+                Ok(Err(e)) => status_label.set_text(&format!("Failed to load mods: {}", e)),
+                Err(_) => status_label.set_text("Failed to load mods"),
+            }
+        }));
+    }));
+
+    feed_dropdown.connect_selected_notify(clone!(@weak category_dropdown, @strong load => move |dropdown| {
+        let by_category = dropdown.selected() as usize == ModFeed::ALL.len();
+        category_dropdown.set_sensitive(by_category);
+        load();
+    }));
+
+    category_dropdown.connect_selected_notify(clone!(@strong load => move |_| {
+        load();
+    }));
+
+    refresh_button.connect_clicked(clone!(@strong load => move |_| {
+        load();
+    }));
+
+    tracked_button.connect_clicked(clone!(@weak window, @weak mod_list, @weak status_label, @strong client => move |button| {
+        button.set_sensitive(false);
+        status_label.set_text("Fetching tracked mods...");
+
+        let receiver = runtime::spawn(clone!(@strong client => async move {
+            client.get_tracked_mods().await
+        }));
+        glib::spawn_future_local(clone!(@weak window, @weak mod_list, @weak status_label, @weak button => async move {
+            button.set_sensitive(true);
+            let tracked = match receiver.recv().await {
+                Ok(Ok(tracked)) => tracked,
+                Ok(Err(e)) => {
+                    status_label.set_text(&format!("Failed to fetch tracked mods: {}", e));
+                    return;
+                },
+                Err(_) => return,
+            };
+
+            let to_install = ModManager::new(Settings::load())
+                .and_then(|mod_manager| mod_manager.tracked_mods_to_install(&tracked));
+            match to_install {
+                Ok(mod_ids) if mod_ids.is_empty() => {
+                    status_label.set_text("All tracked mods are already installed");
+                },
+                Ok(mod_ids) => {
+                    status_label.set_text(&format!("{} tracked mods to install", mod_ids.len()));
+                    show_install_dialog_with_ids(&window, &mod_list, &mod_ids);
+                },
+                Err(e) => status_label.set_text(&format!("Failed to read mod list: {}", e)),
+            }
+        }));
+    }));
+
+    window.present();
+    load();
+}
+
+fn mod_card(mod_info: &NexusModInfo, client: &NexusClient, window: &Window, mod_list: &ModList) -> Box {
+    let card = Box::new(Orientation::Vertical, 6);
+    card.add_css_class("card");
+    card.set_margin_start(6);
+    card.set_margin_end(6);
+    card.set_margin_top(6);
+    card.set_margin_bottom(6);
+
+    // Thumbnail, filled in once downloaded
+    let picture = Picture::new();
+    picture.set_size_request(200, 112);
+    picture.set_can_shrink(true);
+    card.append(&picture);
+
+    if let Some(url) = mod_info.picture_url.clone() {
+        let receiver = runtime::spawn(clone!(@strong client => async move {
+            client.fetch_image(&url).await
+        }));
+        glib::spawn_future_local(clone!(@weak picture => async move {
+            if let Ok(Ok(bytes)) = receiver.recv().await {
+                if let Ok(texture) = gtk::gdk::Texture::from_bytes(&glib::Bytes::from_owned(bytes)) {
+                    picture.set_paintable(Some(&texture));
+                }
+            }
+        }));
+    }
+
+    let name_label = Label::new(None);
+    name_label.set_markup(&format!("<b>{}</b>", glib::markup_escape_text(&mod_info.name)));
+    name_label.set_xalign(0.0);
+    name_label.set_wrap(true);
+    card.append(&name_label);
+
+    let summary_label = Label::new(Some(&clean_summary(mod_info.summary.as_deref().unwrap_or_default())));
+    summary_label.set_xalign(0.0);
+    summary_label.set_wrap(true);
+    summary_label.set_lines(4);
+    summary_label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+    summary_label.set_vexpand(true);
+    summary_label.set_valign(gtk::Align::Start);
+    card.append(&summary_label);
+
+    let meta_label = Label::new(Some(&format!(
+        "by {} · {} endorsements",
+        mod_info.author,
+        mod_info.endorsement_count
+    )));
+    meta_label.set_xalign(0.0);
+    meta_label.add_css_class("dim-label");
+    card.append(&meta_label);
+
+    let install_button = Button::with_label("Install");
+    let mod_id = mod_info.id;
+    install_button.connect_clicked(clone!(@weak window, @weak mod_list => move |_| {
+        show_install_dialog_with_ids(&window, &mod_list, &[mod_id]);
+    }));
+    card.append(&install_button);
+
+    card
+}
+
+/// Summaries come HTML-escaped with `<br />` line breaks.
+fn clean_summary(summary: &str) -> String {
+    bbcode::decode_entities(&summary.replace("<br />", "\n").replace("<br/>", "\n"))
+}
@@ -0,0 +1,316 @@
+use gtk::prelude::*;
+use gtk::{Box, Button, ButtonsType, DialogFlags, FileChooserAction, FileChooserDialog, FileFilter, Label, MessageDialog, MessageType, Orientation, ResponseType, Window};
+use gtk::glib::{self, clone};
+use crate::mod_info::{ModId, ModInfo};
+use crate::mod_list::{confirm_dependency_issues, connect_selection_changed, list_mods, selected_mods, show_toggle_warning, update_mods, ModList};
+use crate::mod_manager::ModManager;
+use crate::profiles::Profiles;
+use crate::settings::Settings;
+use crate::runtime;
+
+/// Builds the bar of actions on the selected mods, shown while any are
+/// selected. Each action runs as one batch, so paks are deployed or
+/// removed in a single transaction.
+pub fn build_bulk_bar(mod_list: &ModList) -> Box {
+    let bar = Box::new(Orientation::Horizontal, 6);
+    bar.set_margin_start(6);
+    bar.set_margin_end(6);
+    bar.set_margin_bottom(6);
+    bar.set_visible(false);
+
+    let count = Label::new(None);
+    count.set_xalign(0.0);
+    count.set_hexpand(true);
+    bar.append(&count);
+
+    let enable = Button::with_label("Enable");
+    let disable = Button::with_label("Disable");
+    let profile = Button::with_label("Add to Profile");
+    let updates = Button::with_label("Check for Updates");
+    let export = Button::with_label("Export");
+    let uninstall = Button::with_label("Uninstall");
+    uninstall.add_css_class("destructive-action");
+    for button in [&enable, &disable, &profile, &updates, &export, &uninstall] {
+        bar.append(button);
+    }
+
+    enable.connect_clicked(clone!(@weak mod_list => move |_| set_enabled(&mod_list, true)));
+    disable.connect_clicked(clone!(@weak mod_list => move |_| set_enabled(&mod_list, false)));
+    profile.connect_clicked(clone!(@weak mod_list => move |_| add_to_profile(&mod_list)));
+    updates.connect_clicked(clone!(@weak mod_list => move |button| check_for_updates(&mod_list, button)));
+    export.connect_clicked(clone!(@weak mod_list => move |_| export_selection(&mod_list)));
+    uninstall.connect_clicked(clone!(@weak mod_list => move |_| confirm_uninstall(&mod_list)));
+
+    connect_selection_changed(mod_list, clone!(@weak bar, @weak count => move |selected| {
+        bar.set_visible(!selected.is_empty());
+        count.set_text(&match selected.len() {
+            1 => "1 mod selected".to_string(),
+            n => format!("{} mods selected", n),
+        });
+    }));
+
+    bar
+}
+
+fn selected_ids(mod_list: &ModList) -> Vec<ModId> {
+    selected_mods(mod_list).iter().map(|object| object.id()).collect()
+}
+
+/// Enables or disables the selected mods with the checks of a single
+/// switch: incompatible mods are refused, and unmet dependencies are shown
+/// before anything is moved so the batch can be cancelled, forced or fixed.
+fn set_enabled(mod_list: &ModList, enabled: bool) {
+    let Some(mod_manager) = mod_manager(mod_list) else {
+        return;
+    };
+    let mods = list_mods(mod_list);
+    let selected = selected_ids(mod_list);
+    let ids: Vec<ModId> = mods.iter()
+        .filter(|mod_info| selected.contains(&mod_info.id))
+        .filter(|mod_info| mod_info.installed_path.is_some() && mod_info.enabled != enabled)
+        .map(|mod_info| mod_info.id)
+        .collect();
+    if ids.is_empty() {
+        return;
+    }
+
+    let (violations, issues) = mod_manager.batch_problems(&mods, &ids, enabled);
+    if violations.iter().any(|violation| violation.is_blocking()) {
+        let messages = violations.iter()
+            .filter(|violation| violation.is_blocking())
+            .map(ToString::to_string)
+            .collect();
+        show_toggle_warning(mod_list, MessageType::Error, messages, Vec::new());
+        return;
+    }
+    let warnings: Vec<String> = violations.iter().map(ToString::to_string).collect();
+
+    if issues.is_empty() {
+        apply_enabled(mod_list, &ids, enabled, warnings);
+        return;
+    }
+    confirm_dependency_issues(mod_list, enabled, &issues, warnings, clone!(@weak mod_list => move || {
+        apply_enabled(&mod_list, &ids, enabled, Vec::new());
+    }));
+}
+
+/// Backs up saves once for the batch if needed, toggles the mods in one
+/// transaction, then shows `warnings`.
+fn apply_enabled(mod_list: &ModList, ids: &[ModId], enabled: bool, warnings: Vec<String>) {
+    let Some(mod_manager) = mod_manager(mod_list) else {
+        return;
+    };
+    let mut mods = list_mods(mod_list);
+
+    // One backup covers every save-breaking mod of the batch
+    let save_breaking = mods.iter().find(|mod_info| ids.contains(&mod_info.id) && mod_info.save_breaking);
+    if let Some(mod_info) = save_breaking.filter(|_| enabled) {
+        if let Err(e) = mod_manager.backup_before_enable(mod_info, &mods) {
+            let messages = vec![format!("The saves could not be backed up, so the mods were left disabled: {}", e)];
+            show_toggle_warning(mod_list, MessageType::Error, messages, Vec::new());
+            return;
+        }
+    }
+
+    if let Err(e) = mod_manager.set_mods_enabled(&mut mods, ids, enabled) {
+        show_message(mod_list, MessageType::Error, &format!("Failed to {} the mods: {}", if enabled { "enable" } else { "disable" }, e));
+        return;
+    }
+    update_mods(mod_list, &mods);
+
+    if !warnings.is_empty() {
+        show_toggle_warning(mod_list, MessageType::Warning, warnings, Vec::new());
+    }
+}
+
+/// Asks which profile to add the selected mods to.
+fn add_to_profile(mod_list: &ModList) {
+    let Some(mod_manager) = mod_manager(mod_list) else {
+        return;
+    };
+    let names: Vec<String> = Profiles::load(&mod_manager.profiles_path())
+        .map(|profiles| profiles.profiles.into_iter().map(|profile| profile.name).collect())
+        .unwrap_or_default();
+    if names.is_empty() {
+        show_message(mod_list, MessageType::Info, "There are no profiles yet. Create one under Profiles & Saves.");
+        return;
+    }
+
+    let dialog = MessageDialog::new(
+        window(mod_list).as_ref(),
+        DialogFlags::MODAL,
+        MessageType::Question,
+        ButtonsType::None,
+        "Add the selected mods to a profile",
+    );
+    dialog.set_secondary_text(Some("They are enabled the next time the profile is switched to."));
+    let profiles = gtk::DropDown::from_strings(&names.iter().map(String::as_str).collect::<Vec<_>>());
+    if let Some(area) = dialog.message_area().downcast_ref::<Box>() {
+        area.append(&profiles);
+    }
+    dialog.add_button("Cancel", ResponseType::Cancel);
+    dialog.add_button("Add", ResponseType::Accept);
+    dialog.set_default_response(ResponseType::Accept);
+
+    let ids = selected_ids(mod_list);
+    dialog.connect_response(clone!(@weak mod_list, @weak profiles => move |dialog, response| {
+        dialog.close();
+        if response != ResponseType::Accept {
+            return;
+        }
+        let Some(name) = names.get(profiles.selected() as usize) else {
+            return;
+        };
+        if let Err(e) = mod_manager.add_to_profile(name, &ids) {
+            show_message(&mod_list, MessageType::Error, &format!("Failed to update the profile: {}", e));
+        }
+    }));
+    dialog.present();
+}
+
+/// Looks up the latest version of the selected Nexus mods, which the
+/// version column and the Outdated filter then show.
+fn check_for_updates(mod_list: &ModList, button: &Button) {
+    let Some(client) = mod_manager(mod_list).and_then(|mod_manager| mod_manager.nexus_client().cloned()) else {
+        show_message(mod_list, MessageType::Info, "Set a Nexus API key in Settings to check for updates.");
+        return;
+    };
+    let objects = selected_mods(mod_list);
+    let mut mod_ids: Vec<i32> = objects.iter()
+        .filter_map(|object| object.mod_info().nexus_mod_id)
+        .collect();
+    mod_ids.sort_unstable();
+    mod_ids.dedup();
+    if mod_ids.is_empty() {
+        show_message(mod_list, MessageType::Info, "None of the selected mods are from Nexus Mods.");
+        return;
+    }
+
+    button.set_sensitive(false);
+    let receiver = runtime::spawn(async move {
+        client.latest_versions(&mod_ids).await
+    });
+    glib::spawn_future_local(clone!(@weak mod_list, @weak button => async move {
+        let result = receiver.recv().await;
+        button.set_sensitive(true);
+        let versions = match result {
+            Ok(Ok(versions)) => versions,
+            Ok(Err(e)) => {
+                show_message(&mod_list, MessageType::Error, &format!("Failed to check for updates: {}", e));
+                return;
+            }
+            Err(_) => {
+                show_message(&mod_list, MessageType::Error, "The update check stopped before it finished.");
+                return;
+            }
+        };
+        for object in &objects {
+            object.update(|mod_info| mod_info.apply_latest_version(&versions));
+        }
+        let outdated: Vec<String> = objects.iter()
+            .map(|object| object.mod_info())
+            .filter(ModInfo::is_outdated)
+            .map(|mod_info| format!("• {} {} → {}", mod_info.name, mod_info.version, mod_info.latest_version.unwrap_or_default()))
+            .collect();
+        if outdated.is_empty() {
+            show_message(&mod_list, MessageType::Info, "The selected mods are up to date.");
+        } else {
+            show_message(&mod_list, MessageType::Info, &format!("Updates are available:\n{}", outdated.join("\n")));
+        }
+    }));
+}
+
+/// Exports the selected mods to a zip that "Import Mods" in Settings reads.
+fn export_selection(mod_list: &ModList) {
+    let file_chooser = FileChooserDialog::new(
+        Some("Export Selected Mods"),
+        window(mod_list).as_ref(),
+        FileChooserAction::Save,
+        &[("Cancel", ResponseType::Cancel), ("Export", ResponseType::Accept)]
+    );
+    let filter = FileFilter::new();
+    filter.add_pattern("*.zip");
+    filter.set_name(Some("ZIP files"));
+    file_chooser.add_filter(&filter);
+    file_chooser.set_current_name("mods-export.zip");
+
+    let ids = selected_ids(mod_list);
+    file_chooser.connect_response(clone!(@weak mod_list => move |file_chooser, response| {
+        file_chooser.close();
+        if response != ResponseType::Accept {
+            return;
+        }
+        let (Some(path), Some(mod_manager)) = (file_chooser.file().and_then(|file| file.path()), mod_manager(&mod_list)) else {
+            return;
+        };
+        let mods: Vec<ModInfo> = list_mods(&mod_list).into_iter()
+            .filter(|mod_info| ids.contains(&mod_info.id))
+            .collect();
+        if let Err(e) = mod_manager.export_mod_selection(&path, &mods) {
+            show_message(&mod_list, MessageType::Error, &format!("Failed to export the mods: {}", e));
+        }
+    }));
+    file_chooser.show();
+}
+
+fn confirm_uninstall(mod_list: &ModList) {
+    let ids = selected_ids(mod_list);
+    let dialog = MessageDialog::new(
+        window(mod_list).as_ref(),
+        DialogFlags::MODAL,
+        MessageType::Question,
+        ButtonsType::None,
+        match ids.len() {
+            1 => "Uninstall the selected mod?".to_string(),
+            n => format!("Uninstall {} mods?", n),
+        },
+    );
+    dialog.set_secondary_text(Some("Their files are deleted from the game folder and the mod store."));
+    dialog.add_button("Cancel", ResponseType::Cancel);
+    dialog.add_button("Uninstall", ResponseType::Accept);
+    if let Some(widget) = dialog.widget_for_response(ResponseType::Accept) {
+        widget.add_css_class("destructive-action");
+    }
+
+    dialog.connect_response(clone!(@weak mod_list => move |dialog, response| {
+        dialog.close();
+        if response != ResponseType::Accept {
+            return;
+        }
+        let Some(mod_manager) = mod_manager(&mod_list) else {
+            return;
+        };
+        let mut mods = list_mods(&mod_list);
+        match mod_manager.uninstall_mods(&mut mods, &ids) {
+            Ok(()) => update_mods(&mod_list, &mods),
+            Err(e) => show_message(&mod_list, MessageType::Error, &format!("Failed to uninstall the mods: {}", e)),
+        }
+    }));
+    dialog.present();
+}
+
+fn mod_manager(mod_list: &ModList) -> Option<ModManager> {
+    match ModManager::new(Settings::load()) {
+        Ok(mod_manager) => Some(mod_manager),
+        Err(e) => {
+            show_message(mod_list, MessageType::Error, &format!("Failed to load the mods: {}", e));
+            None
+        }
+    }
+}
+
+fn window(mod_list: &ModList) -> Option<Window> {
+    mod_list.root().and_downcast::<Window>()
+}
+
+fn show_message(mod_list: &ModList, message_type: MessageType, text: &str) {
+    let dialog = MessageDialog::new(
+        window(mod_list).as_ref(),
+        DialogFlags::MODAL,
+        message_type,
+        ButtonsType::Close,
+        text,
+    );
+    dialog.connect_response(|dialog, _| dialog.close());
+    dialog.present();
+}
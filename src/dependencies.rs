@@ -0,0 +1,210 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::mod_info::{ModId, ModInfo, ModKind};
+use crate::nexus_api::ModRequirement;
+use crate::ue4ss;
+
+/// What a dependency points at: a Nexus mod, a mod installed locally that
+/// never came from Nexus, or the UE4SS runtime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DependencyTarget {
+    Nexus { mod_id: i32 },
+    Local { id: ModId },
+    Ue4ss,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dependency {
+    pub target: DependencyTarget,
+    /// Display name, kept so missing dependencies can still be named.
+    pub name: String,
+}
+
+impl DependencyTarget {
+    /// How another mod refers to `mod_info`, preferring its Nexus ID.
+    pub fn for_mod(mod_info: &ModInfo) -> Self {
+        match mod_info.nexus_mod_id {
+            Some(mod_id) => DependencyTarget::Nexus { mod_id },
+            None if mod_info.kind == ModKind::Ue4ss => DependencyTarget::Ue4ss,
+            None => DependencyTarget::Local { id: mod_info.id },
+        }
+    }
+
+    pub fn matches(&self, mod_info: &ModInfo) -> bool {
+        match self {
+            DependencyTarget::Nexus { mod_id } => mod_info.nexus_mod_id == Some(*mod_id),
+            DependencyTarget::Local { id } => mod_info.id == *id,
+            DependencyTarget::Ue4ss => mod_info.kind == ModKind::Ue4ss,
+        }
+    }
+}
+
+/// Rewrites the local targets of rules and dependencies in a saved mod list
+/// that still name their mod, from before they held its ID. Targets naming
+/// no entry are dropped. Every entry must already have an ID. Returns
+/// whether anything changed.
+pub fn migrate_local_targets(entries: &mut [serde_json::Value]) -> bool {
+    let ids: Vec<(String, serde_json::Value)> = entries.iter()
+        .filter_map(|entry| Some((entry.get("name")?.as_str()?.to_string(), entry.get("id")?.clone())))
+        .collect();
+    let mut changed = false;
+
+    for entry in entries.iter_mut() {
+        for field in ["rules", "dependencies"] {
+            let Some(list) = entry.get_mut(field).and_then(|list| list.as_array_mut()) else {
+                continue;
+            };
+            list.retain_mut(|item| {
+                let Some(target) = item.get_mut("target") else {
+                    return true;
+                };
+                if target.get("kind").and_then(|kind| kind.as_str()) != Some("local") {
+                    return true;
+                }
+                let Some(name) = target.get("name").and_then(|name| name.as_str()).map(str::to_string) else {
+                    return true;
+                };
+                changed = true;
+                if name == ue4ss::NAME {
+                    *target = serde_json::json!({ "kind": "ue4ss" });
+                    return true;
+                }
+                match ids.iter().find(|(other, _)| *other == name) {
+                    Some((_, id)) => {
+                        *target = serde_json::json!({ "kind": "local", "id": id });
+                        true
+                    }
+                    None => false,
+                }
+            });
+        }
+    }
+
+    changed
+}
+
+impl Dependency {
+    /// Requirements listed on Nexus; external ones can't be checked locally
+    /// and are only shown in the details pane.
+    pub fn from_requirements(requirements: &[ModRequirement]) -> Vec<Dependency> {
+        requirements.iter()
+            .filter_map(|requirement| Some(Dependency {
+                target: DependencyTarget::Nexus { mod_id: requirement.nexus_mod_id()? },
+                name: requirement.mod_name.clone(),
+            }))
+            .collect()
+    }
+
+    pub fn matches(&self, mod_info: &ModInfo) -> bool {
+        self.target.matches(mod_info)
+    }
+}
+
+/// The recorded dependencies of a mod plus the implicit ones, like UE4SS
+/// for Lua mods.
+pub fn requirements(mod_info: &ModInfo) -> Vec<Dependency> {
+    let mut requirements = mod_info.dependencies.clone();
+    if mod_info.kind == ModKind::Lua {
+        requirements.push(Dependency {
+            target: DependencyTarget::Ue4ss,
+            name: ue4ss::NAME.to_string(),
+        });
+    }
+    requirements
+}
+
+/// One-click resolution for a dependency problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyFix {
+    Enable(ModId),
+    Disable(ModId),
+    Install(i32),
+}
+
+#[derive(Debug, Clone)]
+pub enum DependencyIssue {
+    /// `mod_name` requires `dependency`, which is installed but disabled.
+    Disabled { mod_name: String, dependency: String, id: ModId },
+    /// `mod_name` requires `dependency`, which is not installed.
+    Missing { mod_name: String, dependency: Dependency },
+    /// `dependent` is enabled and requires `mod_name`, which is disabled.
+    RequiredBy { mod_name: String, dependent: String, id: ModId },
+}
+
+impl DependencyIssue {
+    pub fn fix(&self) -> Option<DependencyFix> {
+        match self {
+            DependencyIssue::Disabled { id, .. } => Some(DependencyFix::Enable(*id)),
+            DependencyIssue::Missing { dependency, .. } => match dependency.target {
+                DependencyTarget::Nexus { mod_id } => Some(DependencyFix::Install(mod_id)),
+                DependencyTarget::Local { .. } | DependencyTarget::Ue4ss => None,
+            },
+            DependencyIssue::RequiredBy { id, .. } => Some(DependencyFix::Disable(*id)),
+        }
+    }
+}
+
+impl fmt::Display for DependencyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyIssue::Disabled { mod_name, dependency, .. } => {
+                write!(f, "{} requires {} which is disabled", mod_name, dependency)
+            }
+            DependencyIssue::Missing { mod_name, dependency } => {
+                write!(f, "{} requires {} which is not installed", mod_name, dependency.name)
+            }
+            DependencyIssue::RequiredBy { mod_name, dependent, .. } => {
+                write!(f, "{} requires {} which is disabled", dependent, mod_name)
+            }
+        }
+    }
+}
+
+/// Problems with the requirements of `mods[index]` when it is enabled.
+pub fn check_enable(mods: &[ModInfo], index: usize) -> Vec<DependencyIssue> {
+    let Some(mod_info) = mods.get(index) else {
+        return Vec::new();
+    };
+
+    requirements(mod_info).into_iter()
+        .filter_map(|dependency| {
+            match mods.iter().find(|m| dependency.matches(m)) {
+                Some(found) if found.enabled => None,
+                Some(found) => Some(DependencyIssue::Disabled {
+                    mod_name: mod_info.name.clone(),
+                    dependency: found.name.clone(),
+                    id: found.id,
+                }),
+                None => Some(DependencyIssue::Missing {
+                    mod_name: mod_info.name.clone(),
+                    dependency,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Unmet dependencies of every enabled mod.
+pub fn check_all(mods: &[ModInfo]) -> Vec<DependencyIssue> {
+    (0..mods.len())
+        .filter(|&index| mods[index].enabled)
+        .flat_map(|index| check_enable(mods, index))
+        .collect()
+}
+
+/// Enabled mods that depend on `mods[index]` once it is disabled.
+pub fn check_disable(mods: &[ModInfo], index: usize) -> Vec<DependencyIssue> {
+    let Some(mod_info) = mods.get(index) else {
+        return Vec::new();
+    };
+
+    mods.iter()
+        .filter(|other| other.enabled && requirements(other).iter().any(|d| d.matches(mod_info)))
+        .map(|dependent| DependencyIssue::RequiredBy {
+            mod_name: mod_info.name.clone(),
+            dependent: dependent.name.clone(),
+            id: dependent.id,
+        })
+        .collect()
+}
@@ -0,0 +1,491 @@
+use gtk::prelude::*;
+use gtk::{ScrolledWindow, Box, Label, Picture, FlowBox, LinkButton, Orientation};
+use gtk::glib::{self, clone};
+use crate::bbcode;
+use crate::docs_window::render_markdown;
+use crate::dependencies::{self, Dependency, DependencyTarget};
+use crate::mod_info::ModInfo;
+use crate::mod_list::{list_mods, ModList};
+use crate::mod_object::ModObject;
+use crate::rules::{ModRule, RuleKind};
+use crate::mod_manager::ModManager;
+use crate::nexus_api::{self, NexusClient, GAME_DOMAIN};
+use crate::settings::Settings;
+use crate::runtime;
+
+const MAX_CHANGELOG_VERSIONS: usize = 10;
+
+/// Side pane showing the description, changelog, files, requirements and
+/// pictures of the selected mod.
+pub struct DetailsPane {
+    scrolled: ScrolledWindow,
+    content: Box,
+}
+
+impl DetailsPane {
+    pub fn new() -> Self {
+        let scrolled = ScrolledWindow::new();
+        scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+        scrolled.set_vexpand(true);
+        scrolled.set_size_request(320, -1);
+
+        let content = Box::new(Orientation::Vertical, 12);
+        content.set_margin_start(18);
+        content.set_margin_end(18);
+        content.set_margin_top(18);
+        content.set_margin_bottom(18);
+        content.add_css_class("details-pane");
+        scrolled.set_child(Some(&content));
+
+        let pane = Self { scrolled, content };
+        pane.clear();
+        pane
+    }
+
+    pub fn widget(&self) -> &ScrolledWindow {
+        &self.scrolled
+    }
+
+    pub fn clear(&self) {
+        self.remove_children();
+        let placeholder = Label::new(Some("Select a mod to see its details"));
+        placeholder.add_css_class("dim-label");
+        placeholder.set_vexpand(true);
+        self.content.append(&placeholder);
+    }
+
+    pub fn show_mod(&self, mod_list: &ModList, object: &ModObject) {
+        let mod_info = &object.mod_info();
+        self.remove_children();
+
+        let title = Label::new(Some(&mod_info.name));
+        title.add_css_class("title-2");
+        title.set_xalign(0.0);
+        title.set_wrap(true);
+        self.content.append(&title);
+
+        let subtitle = Label::new(Some(&format!("Version {} · by {}", mod_info.version, mod_info.author)));
+        subtitle.add_css_class("dim-label");
+        subtitle.set_xalign(0.0);
+        self.content.append(&subtitle);
+
+        let client = ModManager::new(Settings::load())
+            .ok()
+            .and_then(|mod_manager| mod_manager.nexus_client().cloned());
+
+        if let Some(mod_id) = mod_info.nexus_mod_id {
+            let url = match &client {
+                Some(client) => client.mod_page_url(mod_id),
+                None => nexus_api::mod_page_url(GAME_DOMAIN, mod_id),
+            };
+            let links = Box::new(Orientation::Horizontal, 12);
+            let link = LinkButton::with_label(&url, "View on Nexus Mods");
+            links.append(&link);
+            if let Some(client) = &client {
+                links.append(&track_button(client, mod_id, object));
+            }
+            self.content.append(&links);
+        }
+
+        let save_breaking = gtk::CheckButton::with_label("Breaks saves made without it");
+        save_breaking.set_active(mod_info.save_breaking);
+        save_breaking.set_tooltip_text(Some("With automatic save backups on, the saves are backed up before this mod is enabled"));
+        save_breaking.connect_toggled(clone!(@weak object => move |check| {
+            object.update(|mod_info| mod_info.save_breaking = check.is_active());
+        }));
+        self.content.append(&save_breaking);
+
+        render_rules(&self.section("Rules"), mod_list, object);
+        render_dependencies(&self.section("Dependencies"), mod_list, object);
+
+        if !mod_info.ini_tweaks.is_empty() {
+            let tweaks = self.section("Tweaks");
+            for tweak in &mod_info.ini_tweaks {
+                tweaks.append(&wrapped_label(&format!(
+                    "{} [{}] {}={}",
+                    tweak.file.file_name(),
+                    tweak.section,
+                    tweak.key,
+                    tweak.value
+                )));
+            }
+        }
+
+        if !mod_info.file_mappings.is_empty() {
+            let deployment = self.section("Deployment");
+            for mapping in &mod_info.file_mappings {
+                deployment.append(&wrapped_label(&format!(
+                    "{} → {}/{}",
+                    mapping.source.display(),
+                    mapping.root.label(),
+                    mapping.target.display()
+                )));
+            }
+        }
+
+        let description = self.section("Description");
+        let gallery = FlowBox::new();
+        gallery.set_selection_mode(gtk::SelectionMode::None);
+        gallery.set_max_children_per_line(2);
+
+        if mod_info.description.is_empty() {
+            description.append(&dim_label("No description"));
+        } else {
+            render_description(&description, &gallery, &mod_info.description, client.as_ref());
+        }
+
+        let (Some(mod_id), Some(client)) = (mod_info.nexus_mod_id, client) else {
+            if gallery.first_child().is_some() {
+                self.section("Pictures").append(&gallery);
+            }
+            return;
+        };
+
+        let changelog = self.section("Changelog");
+        let files = self.section("Files");
+        let requirements = self.section("Requirements");
+        self.section("Pictures").append(&gallery);
+        for section in [&changelog, &files, &requirements] {
+            section.append(&dim_label("Loading..."));
+        }
+
+        // Mod info gives the main picture, and the description for mods
+        // that were added without one
+        let has_description = !mod_info.description.is_empty();
+        let receiver = runtime::spawn(clone!(@strong client => async move {
+            client.get_mod_info(mod_id).await
+        }));
+        glib::spawn_future_local(clone!(@weak description, @weak gallery, @strong client => async move {
+            if let Ok(Ok(info)) = receiver.recv().await {
+                if let Some(url) = info.picture_url {
+                    add_picture(&gallery, &client, url);
+                }
+                if !has_description && !info.description.is_empty() {
+                    remove_children(&description);
+                    render_description(&description, &gallery, &info.description, Some(&client));
+                }
+            }
+        }));
+
+        let receiver = runtime::spawn(clone!(@strong client => async move {
+            client.get_changelogs(mod_id).await
+        }));
+        glib::spawn_future_local(clone!(@weak changelog => async move {
+            remove_children(&changelog);
+            match receiver.recv().await {
+                Ok(Ok(entries)) if !entries.is_empty() => {
+                    for (version, changes) in entries.iter().take(MAX_CHANGELOG_VERSIONS) {
+                        let version_label = Label::new(None);
+                        version_label.set_markup(&format!("<b>{}</b>", glib::markup_escape_text(version)));
+                        version_label.set_xalign(0.0);
+                        changelog.append(&version_label);
+                        for change in changes {
+                            changelog.append(&wrapped_label(&format!("• {}", bbcode::decode_entities(change))));
+                        }
+                    }
+                },
+                Ok(Ok(_)) => changelog.append(&dim_label("No changelog")),
+                _ => changelog.append(&dim_label("Failed to load changelog")),
+            }
+        }));
+
+        let receiver = runtime::spawn(clone!(@strong client => async move {
+            client.get_mod_files(mod_id).await
+        }));
+        glib::spawn_future_local(clone!(@weak files => async move {
+            remove_children(&files);
+            match receiver.recv().await {
+                Ok(Ok(mod_files)) if !mod_files.is_empty() => {
+                    for file in mod_files {
+                        let mut text = file.name.clone();
+                        if let Some(version) = &file.version {
+                            text.push_str(&format!(" {}", version));
+                        }
+                        if let Some(category) = &file.category_name {
+                            text.push_str(&format!(" ({})", category.to_lowercase().replace('_', " ")));
+                        }
+                        if let Some(size_kb) = file.size_kb {
+                            text.push_str(&format!(" · {:.1} MB", size_kb as f64 / 1024.0));
+                        }
+                        files.append(&wrapped_label(&text));
+                    }
+                },
+                Ok(Ok(_)) => files.append(&dim_label("No files")),
+                _ => files.append(&dim_label("Failed to load files")),
+            }
+        }));
+
+        let receiver = runtime::spawn(async move {
+            client.get_mod_requirements(mod_id).await
+        });
+        glib::spawn_future_local(clone!(@weak requirements => async move {
+            remove_children(&requirements);
+            match receiver.recv().await {
+                Ok(Ok(mods)) if !mods.is_empty() => {
+                    for requirement in mods {
+                        let row = Box::new(Orientation::Vertical, 2);
+                        match &requirement.url {
+                            Some(url) => {
+                                let link = LinkButton::with_label(url, &requirement.mod_name);
+                                link.set_halign(gtk::Align::Start);
+                                row.append(&link);
+                            },
+                            None => row.append(&wrapped_label(&requirement.mod_name)),
+                        }
+                        if let Some(notes) = requirement.notes.as_ref().filter(|n| !n.is_empty()) {
+                            let notes_label = wrapped_label(notes);
+                            notes_label.add_css_class("dim-label");
+                            row.append(&notes_label);
+                        }
+                        requirements.append(&row);
+                    }
+                },
+                Ok(Ok(_)) => requirements.append(&dim_label("No requirements")),
+                _ => requirements.append(&dim_label("Failed to load requirements")),
+            }
+        }));
+    }
+
+    fn section(&self, title: &str) -> Box {
+        let heading = Label::new(Some(title));
+        heading.add_css_class("title-3");
+        heading.set_xalign(0.0);
+        heading.set_margin_top(12);
+        self.content.append(&heading);
+
+        let section = Box::new(Orientation::Vertical, 6);
+        self.content.append(&section);
+        section
+    }
+
+    fn remove_children(&self) {
+        remove_children(&self.content);
+    }
+}
+
+/// Tracks or untracks the mod on Nexus, keeping its list entry in step so
+/// the Untracked filter and later renders see the change.
+fn track_button(client: &NexusClient, mod_id: i32, object: &ModObject) -> gtk::Button {
+    let label = |tracked: bool| if tracked { "Untrack" } else { "Track" };
+    let button = gtk::Button::with_label(label(object.mod_info().tracked));
+    button.set_tooltip_text(Some("Tracked mods show up in your Nexus update notifications"));
+
+    button.connect_clicked(clone!(@strong client, @weak object => move |button| {
+        let track = !object.mod_info().tracked;
+        button.set_sensitive(false);
+        let receiver = runtime::spawn(clone!(@strong client => async move {
+            if track {
+                client.track_mod(mod_id).await
+            } else {
+                client.untrack_mod(mod_id).await
+            }
+        }));
+        glib::spawn_future_local(clone!(@weak button, @weak object => async move {
+            let result = receiver.recv().await;
+            button.set_sensitive(true);
+            match result {
+                Ok(Ok(())) => {
+                    object.update(|mod_info| mod_info.tracked = track);
+                    button.set_label(label(track));
+                },
+                Ok(Err(e)) => eprintln!("Failed to change tracking for mod {}: {}", mod_id, e),
+                Err(_) => {}
+            }
+        }));
+    }));
+
+    button
+}
+
+/// Lists the rules of the mod with a form to add more, and the current
+/// violations it is part of.
+fn render_rules(container: &Box, mod_list: &ModList, object: &ModObject) {
+    remove_children(container);
+    let mod_info = object.mod_info();
+    let mods = list_mods(mod_list);
+
+    for (index, rule) in mod_info.rules.iter().enumerate() {
+        let line = Box::new(Orientation::Horizontal, 6);
+        let label = wrapped_label(&format!("{} {}", rule.kind.label(), rule.name));
+        label.set_hexpand(true);
+        let remove = gtk::Button::from_icon_name("list-remove-symbolic");
+        remove.add_css_class("flat");
+        remove.set_tooltip_text(Some("Remove rule"));
+        remove.connect_clicked(clone!(@weak container, @weak mod_list, @weak object => move |_| {
+            object.update(|mod_info| {
+                if index < mod_info.rules.len() {
+                    mod_info.rules.remove(index);
+                }
+            });
+            render_rules(&container, &mod_list, &object);
+        }));
+        line.append(&label);
+        line.append(&remove);
+        container.append(&line);
+    }
+
+    if let Ok(mod_manager) = ModManager::new(Settings::load()) {
+        for violation in mod_manager.validate_rules(&mods)
+            .iter()
+            .filter(|violation| violation.involves(mod_info.id))
+        {
+            let warning = wrapped_label(&format!("⚠ {}", violation));
+            warning.add_css_class("warning");
+            container.append(&warning);
+        }
+    }
+
+    let others: Vec<ModInfo> = mods.into_iter()
+        .filter(|other| other.id != mod_info.id)
+        .collect();
+    if others.is_empty() {
+        return;
+    }
+
+    let form = Box::new(Orientation::Horizontal, 6);
+    let kinds = gtk::DropDown::from_strings(&RuleKind::ALL.map(|kind| kind.label()));
+    let names: Vec<&str> = others.iter().map(|other| other.name.as_str()).collect();
+    let targets = gtk::DropDown::from_strings(&names);
+    targets.set_hexpand(true);
+    let add = gtk::Button::with_label("Add Rule");
+    form.append(&kinds);
+    form.append(&targets);
+    form.append(&add);
+    container.append(&form);
+
+    add.connect_clicked(clone!(@weak container, @weak mod_list, @weak object, @weak kinds, @weak targets => move |_| {
+        let (Some(kind), Some(other)) = (
+            RuleKind::ALL.get(kinds.selected() as usize),
+            others.get(targets.selected() as usize),
+        ) else {
+            return;
+        };
+        let rule = ModRule {
+            kind: *kind,
+            target: DependencyTarget::for_mod(other),
+            name: other.name.clone(),
+        };
+        object.update(|mod_info| {
+            if !mod_info.rules.contains(&rule) {
+                mod_info.rules.push(rule);
+            }
+        });
+        render_rules(&container, &mod_list, &object);
+    }));
+}
+
+/// Lists what the mod needs with a form to add installed mods to it. The
+/// implicit ones, like UE4SS for Lua mods, can't be removed.
+fn render_dependencies(container: &Box, mod_list: &ModList, object: &ModObject) {
+    remove_children(container);
+    let mod_info = object.mod_info();
+    let mods = list_mods(mod_list);
+
+    for (index, dependency) in dependencies::requirements(&mod_info).iter().enumerate() {
+        let state = match mods.iter().find(|m| dependency.matches(m)) {
+            Some(found) if found.enabled => "",
+            Some(_) => " (disabled)",
+            None => " (not installed)",
+        };
+        let line = Box::new(Orientation::Horizontal, 6);
+        let label = wrapped_label(&format!("{}{}", dependency.name, state));
+        label.set_hexpand(true);
+        line.append(&label);
+        if index < mod_info.dependencies.len() {
+            let remove = gtk::Button::from_icon_name("list-remove-symbolic");
+            remove.add_css_class("flat");
+            remove.set_tooltip_text(Some("Remove dependency"));
+            remove.connect_clicked(clone!(@weak container, @weak mod_list, @weak object => move |_| {
+                object.update(|mod_info| {
+                    if index < mod_info.dependencies.len() {
+                        mod_info.dependencies.remove(index);
+                    }
+                });
+                render_dependencies(&container, &mod_list, &object);
+            }));
+            line.append(&remove);
+        }
+        container.append(&line);
+    }
+
+    let others: Vec<ModInfo> = mods.into_iter()
+        .filter(|other| other.id != mod_info.id && !mod_info.dependencies.iter().any(|d| d.matches(other)))
+        .collect();
+    if others.is_empty() {
+        return;
+    }
+
+    let form = Box::new(Orientation::Horizontal, 6);
+    let names: Vec<&str> = others.iter().map(|other| other.name.as_str()).collect();
+    let targets = gtk::DropDown::from_strings(&names);
+    targets.set_hexpand(true);
+    let add = gtk::Button::with_label("Add Dependency");
+    form.append(&targets);
+    form.append(&add);
+    container.append(&form);
+
+    add.connect_clicked(clone!(@weak container, @weak mod_list, @weak object, @weak targets => move |_| {
+        let Some(other) = others.get(targets.selected() as usize) else {
+            return;
+        };
+        let dependency = Dependency {
+            target: DependencyTarget::for_mod(other),
+            name: other.name.clone(),
+        };
+        object.update(|mod_info| {
+            if !mod_info.dependencies.contains(&dependency) {
+                mod_info.dependencies.push(dependency);
+            }
+        });
+        render_dependencies(&container, &mod_list, &object);
+    }));
+}
+
+fn render_description(container: &Box, gallery: &FlowBox, description: &str, client: Option<&NexusClient>) {
+    // Inline images are shown in the gallery instead
+    render_markdown(&bbcode::to_markdown(description), container, |_| None);
+
+    if let Some(client) = client {
+        for url in bbcode::image_urls(description) {
+            add_picture(gallery, client, url);
+        }
+    }
+}
+
+fn add_picture(gallery: &FlowBox, client: &NexusClient, url: String) {
+    let picture = Picture::new();
+    picture.set_can_shrink(true);
+    picture.set_size_request(140, 80);
+    gallery.insert(&picture, -1);
+
+    let receiver = runtime::spawn(clone!(@strong client => async move {
+        client.fetch_image(&url).await
+    }));
+    glib::spawn_future_local(clone!(@weak picture => async move {
+        if let Ok(Ok(bytes)) = receiver.recv().await {
+            if let Ok(texture) = gtk::gdk::Texture::from_bytes(&glib::Bytes::from_owned(bytes)) {
+                picture.set_paintable(Some(&texture));
+            }
+        }
+    }));
+}
+
+fn remove_children(container: &Box) {
+    while let Some(child) = container.first_child() {
+        container.remove(&child);
+    }
+}
+
+fn wrapped_label(text: &str) -> Label {
+    let label = Label::new(Some(text));
+    label.set_xalign(0.0);
+    label.set_wrap(true);
+    label.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+    label
+}
+
+fn dim_label(text: &str) -> Label {
+    let label = wrapped_label(text);
+    label.add_css_class("dim-label");
+    label
+}
@@ -27,11 +27,73 @@ pub fn show_docs_window(parent: &impl IsA<Window>) {
     let content = fs::read_to_string("docs/homepage.md")
         .unwrap_or_else(|_| String::from("# Documentation not found\n\nPlease ensure the docs folder exists."));
     
-    let parser = pulldown_cmark::Parser::new(&content);
+    render_markdown(&content, &content_box, |path| {
+        let picture = Picture::for_filename(path);
+        picture.set_can_shrink(true);
+        Some(picture)
+    });
+
+    scrolled.set_child(Some(&content_box));
+    window.set_child(Some(&scrolled));
+
+    let css_provider = gtk::CssProvider::new();
+    let css = "
+        .title-1 { 
+            font-size: 24px; 
+            font-weight: bold; 
+            margin-bottom: 16px;
+            color: @theme_fg_color;
+        }
+        .title-2 { 
+            font-size: 20px; 
+            font-weight: bold; 
+            margin-bottom: 12px;
+            color: @theme_fg_color;
+        }
+        .title-3 { 
+            font-size: 16px; 
+            font-weight: bold; 
+            margin-bottom: 8px;
+            color: @theme_fg_color;
+        }
+        label { 
+            margin-bottom: 8px;
+            color: @theme_fg_color;
+        }
+        
+        /* Handle cursor through CSS */
+        window, * {
+            cursor: default;
+            -gtk-icon-theme: 'Adwaita';
+            cursor-theme-size: 32;
+        }
+        
+        scrolledwindow {
+            min-width: 2px;
+            min-height: 2px;
+        }
+    ";
+    css_provider.load_from_data(css);
+
+    window.style_context().add_provider(
+        &css_provider,
+        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION + 1,
+    );
+
+    window.present();
+}
+
+/// Renders markdown into `content_box` as a sequence of labels. Inline
+/// formatting and links become Pango markup; images are created through
+/// `load_image`, which may return `None` to leave an image out.
+pub fn render_markdown(content: &str, content_box: &Box, load_image: impl Fn(&str) -> Option<Picture>) {
+    let parser = pulldown_cmark::Parser::new_ext(content, pulldown_cmark::Options::ENABLE_STRIKETHROUGH);
     let mut current_list: Option<Box> = None;
+    let mut list_depth = 0;
     let mut list_counter = 0;
     let mut current_text = String::new();
     let mut in_heading = false;
+    let mut in_image = false;
 
     for event in parser {
         match event {
@@ -55,25 +117,39 @@ pub fn show_docs_window(parent: &impl IsA<Window>) {
                 in_heading = false;
                 if let Some(last_widget) = content_box.last_child() {
                     if let Some(label) = last_widget.downcast_ref::<Label>() {
-                        label.set_text(&current_text);
+                        label.set_markup(&current_text);
                     }
                 }
                 current_text.clear();
             }
+            pulldown_cmark::Event::Start(pulldown_cmark::Tag::Paragraph) if current_list.is_none() => {
+                current_text.clear();
+            }
+            pulldown_cmark::Event::End(pulldown_cmark::Tag::Paragraph) => {
+                if current_list.is_some() {
+                    current_text.push('\n');
+                } else if !current_text.trim().is_empty() {
+                    content_box.append(&text_label(current_text.trim_end()));
+                    current_text.clear();
+                }
+            }
             pulldown_cmark::Event::Start(pulldown_cmark::Tag::List(first_item_number)) => {
-                let list_box = Box::new(Orientation::Vertical, 6);
-                list_box.set_margin_start(24);
-                list_box.set_margin_top(8);
-                if let Some(n) = first_item_number {
-                    list_counter = n;
+                list_depth += 1;
+                // Nested lists are flattened into the outer one
+                if let Some(list_box) = &current_list {
+                    flush_list_item(list_box, &current_text);
                 } else {
-                    list_counter = 0;
+                    let list_box = Box::new(Orientation::Vertical, 6);
+                    list_box.set_margin_start(24);
+                    list_box.set_margin_top(8);
+                    list_counter = first_item_number.unwrap_or(0);
+                    current_list = Some(list_box);
                 }
-                current_list = Some(list_box);
                 current_text.clear();
             }
             pulldown_cmark::Event::Start(pulldown_cmark::Tag::Item) => {
                 if let Some(list_box) = &current_list {
+                    flush_list_item(list_box, &current_text);
                     let item_box = Box::new(Orientation::Horizontal, 6);
                     let bullet = if list_counter > 0 {
                         let bullet = format!("{}.", list_counter);
@@ -85,12 +161,10 @@ pub fn show_docs_window(parent: &impl IsA<Window>) {
                     
                     let bullet_label = Label::new(Some(&bullet));
                     bullet_label.set_xalign(0.0);
+                    bullet_label.set_valign(gtk::Align::Start);
                     item_box.append(&bullet_label);
                     
-                    let text_label = Label::new(None);
-                    text_label.set_wrap(true);
-                    text_label.set_wrap_mode(gtk::pango::WrapMode::Word);
-                    text_label.set_xalign(0.0);
+                    let text_label = text_label("");
                     text_label.set_hexpand(true);
                     item_box.append(&text_label);
                     
@@ -98,97 +172,77 @@ pub fn show_docs_window(parent: &impl IsA<Window>) {
                     current_text.clear();
                 }
             }
-            pulldown_cmark::Event::Text(text) => {
-                current_text.push_str(&text);
-                if in_heading {
-                    if let Some(last_widget) = content_box.last_child() {
-                        if let Some(label) = last_widget.downcast_ref::<Label>() {
-                            label.set_text(&current_text);
-                        }
-                    }
-                } else if let Some(list_box) = &current_list {
-                    if let Some(item_box) = list_box.last_child() {
-                        if let Some(text_label) = item_box.last_child() {
-                            if let Some(label) = text_label.downcast_ref::<Label>() {
-                                label.set_text(&current_text);
-                            }
-                        }
-                    }
-                } else {
-                    let label = Label::new(Some(&text));
-                    label.set_wrap(true);
-                    label.set_wrap_mode(gtk::pango::WrapMode::Word);
-                    label.set_xalign(0.0);
-                    content_box.append(&label);
-                }
-            }
             pulldown_cmark::Event::End(pulldown_cmark::Tag::Item) => {
+                if let Some(list_box) = &current_list {
+                    flush_list_item(list_box, &current_text);
+                }
                 current_text.clear();
             }
             pulldown_cmark::Event::End(pulldown_cmark::Tag::List(_)) => {
-                if let Some(list_box) = current_list.take() {
-                    content_box.append(&list_box);
+                list_depth -= 1;
+                if list_depth == 0 {
+                    if let Some(list_box) = current_list.take() {
+                        content_box.append(&list_box);
+                    }
+                    list_counter = 0;
                 }
-                list_counter = 0;
                 current_text.clear();
             }
+            pulldown_cmark::Event::Start(pulldown_cmark::Tag::Strong) => current_text.push_str("<b>"),
+            pulldown_cmark::Event::End(pulldown_cmark::Tag::Strong) => current_text.push_str("</b>"),
+            pulldown_cmark::Event::Start(pulldown_cmark::Tag::Emphasis) => current_text.push_str("<i>"),
+            pulldown_cmark::Event::End(pulldown_cmark::Tag::Emphasis) => current_text.push_str("</i>"),
+            pulldown_cmark::Event::Start(pulldown_cmark::Tag::Strikethrough) => current_text.push_str("<s>"),
+            pulldown_cmark::Event::End(pulldown_cmark::Tag::Strikethrough) => current_text.push_str("</s>"),
+            pulldown_cmark::Event::Start(pulldown_cmark::Tag::Link(_, url, _)) => {
+                current_text.push_str(&format!("<a href=\"{}\">", glib::markup_escape_text(&url)));
+            }
+            pulldown_cmark::Event::End(pulldown_cmark::Tag::Link(_, _, _)) => current_text.push_str("</a>"),
+            pulldown_cmark::Event::Text(text) if !in_image => {
+                current_text.push_str(&glib::markup_escape_text(&text));
+            }
+            pulldown_cmark::Event::Code(code) => {
+                current_text.push_str(&format!("<tt>{}</tt>", glib::markup_escape_text(&code)));
+            }
+            pulldown_cmark::Event::SoftBreak => current_text.push(' '),
+            pulldown_cmark::Event::HardBreak => current_text.push('\n'),
+            pulldown_cmark::Event::Rule => {
+                content_box.append(&gtk::Separator::new(Orientation::Horizontal));
+            }
+            pulldown_cmark::Event::End(pulldown_cmark::Tag::Image(_, _, _)) => in_image = false,
             pulldown_cmark::Event::Start(pulldown_cmark::Tag::Image(_, path, _)) => {
-                let picture = Picture::for_filename(path.as_ref());
-                picture.set_can_shrink(true);
-                picture.set_margin_top(12);
-                picture.set_margin_bottom(12);
-                content_box.append(&picture);
+                in_image = true;
+                if let Some(picture) = load_image(path.as_ref()) {
+                    picture.set_margin_top(12);
+                    picture.set_margin_bottom(12);
+                    content_box.append(&picture);
+                }
             }
             _ => {}
         }
     }
 
-    scrolled.set_child(Some(&content_box));
-    window.set_child(Some(&scrolled));
-
-    let css_provider = gtk::CssProvider::new();
-    let css = "
-        .title-1 { 
-            font-size: 24px; 
-            font-weight: bold; 
-            margin-bottom: 16px;
-            color: @theme_fg_color;
-        }
-        .title-2 { 
-            font-size: 20px; 
-            font-weight: bold; 
-            margin-bottom: 12px;
-            color: @theme_fg_color;
-        }
-        .title-3 { 
-            font-size: 16px; 
-            font-weight: bold; 
-            margin-bottom: 8px;
-            color: @theme_fg_color;
-        }
-        label { 
-            margin-bottom: 8px;
-            color: @theme_fg_color;
-        }
-        
-        /* Handle cursor through CSS */
-        window, * {
-            cursor: default;
-            -gtk-icon-theme: 'Adwaita';
-            cursor-theme-size: 32;
-        }
-        
-        scrolledwindow {
-            min-width: 2px;
-            min-height: 2px;
-        }
-    ";
-    css_provider.load_from_data(css);
+    if !current_text.trim().is_empty() && !in_heading {
+        content_box.append(&text_label(current_text.trim_end()));
+    }
+}
 
-    window.style_context().add_provider(
-        &css_provider,
-        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION + 1,
-    );
+fn text_label(markup: &str) -> Label {
+    let label = Label::new(None);
+    label.set_markup(markup);
+    label.set_wrap(true);
+    label.set_wrap_mode(gtk::pango::WrapMode::Word);
+    label.set_xalign(0.0);
+    label
+}
 
-    window.present();
-} 
\ No newline at end of file
+fn flush_list_item(list_box: &Box, markup: &str) {
+    if markup.trim().is_empty() {
+        return;
+    }
+    if let Some(item_box) = list_box.last_child() {
+        if let Some(label) = item_box.last_child().and_downcast::<Label>() {
+            label.set_markup(markup.trim_end());
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use gtk::prelude::*;
+use gtk::{MessageDialog, ResponseType, Window};
+use gtk::glib::{self, clone};
+use crate::mod_info::unix_now;
+use crate::mod_list::{mod_objects, ModList};
+use crate::mod_manager::ModManager;
+use crate::mod_object::ModObject;
+use crate::nexus_api::EndorsementStatus;
+use crate::settings::Settings;
+use crate::runtime;
+
+/// Pulls endorsement and tracking state from the user's Nexus account, and
+/// the latest version of each Nexus mod, into the list, then suggests endorsing a mod that has been enabled for a while.
+pub fn sync_nexus_state(window: &impl IsA<Window>, mod_list: &ModList) {
+    let Some(client) = ModManager::new(Settings::load())
+        .ok()
+        .and_then(|mod_manager| mod_manager.nexus_client().cloned())
+    else {
+        return;
+    };
+
+    let mut nexus_ids: Vec<i32> = mod_objects(mod_list).iter()
+        .filter_map(|object| object.mod_info().nexus_mod_id)
+        .collect();
+    nexus_ids.sort_unstable();
+    nexus_ids.dedup();
+
+    let receiver = runtime::spawn(async move {
+        let endorsements = client.get_endorsements().await?;
+        let tracked = client.get_tracked_mods().await?;
+        let versions = client.latest_versions(&nexus_ids).await?;
+        anyhow::Ok((endorsements, tracked, versions))
+    });
+
+    let window = window.clone().upcast::<Window>();
+    glib::spawn_future_local(clone!(@weak window, @weak mod_list => async move {
+        let (endorsements, tracked, versions) = match receiver.recv().await {
+            Ok(Ok(state)) => state,
+            Ok(Err(e)) => {
+                eprintln!("Failed to sync Nexus state: {}", e);
+                return;
+            },
+            Err(_) => return,
+        };
+
+        for object in mod_objects(&mod_list) {
+            object.update(|mod_info| {
+                mod_info.apply_nexus_state(&endorsements, &tracked);
+                mod_info.apply_latest_version(&versions);
+            });
+        }
+
+        prompt_endorsement(&window, &mod_list);
+    }));
+}
+
+/// Asks about one mod per launch so the user isn't flooded with dialogs.
+fn prompt_endorsement(window: &Window, mod_list: &ModList) {
+    let now = unix_now();
+    if let Some(object) = mod_objects(mod_list).into_iter()
+        .find(|object| object.mod_info().should_prompt_endorsement(now))
+    {
+        show_endorse_dialog(window, &object);
+    }
+}
+
+fn show_endorse_dialog(window: &Window, object: &ModObject) {
+    let dialog = MessageDialog::new(
+        Some(window),
+        gtk::DialogFlags::MODAL,
+        gtk::MessageType::Question,
+        gtk::ButtonsType::None,
+        format!(
+            "You have been using {} for over a week. Would you like to endorse it on Nexus Mods?",
+            object.mod_info().name
+        ),
+    );
+    dialog.add_button("Don't Ask Again", ResponseType::Reject);
+    dialog.add_button("Not Now", ResponseType::Cancel);
+    dialog.add_button("Endorse", ResponseType::Accept);
+    dialog.set_default_response(ResponseType::Accept);
+
+    dialog.connect_response(clone!(@weak object => move |dialog, response| {
+        match response {
+            ResponseType::Accept => {
+                object.update(|mod_info| mod_info.endorse_prompted = true);
+                endorse(&object);
+            },
+            ResponseType::Reject => {
+                object.update(|mod_info| mod_info.endorse_prompted = true);
+            },
+            _ => {}
+        }
+        dialog.close();
+    }));
+
+    dialog.present();
+}
+
+fn endorse(object: &ModObject) {
+    let mod_info = object.mod_info();
+    let (Some(mod_id), Some(client)) = (
+        mod_info.nexus_mod_id,
+        ModManager::new(Settings::load())
+            .ok()
+            .and_then(|mod_manager| mod_manager.nexus_client().cloned()),
+    ) else {
+        return;
+    };
+
+    let version = mod_info.version.clone();
+    let receiver = runtime::spawn(async move {
+        client.endorse_mod(mod_id, &version).await
+    });
+    glib::spawn_future_local(clone!(@weak object => async move {
+        match receiver.recv().await {
+            Ok(Ok(())) => {
+                object.update(|mod_info| mod_info.endorsement = Some(EndorsementStatus::Endorsed));
+            },
+            Ok(Err(e)) => eprintln!("Failed to endorse mod: {}", e),
+            Err(_) => {}
+        }
+    }));
+}
@@ -0,0 +1,309 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Name of the manifest kept next to the stored files of a mod.
+const MANIFEST_FILE: &str = "deployment.json";
+/// Subfolder of a mod's store holding the files as they came in the archive.
+const FILES_DIR: &str = "files";
+/// Subfolder of a mod's store holding the originals it replaced.
+const BACKUP_DIR: &str = "backup";
+
+/// Config files the game reads from the user config folder.
+const USER_CONFIG_FILES: &[&str] = &[
+    "engine.ini",
+    "game.ini",
+    "gameusersettings.ini",
+    "input.ini",
+    "scalability.ini",
+];
+
+/// Where a deployed file goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployRoot {
+    /// The game install folder.
+    GameRoot,
+    /// `Stalker2/Binaries/Win64`, where DLL and ASI loaders go.
+    Win64,
+    /// `Saved/Config/Windows` in the user's local app data.
+    UserConfig,
+}
+
+impl DeployRoot {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeployRoot::GameRoot => "Game folder",
+            DeployRoot::Win64 => "Binaries/Win64",
+            DeployRoot::UserConfig => "User config",
+        }
+    }
+}
+
+/// Maps a file from the mod archive to its destination.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMapping {
+    /// Path inside the archive.
+    pub source: PathBuf,
+    pub root: DeployRoot,
+    /// Path relative to `root`.
+    pub target: PathBuf,
+}
+
+/// A file currently deployed by a mod, with the original it replaced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployedFile {
+    pub root: DeployRoot,
+    pub target: PathBuf,
+    /// Backup of the file that was there before, relative to the store.
+    pub backup: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub mappings: Vec<FileMapping>,
+    /// Empty while the mod is disabled.
+    #[serde(default)]
+    pub deployed: Vec<DeployedFile>,
+}
+
+/// Resolved destination folders. The user config folder may be unknown,
+/// e.g. before the game has been started once.
+pub struct DeployRoots {
+    pub game_root: PathBuf,
+    pub win64: PathBuf,
+    pub user_config: Option<PathBuf>,
+}
+
+impl DeployRoots {
+    pub fn resolve(&self, root: DeployRoot) -> Result<&Path> {
+        match root {
+            DeployRoot::GameRoot => Ok(&self.game_root),
+            DeployRoot::Win64 => Ok(&self.win64),
+            DeployRoot::UserConfig => self.user_config.as_deref()
+                .ok_or_else(|| anyhow!("The game's user config folder was not found")),
+        }
+    }
+}
+
+/// Picks a destination for each file of an archive: anything under a
+/// `Stalker2` folder mirrors the game install, config files go to the user
+/// config and binaries to `Binaries/Win64`, along with the settings files
+/// next to them. Readmes, paks and other loose text files are left out.
+/// `names` are the file entries of the archive.
+pub fn guess_mappings(names: &[PathBuf]) -> Vec<FileMapping> {
+    let binary_dirs: Vec<&Path> = names.iter()
+        .filter(|name| name.extension().is_some_and(|e| e.eq_ignore_ascii_case("dll") || e.eq_ignore_ascii_case("asi")))
+        .filter_map(|name| name.parent())
+        .collect();
+    let binary_root = binary_dirs.iter().copied().min_by_key(|dir| dir.components().count());
+
+    names.iter()
+        .filter_map(|name| {
+            let file_name = name.file_name()?.to_string_lossy().to_lowercase();
+            let extension = name.extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if file_name.starts_with("readme") || matches!(extension.as_str(), "pak" | "md" | "url") {
+                return None;
+            }
+
+            let components: Vec<&std::ffi::OsStr> = name.iter().collect();
+            if let Some(start) = components.iter().position(|c| c.eq_ignore_ascii_case("Stalker2")) {
+                return Some(FileMapping {
+                    source: name.clone(),
+                    root: DeployRoot::GameRoot,
+                    target: components[start..].iter().collect(),
+                });
+            }
+
+            if USER_CONFIG_FILES.contains(&file_name.as_str()) {
+                return Some(FileMapping {
+                    source: name.clone(),
+                    root: DeployRoot::UserConfig,
+                    target: PathBuf::from(name.file_name()?),
+                });
+            }
+            if matches!(extension.as_str(), "dll" | "asi")
+                || matches!(extension.as_str(), "ini" | "toml" | "json" | "txt")
+                    && name.parent().is_some_and(|parent| binary_dirs.contains(&parent))
+            {
+                return Some(FileMapping {
+                    source: name.clone(),
+                    root: DeployRoot::Win64,
+                    target: win64_target(name, binary_root),
+                });
+            }
+            None
+        })
+        .collect()
+}
+
+/// Folders below `Binaries/Win64` that ASI loaders and UE4SS read from.
+const WIN64_FOLDERS: &[&str] = &["plugins", "scripts", "mods", "ue4ss"];
+
+/// Where a file bound for `Binaries/Win64` goes below it: the path after
+/// a `Win64` folder, from a loader folder such as `plugins` on, or else
+/// the path below `binary_root`, the shallowest folder holding a binary.
+fn win64_target(name: &Path, binary_root: Option<&Path>) -> PathBuf {
+    let components: Vec<&std::ffi::OsStr> = name.iter().collect();
+    let folders = &components[..components.len() - 1];
+    if let Some(win64) = folders.iter().rposition(|c| c.eq_ignore_ascii_case("Win64")) {
+        return components[win64 + 1..].iter().collect();
+    }
+    if let Some(start) = folders.iter().position(|c| {
+        WIN64_FOLDERS.iter().any(|folder| c.eq_ignore_ascii_case(folder))
+    }) {
+        return components[start..].iter().collect();
+    }
+    binary_root
+        .and_then(|root| name.strip_prefix(root).ok())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(name.file_name().unwrap_or_default()))
+}
+
+/// Extracts the mapped files of an archive into a mod's store and writes
+/// its manifest. Nothing is deployed yet.
+pub fn store_archive(archive_path: &Path, store: &Path, mappings: Vec<FileMapping>) -> Result<Manifest> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+    for mapping in &mappings {
+        let mut entry = archive.by_name(&mapping.source.to_string_lossy())?;
+        let out_path = store.join(FILES_DIR).join(safe_relative(&mapping.source)?);
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut fs::File::create(&out_path)?)?;
+    }
+
+    let manifest = Manifest { mappings, deployed: Vec::new() };
+    save_manifest(store, &manifest)?;
+    Ok(manifest)
+}
+
+pub fn load_manifest(store: &Path) -> Result<Manifest> {
+    let json = fs::read_to_string(store.join(MANIFEST_FILE))?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn save_manifest(store: &Path, manifest: &Manifest) -> Result<()> {
+    fs::write(store.join(MANIFEST_FILE), serde_json::to_string_pretty(manifest)?)?;
+    Ok(())
+}
+
+pub fn is_deployed(store: &Path) -> bool {
+    load_manifest(store).is_ok_and(|manifest| !manifest.deployed.is_empty())
+}
+
+/// Copies a mod's files to their destinations, backing up any file they
+/// replace. Files deployed before an error are recorded so they can still
+/// be removed.
+///
+/// A file another deployed mod put there is refused rather than backed up:
+/// disabling the two in the wrong order would restore the wrong file. The
+/// stores of all mods share a parent folder, which is where the others are
+/// looked up.
+pub fn deploy(store: &Path, roots: &DeployRoots) -> Result<()> {
+    let mut manifest = load_manifest(store)?;
+    if !manifest.deployed.is_empty() {
+        return Ok(());
+    }
+    if let Some((other, target)) = deployed_by_others(store, &manifest) {
+        bail!("{} already deploys {}; disable it first", other, target.display());
+    }
+
+    let mut result = Ok(());
+    for mapping in manifest.mappings.clone() {
+        match deploy_file(store, &mapping, roots) {
+            Ok(deployed) => manifest.deployed.push(deployed),
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+
+    save_manifest(store, &manifest)?;
+    result
+}
+
+/// The first file of `manifest` that another mod has deployed, with that
+/// mod's store name.
+fn deployed_by_others(store: &Path, manifest: &Manifest) -> Option<(String, PathBuf)> {
+    let parent = store.parent()?;
+    let same = |a: &Path, b: &Path| a.to_string_lossy().eq_ignore_ascii_case(&b.to_string_lossy());
+    fs::read_dir(parent).ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|other| other != store)
+        .find_map(|other| {
+            let deployed = load_manifest(&other).ok()?.deployed;
+            let target = manifest.mappings.iter().find(|mapping| {
+                deployed.iter().any(|file| file.root == mapping.root && same(&file.target, &mapping.target))
+            })?;
+            let name = other.file_name()?.to_string_lossy().to_string();
+            Some((name, target.target.clone()))
+        })
+}
+
+fn deploy_file(store: &Path, mapping: &FileMapping, roots: &DeployRoots) -> Result<DeployedFile> {
+    let target = safe_relative(&mapping.target)?;
+    let destination = roots.resolve(mapping.root)?.join(&target);
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let backup = if destination.exists() {
+        let backup = Path::new(BACKUP_DIR).join(root_dir_name(mapping.root)).join(&target);
+        let backup_path = store.join(&backup);
+        if let Some(parent) = backup_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&destination, &backup_path)?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    fs::copy(store.join(FILES_DIR).join(safe_relative(&mapping.source)?), &destination)?;
+    Ok(DeployedFile { root: mapping.root, target, backup })
+}
+
+/// Removes every file a mod deployed and puts the originals back.
+pub fn undeploy(store: &Path, roots: &DeployRoots) -> Result<()> {
+    let mut manifest = load_manifest(store)?;
+
+    while let Some(deployed) = manifest.deployed.pop() {
+        let destination = roots.resolve(deployed.root)?.join(&deployed.target);
+        let restored = match &deployed.backup {
+            Some(backup) => fs::copy(store.join(backup), &destination).map(|_| ()),
+            None if destination.exists() => fs::remove_file(&destination),
+            None => Ok(()),
+        };
+        if let Err(e) = restored {
+            manifest.deployed.push(deployed);
+            save_manifest(store, &manifest)?;
+            return Err(e.into());
+        }
+    }
+
+    save_manifest(store, &manifest)?;
+    let _ = fs::remove_dir_all(store.join(BACKUP_DIR));
+    Ok(())
+}
+
+fn root_dir_name(root: DeployRoot) -> &'static str {
+    match root {
+        DeployRoot::GameRoot => "game",
+        DeployRoot::Win64 => "win64",
+        DeployRoot::UserConfig => "user_config",
+    }
+}
+
+/// Rejects absolute paths and `..` so a manifest can't point outside its roots.
+fn safe_relative(path: &Path) -> Result<PathBuf> {
+    if path.components().all(|c| matches!(c, Component::Normal(_))) {
+        Ok(path.to_path_buf())
+    } else {
+        bail!("Unsafe path in mod archive: {}", path.display())
+    }
+}
@@ -0,0 +1,119 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Moves a file or folder. A rename is used when both sides are on the same
+/// filesystem; across filesystems (another drive, a separate mount) the
+/// source is copied instead, see `copy_then_remove`.
+pub fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => copy_then_remove(from, to),
+        result => result,
+    }
+}
+
+/// Moves by copying to a temporary name next to `to`, syncing the copy to
+/// disk and comparing it with the source, then renaming it into place and
+/// only then removing the source. An interruption leaves the source intact,
+/// at worst alongside a complete copy.
+pub fn copy_then_remove(from: &Path, to: &Path) -> io::Result<()> {
+    let partial = partial_path(to);
+    if partial.exists() {
+        remove_path(&partial)?;
+    }
+
+    if let Err(e) = copy_recursive(from, &partial).and_then(|()| verify(from, &partial)) {
+        let _ = remove_path(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, to)?;
+    sync_parent(to);
+    remove_path(from)
+}
+
+/// Removes a file, a symlink or a folder with everything in it.
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+fn partial_path(to: &Path) -> PathBuf {
+    let mut name = to.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    to.with_file_name(name)
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> io::Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        sync_dir(to);
+        return Ok(());
+    }
+
+    let mut source = fs::File::open(from)?;
+    let mut target = fs::File::create(to)?;
+    io::copy(&mut source, &mut target)?;
+    target.sync_all()?;
+    Ok(())
+}
+
+/// Checks that `copy` holds the same files with the same bytes as `original`.
+fn verify(original: &Path, copy: &Path) -> io::Result<()> {
+    if original.is_dir() {
+        for entry in fs::read_dir(original)? {
+            let entry = entry?;
+            verify(&entry.path(), &copy.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+
+    if same_contents(original, copy)? {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("The copy of {} doesn't match the original", original.display()),
+        ))
+    }
+}
+
+/// Whether two files hold the same bytes.
+pub fn same_contents(a: &Path, b: &Path) -> io::Result<bool> {
+    if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+
+    let mut a_file = io::BufReader::new(fs::File::open(a)?);
+    let mut b_file = io::BufReader::new(fs::File::open(b)?);
+    let mut a_buf = vec![0; 64 * 1024];
+    let mut b_buf = vec![0; 64 * 1024];
+    loop {
+        let read = a_file.read(&mut a_buf)?;
+        if read == 0 {
+            return Ok(true);
+        }
+        b_file.read_exact(&mut b_buf[..read])?;
+        if a_buf[..read] != b_buf[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+fn sync_parent(path: &Path) {
+    if let Some(parent) = path.parent() {
+        sync_dir(parent);
+    }
+}
+
+/// Makes renames and new entries in a folder durable. Not possible on every
+/// platform, so failures are ignored.
+fn sync_dir(dir: &Path) {
+    let _ = fs::File::open(dir).and_then(|dir| dir.sync_all());
+}
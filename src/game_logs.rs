@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use anyhow::Result;
+use crate::user_data::{crashes_dir, logs_dir};
+
+/// Pak names shorter than this match too many unrelated lines.
+const MIN_PAK_NAME_LEN: usize = 4;
+/// Log the game writes for the current run; earlier runs are kept as
+/// `Stalker2-backup-<date>.log`.
+const GAME_LOG: &str = "Stalker2.log";
+
+/// A log file that can be shown, from the logs folder or a crash report.
+#[derive(Debug, Clone)]
+pub struct LogSource {
+    pub title: String,
+    pub path: PathBuf,
+    pub modified: SystemTime,
+}
+
+/// The game logs followed by the logs of crash reports, newest first within each.
+pub fn find_logs(user_data: &Path) -> Vec<LogSource> {
+    let mut logs: Vec<LogSource> = files_with_extension(&logs_dir(user_data), "log")
+        .into_iter()
+        .map(|(path, modified)| LogSource {
+            title: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            path,
+            modified,
+        })
+        .collect();
+    // The current log first, whatever its time stamp
+    logs.sort_by_key(|log| (log.title != GAME_LOG, std::cmp::Reverse(log.modified)));
+
+    let mut crashes: Vec<LogSource> = fs::read_dir(crashes_dir(user_data))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let (path, modified) = files_with_extension(&entry.path(), "log").into_iter().next()?;
+            Some(LogSource {
+                title: format!("Crash {}", entry.file_name().to_string_lossy()),
+                path,
+                modified,
+            })
+        })
+        .collect();
+    crashes.sort_by_key(|log| std::cmp::Reverse(log.modified));
+
+    logs.extend(crashes);
+    logs
+}
+
+fn files_with_extension(dir: &Path, extension: &str) -> Vec<(PathBuf, SystemTime)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries.filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case(extension)))
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|meta| meta.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Fatal,
+    Error,
+    Warning,
+    Display,
+    Log,
+    Verbose,
+}
+
+impl Verbosity {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "Fatal" => Some(Verbosity::Fatal),
+            "Error" => Some(Verbosity::Error),
+            "Warning" => Some(Verbosity::Warning),
+            "Display" => Some(Verbosity::Display),
+            "Log" => Some(Verbosity::Log),
+            "Verbose" | "VeryVerbose" => Some(Verbosity::Verbose),
+            _ => None,
+        }
+    }
+
+    pub fn is_problem(&self) -> bool {
+        *self <= Verbosity::Warning
+    }
+}
+
+/// One line of an Unreal log:
+/// `[2024.11.20-14.03.12:345][  0]LogPakFile: Warning: message`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub timestamp: Option<String>,
+    pub category: Option<String>,
+    pub verbosity: Verbosity,
+    pub message: String,
+    pub raw: String,
+}
+
+impl LogLine {
+    pub fn parse(raw: &str) -> Self {
+        let mut rest = raw;
+        let mut timestamp = None;
+        // Time stamp and frame counter
+        if let Some(stripped) = rest.strip_prefix('[') {
+            if let Some((time, after)) = stripped.split_once(']') {
+                timestamp = Some(time.to_string());
+                rest = after;
+                if let Some(after_frame) = rest.strip_prefix('[').and_then(|r| r.split_once(']')).map(|(_, r)| r) {
+                    rest = after_frame;
+                }
+            }
+        }
+
+        let mut category = None;
+        let mut verbosity = Verbosity::Log;
+        if let Some((name, after)) = rest.split_once(": ") {
+            if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                category = Some(name.to_string());
+                rest = after;
+                if let Some((level, after)) = rest.split_once(": ") {
+                    if let Some(level) = Verbosity::parse(level) {
+                        verbosity = level;
+                        rest = after;
+                    }
+                }
+            }
+        }
+        // Crash reports and asserts don't always carry a verbosity
+        if category.is_none() && (raw.contains("Fatal error") || raw.contains("Assertion failed")) {
+            verbosity = Verbosity::Fatal;
+        }
+
+        Self {
+            timestamp,
+            category,
+            verbosity,
+            message: rest.to_string(),
+            raw: raw.to_string(),
+        }
+    }
+}
+
+pub fn read_log(path: &Path) -> Result<Vec<LogLine>> {
+    // Logs may contain text in the system code page
+    let bytes = fs::read(path)?;
+    Ok(String::from_utf8_lossy(&bytes).lines().map(LogLine::parse).collect())
+}
+
+/// A mention of a mod in a log line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mention {
+    /// Index of the mod in the list the index was built from.
+    pub mod_index: usize,
+    /// The pak name or asset path that was found.
+    pub matched: String,
+}
+
+/// Looks up which mods a log line refers to, by their pak file names and
+/// the assets inside their paks.
+#[derive(Debug, Default)]
+pub struct ModIndex {
+    pak_names: Vec<(String, usize)>,
+    /// `/game/...` object paths, lowercased.
+    assets: HashMap<String, usize>,
+}
+
+impl ModIndex {
+    /// `pak_name` is the pak's file stem; `assets` are paths as listed by
+    /// `pak::list_assets`.
+    pub fn add(&mut self, mod_index: usize, pak_name: &str, assets: &[String]) {
+        if pak_name.len() >= MIN_PAK_NAME_LEN {
+            self.pak_names.push((pak_name.to_lowercase(), mod_index));
+        }
+        for asset in assets {
+            if let Some(object_path) = object_path(asset) {
+                self.assets.insert(object_path, mod_index);
+            }
+        }
+    }
+
+    pub fn mentions(&self, line: &str) -> Vec<Mention> {
+        let lower = line.to_lowercase();
+        let mut mentions: Vec<Mention> = self.pak_names.iter()
+            .filter(|(name, _)| lower.contains(name.as_str()))
+            .map(|(name, mod_index)| Mention { mod_index: *mod_index, matched: name.clone() })
+            .collect();
+
+        for (start, _) in lower.match_indices("/game/") {
+            let path: String = lower[start..].chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-'))
+                .collect();
+            if let Some(&mod_index) = self.assets.get(&path) {
+                if !mentions.iter().any(|m| m.mod_index == mod_index) {
+                    mentions.push(Mention { mod_index, matched: path });
+                }
+            }
+        }
+        mentions
+    }
+}
+
+/// `stalker2/content/gamelite/x.uasset` is loaded as `/game/gamelite/x`.
+fn object_path(asset: &str) -> Option<String> {
+    let relative = asset.strip_prefix("stalker2/content/")?;
+    let without_extension = relative.rsplit_once('.').map_or(relative, |(path, _)| path);
+    Some(format!("/game/{}", without_extension))
+}
@@ -0,0 +1,109 @@
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use anyhow::Result;
+use std::time::Duration;
+
+pub const APP_NAME: &str = env!("CARGO_PKG_NAME");
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// User agent in the format Nexus asks third-party tools to use:
+/// `AppName/Version (OS OSVersion; Arch) Runtime/RuntimeVersion`.
+pub fn default_user_agent() -> String {
+    let os = match os_version() {
+        Some(version) => format!("{} {}", std::env::consts::OS, version),
+        None => std::env::consts::OS.to_string(),
+    };
+    format!(
+        "{}/{} ({}; {}) Rust/{}",
+        APP_NAME,
+        APP_VERSION,
+        os,
+        std::env::consts::ARCH,
+        env!("RUSTC_VERSION")
+    )
+}
+
+/// Kernel release, e.g. `6.8.0-45-generic`.
+#[cfg(target_os = "linux")]
+fn os_version() -> Option<String> {
+    let release = std::fs::read_to_string("/proc/sys/kernel/osrelease").ok()?;
+    Some(release.trim().to_string()).filter(|release| !release.is_empty())
+}
+
+/// Build number as `ver` prints it, e.g. `10.0.19045.3570`.
+#[cfg(windows)]
+fn os_version() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let output = std::process::Command::new("cmd")
+        .args(["/C", "ver"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    let version = output.split("Version").nth(1)?;
+    Some(version.trim_matches(|c: char| c.is_whitespace() || c == ']').to_string())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn os_version() -> Option<String> {
+    None
+}
+
+#[derive(Clone, Debug)]
+pub struct HttpConfig {
+    pub user_agent: String,
+    pub timeout: Option<Duration>,
+    pub connect_timeout: Option<Duration>,
+    pub proxy: Option<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: default_user_agent(),
+            timeout: Some(Duration::from_secs(30)),
+            connect_timeout: Some(Duration::from_secs(10)),
+            proxy: None,
+        }
+    }
+}
+
+/// Headers sent on every request, API or CDN.
+fn application_headers(config: &HttpConfig) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_str(&config.user_agent)?);
+    headers.insert("Application-Name", HeaderValue::from_static(APP_NAME));
+    headers.insert("Application-Version", HeaderValue::from_static(APP_VERSION));
+    Ok(headers)
+}
+
+fn base_builder(config: &HttpConfig, extra_headers: HeaderMap) -> Result<reqwest::ClientBuilder> {
+    let mut headers = application_headers(config)?;
+    headers.extend(extra_headers);
+
+    let mut builder = reqwest::Client::builder()
+        .default_headers(headers);
+    if let Some(timeout) = config.connect_timeout {
+        builder = builder.connect_timeout(timeout);
+    }
+    if let Some(proxy) = &config.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    Ok(builder)
+}
+
+/// Client for API calls. `extra_headers` carries authentication.
+pub fn api_client(config: &HttpConfig, extra_headers: HeaderMap) -> Result<reqwest::Client> {
+    let mut builder = base_builder(config, extra_headers)?;
+    if let Some(timeout) = config.timeout {
+        builder = builder.timeout(timeout);
+    }
+    Ok(builder.build()?)
+}
+
+/// Client for CDN downloads. It never carries API credentials, follows
+/// redirects between mirrors and has no overall timeout since archives can
+/// be large; only the connection attempt is bounded.
+pub fn download_client(config: &HttpConfig) -> Result<reqwest::Client> {
+    Ok(base_builder(config, HeaderMap::new())?.build()?)
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use anyhow::Result;
+
+/// An INI file that writes back exactly as read apart from the entries that
+/// were changed, so comments, blank lines and ordering survive an edit.
+#[derive(Debug, Clone)]
+pub struct IniFile {
+    lines: Vec<Line>,
+    newline: &'static str,
+    trailing_newline: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Line {
+    Section { name: String, raw: String },
+    Entry { key: String, value: String, raw: String },
+    Other(String),
+}
+
+impl IniFile {
+    pub fn parse(content: &str) -> Self {
+        let lines = content.lines()
+            .map(|raw| {
+                let trimmed = raw.trim();
+                if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                    Line::Section { name: trimmed[1..trimmed.len() - 1].trim().to_string(), raw: raw.to_string() }
+                } else if trimmed.starts_with(';') || trimmed.starts_with('#') {
+                    Line::Other(raw.to_string())
+                } else if let Some((key, value)) = trimmed.split_once('=') {
+                    Line::Entry { key: key.trim().to_string(), value: value.trim().to_string(), raw: raw.to_string() }
+                } else {
+                    Line::Other(raw.to_string())
+                }
+            })
+            .collect();
+
+        Self {
+            lines,
+            newline: if content.contains("\r\n") { "\r\n" } else { "\n" },
+            trailing_newline: content.is_empty() || content.ends_with('\n'),
+        }
+    }
+
+    /// Reads a file, treating a missing one as empty.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Self::parse(&content)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::parse("")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    /// The last value of `key` in `section`, which is the one the engine uses.
+    /// Section and key names are case-insensitive.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.section_entries(section)
+            .rev()
+            .find(|(_, k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, _, value)| value)
+    }
+
+    /// Sets `key` in `section`, replacing an existing entry in place or
+    /// adding it at the end of the section, creating the section if needed.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        let raw = format!("{}={}", key, value);
+        let existing = self.section_entries(section)
+            .rev()
+            .find(|(_, k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(index, _, _)| index);
+        if let Some(index) = existing {
+            self.lines[index] = Line::Entry { key: key.to_string(), value: value.to_string(), raw };
+            return;
+        }
+
+        let entry = Line::Entry { key: key.to_string(), value: value.to_string(), raw };
+        match self.section_range(section) {
+            Some((start, end)) => {
+                // After the last non-blank line so spacing between sections is kept
+                let at = (start + 1..end)
+                    .rev()
+                    .find(|&index| !matches!(&self.lines[index], Line::Other(raw) if raw.trim().is_empty()))
+                    .map_or(start + 1, |index| index + 1);
+                self.lines.insert(at, entry);
+            }
+            None => {
+                if self.lines.last().is_some_and(|line| !matches!(line, Line::Other(raw) if raw.trim().is_empty())) {
+                    self.lines.push(Line::Other(String::new()));
+                }
+                self.lines.push(Line::Section { name: section.to_string(), raw: format!("[{}]", section) });
+                self.lines.push(entry);
+            }
+        }
+    }
+
+    /// Removes every entry of `key` in `section`, returning the value that was in effect.
+    pub fn remove(&mut self, section: &str, key: &str) -> Option<String> {
+        let indices: Vec<usize> = self.section_entries(section)
+            .filter(|(_, k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(index, _, _)| index)
+            .collect();
+        let previous = indices.last().and_then(|&index| match &self.lines[index] {
+            Line::Entry { value, .. } => Some(value.clone()),
+            _ => None,
+        });
+        for index in indices.into_iter().rev() {
+            self.lines.remove(index);
+        }
+        previous
+    }
+
+    /// Line range of a section, from its header up to the next one. A
+    /// section appearing more than once uses its last occurrence.
+    fn section_range(&self, section: &str) -> Option<(usize, usize)> {
+        let start = self.lines.iter().rposition(|line| {
+            matches!(line, Line::Section { name, .. } if name.eq_ignore_ascii_case(section))
+        })?;
+        let end = self.lines[start + 1..]
+            .iter()
+            .position(|line| matches!(line, Line::Section { .. }))
+            .map_or(self.lines.len(), |offset| start + 1 + offset);
+        Some((start, end))
+    }
+
+    fn section_entries(&self, section: &str) -> std::vec::IntoIter<(usize, &str, &str)> {
+        let mut current: Option<&str> = None;
+        let mut entries = Vec::new();
+        for (index, line) in self.lines.iter().enumerate() {
+            match line {
+                Line::Section { name, .. } => current = Some(name),
+                Line::Entry { key, value, .. } if current.is_some_and(|name| name.eq_ignore_ascii_case(section)) => {
+                    entries.push((index, key.as_str(), value.as_str()));
+                }
+                _ => {}
+            }
+        }
+        entries.into_iter()
+    }
+}
+
+impl Default for IniFile {
+    fn default() -> Self {
+        Self::parse("")
+    }
+}
+
+impl fmt::Display for IniFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, line) in self.lines.iter().enumerate() {
+            if index > 0 {
+                f.write_str(self.newline)?;
+            }
+            match line {
+                Line::Section { raw, .. } | Line::Entry { raw, .. } | Line::Other(raw) => f.write_str(raw)?,
+            }
+        }
+        if self.trailing_newline && !self.lines.is_empty() {
+            f.write_str(self.newline)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use gtk::prelude::*;
+use gtk::{Window, ScrolledWindow, Box, Label, Entry, Button, DropDown, Orientation};
+use gtk::glib::{self, clone};
+use std::rc::Rc;
+use std::cell::RefCell;
+use crate::ini::IniFile;
+use crate::ini_tweaks::{ConfigFile, IniTweak, PRESETS, SYSTEM_SETTINGS};
+use crate::mod_list::{append_mod, ModList};
+use crate::mod_manager::ModManager;
+use crate::settings::Settings;
+
+const CUSTOM: &str = "Custom";
+
+/// Widgets of one `key=value` line in the editor.
+struct TweakRow {
+    container: Box,
+    file: DropDown,
+    section: Entry,
+    key: Entry,
+    value: Entry,
+}
+
+impl TweakRow {
+    fn tweak(&self) -> Option<IniTweak> {
+        let key = self.key.text().trim().to_string();
+        if key.is_empty() {
+            return None;
+        }
+        Some(IniTweak {
+            file: *ConfigFile::ALL.get(self.file.selected() as usize)?,
+            section: self.section.text().trim().to_string(),
+            key,
+            value: self.value.text().trim().to_string(),
+        })
+    }
+}
+
+/// Builds a tweak mod from `Engine.ini`/`GameUserSettings.ini` entries,
+/// showing the value each key currently has in the user's config.
+pub fn show_ini_editor(parent: &impl IsA<Window>, mod_list: &ModList) {
+    let window = Window::builder()
+        .title("INI Tweaks")
+        .transient_for(parent)
+        .default_width(720)
+        .default_height(480)
+        .build();
+
+    let main_box = Box::new(Orientation::Vertical, 12);
+    main_box.set_margin_start(12);
+    main_box.set_margin_end(12);
+    main_box.set_margin_top(12);
+    main_box.set_margin_bottom(12);
+
+    let mod_manager = ModManager::new(Settings::load()).ok();
+    let config_dir = mod_manager.as_ref().and_then(|m| m.user_config_path());
+
+    let location = Label::new(Some(&match &config_dir {
+        Some(dir) => format!("Config folder: {}", dir.display()),
+        None => "The game's config folder was not found. Start the game once, then try again.".to_string(),
+    }));
+    location.set_xalign(0.0);
+    location.set_wrap(true);
+    location.add_css_class("dim-label");
+    main_box.append(&location);
+
+    // Name and template
+    let name_box = Box::new(Orientation::Horizontal, 12);
+    let name_entry = Entry::new();
+    name_entry.set_placeholder_text(Some("Tweak name"));
+    name_entry.set_hexpand(true);
+    let mut template_names = vec![CUSTOM];
+    template_names.extend(PRESETS.iter().map(|preset| preset.name));
+    let template_dropdown = DropDown::from_strings(&template_names);
+    name_box.append(&name_entry);
+    name_box.append(&template_dropdown);
+    main_box.append(&name_box);
+
+    let rows_box = Box::new(Orientation::Vertical, 6);
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_vexpand(true);
+    scrolled.set_child(Some(&rows_box));
+    main_box.append(&scrolled);
+
+    let add_button = Button::with_label("Add Entry");
+    add_button.set_halign(gtk::Align::Start);
+    main_box.append(&add_button);
+
+    let status_label = Label::new(None);
+    status_label.set_xalign(0.0);
+    status_label.set_wrap(true);
+    main_box.append(&status_label);
+
+    let create_button = Button::with_label("Create Tweak Mod");
+    create_button.set_halign(gtk::Align::End);
+    create_button.add_css_class("suggested-action");
+    create_button.set_sensitive(config_dir.is_some());
+    main_box.append(&create_button);
+
+    window.set_child(Some(&main_box));
+
+    let rows: Rc<RefCell<Vec<TweakRow>>> = Rc::new(RefCell::new(Vec::new()));
+    let config_dir = Rc::new(config_dir);
+
+    add_button.connect_clicked(clone!(@weak rows_box, @strong rows, @strong config_dir => move |_| {
+        add_row(&rows_box, &rows, &config_dir, None);
+    }));
+
+    template_dropdown.connect_selected_notify(clone!(@weak rows_box, @weak name_entry, @strong rows, @strong config_dir => move |dropdown| {
+        let Some(preset) = (dropdown.selected() as usize).checked_sub(1).and_then(|i| PRESETS.get(i)) else {
+            return;
+        };
+        for row in rows.borrow_mut().drain(..) {
+            rows_box.remove(&row.container);
+        }
+        name_entry.set_text(preset.name);
+        for tweak in preset.to_tweaks() {
+            add_row(&rows_box, &rows, &config_dir, Some(&tweak));
+        }
+    }));
+
+    create_button.connect_clicked(clone!(@weak window, @weak mod_list, @weak name_entry, @weak status_label, @strong rows => move |_| {
+        let name = name_entry.text().trim().to_string();
+        let tweaks: Vec<IniTweak> = rows.borrow().iter().filter_map(TweakRow::tweak).collect();
+        if name.is_empty() || tweaks.is_empty() {
+            status_label.set_text("Enter a name and at least one entry.");
+            return;
+        }
+
+        let result = ModManager::new(Settings::load())
+            .and_then(|mod_manager| {
+                let mod_info = mod_manager.create_tweak_mod(&name, tweaks)?;
+                mod_manager.add_to_mod_list(mod_info.clone())?;
+                Ok(mod_info)
+            });
+        match result {
+            Ok(mod_info) => {
+                append_mod(&mod_list, &mod_info);
+                window.close();
+            },
+            Err(e) => status_label.set_text(&format!("Failed to create tweak: {}", e)),
+        }
+    }));
+
+    add_row(&rows_box, &rows, &config_dir, None);
+    window.present();
+}
+
+fn add_row(
+    rows_box: &Box,
+    rows: &Rc<RefCell<Vec<TweakRow>>>,
+    config_dir: &Rc<Option<std::path::PathBuf>>,
+    tweak: Option<&IniTweak>,
+) {
+    let container = Box::new(Orientation::Horizontal, 6);
+    let file_names: Vec<&str> = ConfigFile::ALL.iter().map(|file| file.file_name()).collect();
+    let file = DropDown::from_strings(&file_names);
+    let section = Entry::new();
+    section.set_placeholder_text(Some("Section"));
+    section.set_text(SYSTEM_SETTINGS);
+    let key = Entry::new();
+    key.set_placeholder_text(Some("Key"));
+    key.set_hexpand(true);
+    let value = Entry::new();
+    value.set_placeholder_text(Some("Value"));
+    value.set_width_chars(8);
+    let current = Label::new(None);
+    current.add_css_class("dim-label");
+    current.set_width_chars(14);
+    let remove_button = Button::from_icon_name("list-remove-symbolic");
+    remove_button.add_css_class("flat");
+
+    if let Some(tweak) = tweak {
+        let index = ConfigFile::ALL.iter().position(|f| *f == tweak.file).unwrap_or_default();
+        file.set_selected(index as u32);
+        section.set_text(&tweak.section);
+        key.set_text(&tweak.key);
+        value.set_text(&tweak.value);
+    }
+
+    for widget in [file.upcast_ref::<gtk::Widget>(), section.upcast_ref(), key.upcast_ref(), value.upcast_ref(), current.upcast_ref(), remove_button.upcast_ref()] {
+        container.append(widget);
+    }
+    rows_box.append(&container);
+
+    // Show what the key is set to now, so the user knows what they change
+    let update_current = clone!(@weak file, @weak section, @weak key, @weak current, @strong config_dir => move || {
+        let (Some(dir), Some(config_file)) = (config_dir.as_ref(), ConfigFile::ALL.get(file.selected() as usize)) else {
+            return;
+        };
+        let ini = IniFile::load(&dir.join(config_file.file_name())).unwrap_or_default();
+        current.set_text(&match ini.get(&section.text(), &key.text()) {
+            Some(value) => format!("now: {}", value),
+            None => "not set".to_string(),
+        });
+    });
+    update_current();
+    file.connect_selected_notify(clone!(@strong update_current => move |_| update_current()));
+    section.connect_changed(clone!(@strong update_current => move |_| update_current()));
+    key.connect_changed(move |_| update_current());
+
+    remove_button.connect_clicked(clone!(@weak rows_box, @weak container, @strong rows => move |_| {
+        rows.borrow_mut().retain(|row| row.container != container);
+        rows_box.remove(&container);
+    }));
+
+    rows.borrow_mut().push(TweakRow { container, file, section, key, value });
+}
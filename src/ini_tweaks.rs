@@ -0,0 +1,189 @@
+use std::fs;
+use std::path::Path;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use crate::ini::IniFile;
+
+/// Config files in the user config folder that tweaks can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfigFile {
+    Engine,
+    GameUserSettings,
+}
+
+impl ConfigFile {
+    pub const ALL: [ConfigFile; 2] = [ConfigFile::Engine, ConfigFile::GameUserSettings];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ConfigFile::Engine => "Engine.ini",
+            ConfigFile::GameUserSettings => "GameUserSettings.ini",
+        }
+    }
+}
+
+/// One `key=value` entry a tweak mod writes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IniTweak {
+    pub file: ConfigFile,
+    pub section: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// An entry written by an enabled tweak mod and the value it replaced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedTweak {
+    pub file: ConfigFile,
+    pub section: String,
+    pub key: String,
+    pub previous: Option<String>,
+}
+
+/// Stored as the tweak mod's file, like a pak for pak mods.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TweakManifest {
+    pub tweaks: Vec<IniTweak>,
+    /// Empty while the mod is disabled.
+    #[serde(default)]
+    pub applied: Vec<AppliedTweak>,
+}
+
+pub struct Preset {
+    pub name: &'static str,
+    pub tweaks: &'static [(&'static str, &'static str)],
+}
+
+/// Common `[SystemSettings]` tweaks offered as starting points in the editor.
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "Disable Motion Blur",
+        tweaks: &[("r.MotionBlurQuality", "0"), ("r.DefaultFeature.MotionBlur", "0")],
+    },
+    Preset {
+        name: "Disable Film Grain",
+        tweaks: &[("r.Tonemapper.GrainQuantization", "0"), ("r.FilmGrain", "0")],
+    },
+    Preset {
+        name: "Disable Chromatic Aberration",
+        tweaks: &[("r.SceneColorFringeQuality", "0"), ("r.SceneColorFringe.Max", "0")],
+    },
+    Preset {
+        name: "Disable Depth of Field",
+        tweaks: &[("r.DepthOfFieldQuality", "0")],
+    },
+];
+
+pub const SYSTEM_SETTINGS: &str = "SystemSettings";
+
+impl Preset {
+    pub fn to_tweaks(&self) -> Vec<IniTweak> {
+        self.tweaks.iter()
+            .map(|(key, value)| IniTweak {
+                file: ConfigFile::Engine,
+                section: SYSTEM_SETTINGS.to_string(),
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect()
+    }
+}
+
+pub fn load(path: &Path) -> Result<TweakManifest> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save(path: &Path, manifest: &TweakManifest) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(())
+}
+
+pub fn is_applied(path: &Path) -> bool {
+    load(path).is_ok_and(|manifest| !manifest.applied.is_empty())
+}
+
+/// Writes the tweaks of the mod at `path` into the config files in
+/// `config_dir`, remembering the values they replace. Refused if another
+/// applied tweak mod already writes one of the entries, since reverting
+/// the two out of order would put back the wrong value.
+pub fn apply(path: &Path, config_dir: &Path) -> Result<()> {
+    let mut manifest = load(path)?;
+    if !manifest.applied.is_empty() {
+        return Ok(());
+    }
+    if let Some((other, tweak)) = applied_by_others(path, &manifest) {
+        bail!("{} already sets {} in [{}] of {}; disable it first", other, tweak.key, tweak.section, tweak.file.file_name());
+    }
+
+    for file in ConfigFile::ALL {
+        let tweaks: Vec<&IniTweak> = manifest.tweaks.iter().filter(|t| t.file == file).collect();
+        if tweaks.is_empty() {
+            continue;
+        }
+
+        let ini_path = config_dir.join(file.file_name());
+        let mut ini = IniFile::load(&ini_path)?;
+        for tweak in tweaks {
+            manifest.applied.push(AppliedTweak {
+                file,
+                section: tweak.section.clone(),
+                key: tweak.key.clone(),
+                previous: ini.get(&tweak.section, &tweak.key).map(String::from),
+            });
+            ini.set(&tweak.section, &tweak.key, &tweak.value);
+        }
+        ini.save(&ini_path)?;
+    }
+
+    save(path, &manifest)
+}
+
+/// The first tweak of `manifest` whose entry another applied tweak mod in
+/// the same folder has written, with that mod's file stem.
+fn applied_by_others<'a>(path: &Path, manifest: &'a TweakManifest) -> Option<(String, &'a IniTweak)> {
+    let parent = path.parent()?;
+    fs::read_dir(parent).ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|other| other != path && other.extension().is_some_and(|ext| ext == "json"))
+        .find_map(|other| {
+            let applied = load(&other).ok()?.applied;
+            let tweak = manifest.tweaks.iter().find(|tweak| {
+                applied.iter().any(|entry| {
+                    entry.file == tweak.file
+                        && entry.section.eq_ignore_ascii_case(&tweak.section)
+                        && entry.key.eq_ignore_ascii_case(&tweak.key)
+                })
+            })?;
+            let name = other.file_stem()?.to_string_lossy().to_string();
+            Some((name, tweak))
+        })
+}
+
+/// Puts back the values a tweak mod replaced, removing entries it added.
+pub fn revert(path: &Path, config_dir: &Path) -> Result<()> {
+    let mut manifest = load(path)?;
+
+    for file in ConfigFile::ALL {
+        if !manifest.applied.iter().any(|t| t.file == file) {
+            continue;
+        }
+
+        let ini_path = config_dir.join(file.file_name());
+        let mut ini = IniFile::load(&ini_path)?;
+        for applied in manifest.applied.iter().rev().filter(|t| t.file == file) {
+            match &applied.previous {
+                Some(previous) => ini.set(&applied.section, &applied.key, previous),
+                None => {
+                    ini.remove(&applied.section, &applied.key);
+                }
+            }
+        }
+        ini.save(&ini_path)?;
+    }
+
+    manifest.applied.clear();
+    save(path, &manifest)
+}
@@ -2,14 +2,21 @@ use gtk::prelude::*;
 use gtk::{Dialog, Box, Label, Entry, ProgressBar, ResponseType, Orientation, Button, Window, FileChooserDialog, FileChooserAction, FileFilter};
 use gtk::glib::{self, clone};
 use std::path::Path;
+use crate::dependencies::Dependency;
 use crate::mod_info::ModInfo;
+use crate::mod_list::{append_mod, ModList};
 use crate::mod_manager::ModManager;
 use crate::settings::Settings;
 use crate::nexus_api::NxmLink;
 use std::fs;
 use tempfile::tempdir;
 
-pub fn show_install_dialog(parent: &impl IsA<gtk::Window>, list_box: &gtk::ListBox) {
+pub fn show_install_dialog(parent: &impl IsA<gtk::Window>, mod_list: &ModList) {
+    show_install_dialog_with_ids(parent, mod_list, &[]);
+}
+
+/// Opens the install dialog with the given Nexus mod IDs already filled in.
+pub fn show_install_dialog_with_ids(parent: &impl IsA<gtk::Window>, mod_list: &ModList, mod_ids: &[i32]) {
     let dialog = Dialog::builder()
         .title("Install Mod")
         .transient_for(parent)
@@ -29,6 +36,10 @@ pub fn show_install_dialog(parent: &impl IsA<gtk::Window>, list_box: &gtk::ListB
     let id_label = Label::new(Some("Nexus Mod IDs:"));
     let id_entry = Entry::new();
     id_entry.set_placeholder_text(Some("Enter mod IDs separated by commas (e.g., 1,2,3)"));
+    if !mod_ids.is_empty() {
+        let ids: Vec<String> = mod_ids.iter().map(|id| id.to_string()).collect();
+        id_entry.set_text(&ids.join(","));
+    }
     id_box.append(&id_label);
     id_box.append(&id_entry);
     content.append(&id_box);
@@ -62,12 +73,12 @@ pub fn show_install_dialog(parent: &impl IsA<gtk::Window>, list_box: &gtk::ListB
     content.append(&buttons_box);
 
     // Connect local install button
-    local_button.connect_clicked(clone!(@weak dialog, @weak list_box => move |_| {
-        show_file_chooser_dialog(&dialog, &list_box);
+    local_button.connect_clicked(clone!(@weak dialog, @weak mod_list => move |_| {
+        show_file_chooser_dialog(&dialog, &mod_list);
     }));
 
     // Connect Nexus install button (previous install functionality)
-    nexus_button.connect_clicked(clone!(@weak dialog, @weak id_entry, @weak progress_bar, @weak status_label, @weak list_box => move |_| {
+    nexus_button.connect_clicked(clone!(@weak dialog, @weak id_entry, @weak progress_bar, @weak status_label, @weak mod_list => move |_| {
         let mod_ids: Result<Vec<i32>, _> = id_entry.text()
             .split(',')
             .map(|s| s.trim().parse::<i32>())
@@ -84,7 +95,7 @@ pub fn show_install_dialog(parent: &impl IsA<gtk::Window>, list_box: &gtk::ListB
                 let rt = tokio::runtime::Runtime::new().unwrap();
                 
                 let ctx = glib::MainContext::default();
-                ctx.spawn_local(clone!(@weak dialog, @weak progress_bar, @weak status_label, @weak list_box, @weak install_button => async move {
+                ctx.spawn_local(clone!(@weak dialog, @weak progress_bar, @weak status_label, @weak mod_list, @weak install_button => async move {
                     let settings = Settings::load();
                     let mod_manager = ModManager::new(settings).unwrap();
                     let total_mods = ids.len() as f64;
@@ -97,8 +108,10 @@ pub fn show_install_dialog(parent: &impl IsA<gtk::Window>, list_box: &gtk::ListB
                         
                         // Use the runtime to execute async operations
                         match rt.block_on(install_mod(&mod_manager, *mod_id, &progress_bar, None)) {
-                            Ok(mod_info) => {
-                                list_box.append(&mod_info.to_list_box_row());
+                            Ok(installed) => {
+                                for mod_info in &installed {
+                                    append_mod(&mod_list, mod_info);
+                                }
                                 success_count += 1;
                             },
                             Err(e) => {
@@ -140,7 +153,7 @@ pub fn show_install_dialog(parent: &impl IsA<gtk::Window>, list_box: &gtk::ListB
     dialog.present();
 }
 
-pub fn show_install_dialog_with_nxm(parent: &impl IsA<gtk::Window>, list_box: &gtk::ListBox, nxm: NxmLink) {
+pub fn show_install_dialog_with_nxm(parent: &impl IsA<gtk::Window>, mod_list: &ModList, nxm: NxmLink) {
     let dialog = Dialog::builder()
         .title("Install Mod")
         .transient_for(parent)
@@ -188,7 +201,7 @@ pub fn show_install_dialog_with_nxm(parent: &impl IsA<gtk::Window>, list_box: &g
     let nxm_expires = nxm.expires;
 
     // Connect install button click
-    install_button.connect_clicked(clone!(@weak dialog, @weak progress_bar, @weak status_label, @weak list_box, @weak install_button, @strong nxm_key => move |_| {
+    install_button.connect_clicked(clone!(@weak dialog, @weak progress_bar, @weak status_label, @weak mod_list, @weak install_button, @strong nxm_key => move |_| {
         progress_bar.set_visible(true);
         progress_bar.set_fraction(0.0);
         status_label.set_text("Installing mod...");
@@ -197,13 +210,15 @@ pub fn show_install_dialog_with_nxm(parent: &impl IsA<gtk::Window>, list_box: &g
         let ctx = glib::MainContext::default();
         let nxm_key = nxm_key.clone(); // Clone again for the inner closure
         
-        ctx.spawn_local(clone!(@weak dialog, @weak progress_bar, @weak status_label, @weak list_box, @weak install_button, @strong nxm_key => async move {
+        ctx.spawn_local(clone!(@weak dialog, @weak progress_bar, @weak status_label, @weak mod_list, @weak install_button, @strong nxm_key => async move {
             let settings = Settings::load();
             let mod_manager = ModManager::new(settings).unwrap();
             
             match install_mod(&mod_manager, nxm_mod_id, &progress_bar, Some((nxm_key, nxm_expires))).await {
-                Ok(mod_info) => {
-                    list_box.append(&mod_info.to_list_box_row());
+                Ok(installed) => {
+                    for mod_info in &installed {
+                        append_mod(&mod_list, mod_info);
+                    }
                     dialog.close();
                 },
                 Err(e) => {
@@ -224,13 +239,20 @@ pub fn show_install_dialog_with_nxm(parent: &impl IsA<gtk::Window>, list_box: &g
     dialog.present();
 }
 
-async fn install_mod(mod_manager: &ModManager, mod_id: i32, progress_bar: &ProgressBar, nxm_info: Option<(String, i64)>) -> anyhow::Result<ModInfo> {
+/// Downloads and installs the main file of a Nexus mod, returning a list
+/// entry for each mod it contained.
+async fn install_mod(mod_manager: &ModManager, mod_id: i32, progress_bar: &ProgressBar, nxm_info: Option<(String, i64)>) -> anyhow::Result<Vec<ModInfo>> {
     let client = mod_manager.nexus_client().ok_or_else(|| anyhow::anyhow!("No Nexus client available"))?;
     
     // Get mod info
     progress_bar.set_fraction(0.2);
     let mod_info = client.get_mod_info(mod_id).await?;
     
+    // Requirements are optional, a failed lookup shouldn't block the install
+    let dependencies = client.get_mod_requirements(mod_id).await
+        .map(|requirements| Dependency::from_requirements(&requirements))
+        .unwrap_or_default();
+
     // Get mod files
     progress_bar.set_fraction(0.4);
     let mod_files = client.get_mod_files(mod_id).await?;
@@ -252,6 +274,34 @@ async fn install_mod(mod_manager: &ModManager, mod_id: i32, progress_bar: &Progr
         let temp_dir = tempfile::tempdir()?;
         let temp_zip = temp_dir.path().join(&file.file_name);
         std::fs::write(&temp_zip, &mod_data)?;
+
+        if let Some(installed) = mod_manager.install_ue4ss_archive(&temp_zip)? {
+            progress_bar.set_fraction(1.0);
+            // Archives may bundle several UE4SS mods, each gets its own entry
+            return Ok(installed.into_iter()
+                .map(|installed_mod| ModInfo {
+                    version: file.version.clone().unwrap_or(installed_mod.version.clone()),
+                    author: mod_info.user.name.clone(),
+                    description: mod_info.description.clone(),
+                    nexus_mod_id: Some(mod_id),
+                    dependencies: dependencies.clone(),
+                    ..installed_mod
+                })
+                .collect());
+        }
+
+        if let Some(installed_mod) = mod_manager.install_file_mod(&temp_zip)? {
+            progress_bar.set_fraction(1.0);
+            return Ok(vec![ModInfo {
+                name: mod_info.name,
+                version: file.version.clone().unwrap_or(installed_mod.version.clone()),
+                author: mod_info.user.name,
+                description: mod_info.description,
+                nexus_mod_id: Some(mod_id),
+                dependencies,
+                ..installed_mod
+            }]);
+        }
         
         // Extract pak files
         let mut pak_path = None;
@@ -268,8 +318,9 @@ async fn install_mod(mod_manager: &ModManager, mod_id: i32, progress_bar: &Progr
                         if outpath.extension().map_or(false, |ext| ext == "pak") {
                             // Use the original filename from the zip
                             let pak_filename = outpath.file_name().unwrap();
+                            mod_manager.check_pak_name_free(pak_filename)?;
                             pak_name = Some(pak_filename.to_string_lossy().to_string());
-                            let pak_path_temp = mod_manager.mods_path().join(pak_filename);
+                            let pak_path_temp = mod_manager.staging_path().join(pak_filename);
                             let mut outfile = std::fs::File::create(&pak_path_temp)?;
                             std::io::copy(&mut zip_file, &mut outfile)?;
                             pak_path = Some(pak_path_temp);
@@ -283,10 +334,12 @@ async fn install_mod(mod_manager: &ModManager, mod_id: i32, progress_bar: &Progr
         pak_path.ok_or_else(|| anyhow::anyhow!("No .pak file found in zip archive"))?
     } else {
         // Direct pak file
-        let mod_path = mod_manager.mods_path().join(&file.file_name);
+        mod_manager.check_pak_name_free(std::ffi::OsStr::new(&file.file_name))?;
+        let mod_path = mod_manager.staging_path().join(&file.file_name);
         std::fs::write(&mod_path, mod_data)?;
         mod_path
     };
+    mod_manager.enable_mod(&final_path)?;
     
     progress_bar.set_fraction(1.0);
 
@@ -295,7 +348,7 @@ async fn install_mod(mod_manager: &ModManager, mod_id: i32, progress_bar: &Progr
         .map(|s| s.to_string_lossy().to_string())
         .unwrap_or(mod_info.name);
     
-    Ok(ModInfo {
+    Ok(vec![ModInfo {
         name,
         version: file.version.clone().unwrap_or_else(|| "1.0".to_string()),
         author: mod_info.user.name,
@@ -303,10 +356,12 @@ async fn install_mod(mod_manager: &ModManager, mod_id: i32, progress_bar: &Progr
         nexus_mod_id: Some(mod_id),
         installed_path: Some(final_path),
         enabled: true,
-    })
+        dependencies,
+        ..Default::default()
+    }])
 }
 
-pub fn show_file_chooser_dialog(parent: &impl IsA<Window>, list_box: &gtk::ListBox) {
+pub fn show_file_chooser_dialog(parent: &impl IsA<Window>, mod_list: &ModList) {
     let file_chooser = FileChooserDialog::new(
         Some("Select Mod File"),
         Some(parent),
@@ -325,7 +380,7 @@ pub fn show_file_chooser_dialog(parent: &impl IsA<Window>, list_box: &gtk::ListB
     zip_filter.set_name(Some("ZIP files"));
     file_chooser.add_filter(&zip_filter);
 
-    file_chooser.connect_response(clone!(@weak list_box => move |file_chooser, response| {
+    file_chooser.connect_response(clone!(@weak mod_list => move |file_chooser, response| {
         if response == ResponseType::Accept {
             if let Some(file) = file_chooser.file() {
                 if let Some(path) = file.path() {
@@ -333,10 +388,10 @@ pub fn show_file_chooser_dialog(parent: &impl IsA<Window>, list_box: &gtk::ListB
                     if let Ok(mod_manager) = ModManager::new(settings) {
                         match path.extension().and_then(|ext| ext.to_str()) {
                             Some("pak") => {
-                                handle_pak_file(&mod_manager, &path, &list_box);
+                                handle_pak_file(&mod_manager, &path, &mod_list);
                             },
                             Some("zip") => {
-                                handle_zip_file(&mod_manager, &path, &list_box);
+                                handle_zip_file(&mod_manager, &path, &mod_list);
                             },
                             _ => eprintln!("Unsupported file type"),
                         }
@@ -350,29 +405,68 @@ pub fn show_file_chooser_dialog(parent: &impl IsA<Window>, list_box: &gtk::ListB
     file_chooser.show();
 }
 
-fn handle_pak_file(mod_manager: &ModManager, path: &Path, list_box: &gtk::ListBox) {
-    if let Ok(dest_path) = mod_manager.install_local_mod(path) {
-        let name = dest_path.file_stem()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
-
-        let mod_info = ModInfo {
-            name,
-            version: String::from("1.0"),
-            author: String::from("Unknown"),
-            description: String::new(),
-            nexus_mod_id: None,
-            installed_path: Some(dest_path),
-            enabled: true,
-        };
-
-        list_box.append(&mod_info.to_list_box_row());
-        let _ = mod_manager.add_to_mod_list(mod_info);
-    }
+fn handle_pak_file(mod_manager: &ModManager, path: &Path, mod_list: &ModList) {
+    let dest_path = match mod_manager.install_local_mod(path) {
+        Ok(dest_path) => dest_path,
+        Err(e) => {
+            if let Some(window) = mod_list.root().and_downcast::<Window>() {
+                show_error_dialog(&window, &format!("Failed to install {}: {}", path.display(), e));
+            }
+            return;
+        }
+    };
+
+    let name = dest_path.file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let mod_info = ModInfo {
+        name,
+        version: String::from("1.0"),
+        author: String::from("Unknown"),
+        description: String::new(),
+        nexus_mod_id: None,
+        installed_path: Some(dest_path),
+        enabled: true,
+        ..Default::default()
+    };
+
+    append_mod(mod_list, &mod_info);
+    let _ = mod_manager.add_to_mod_list(mod_info);
 }
 
-fn handle_zip_file(mod_manager: &ModManager, path: &Path, list_box: &gtk::ListBox) {
+fn handle_zip_file(mod_manager: &ModManager, path: &Path, mod_list: &ModList) {
+    // UE4SS itself and UE4SS mods have their own layout
+    match mod_manager.install_ue4ss_archive(path) {
+        Ok(Some(installed)) => {
+            for mod_info in installed {
+                append_mod(mod_list, &mod_info);
+                let _ = mod_manager.add_to_mod_list(mod_info);
+            }
+            return;
+        },
+        Ok(None) => {},
+        Err(e) => {
+            eprintln!("Failed to install UE4SS archive: {}", e);
+            return;
+        },
+    }
+
+    // Archives without paks may hold loose files to deploy
+    match mod_manager.install_file_mod(path) {
+        Ok(Some(mod_info)) => {
+            append_mod(mod_list, &mod_info);
+            let _ = mod_manager.add_to_mod_list(mod_info);
+            return;
+        },
+        Ok(None) => {},
+        Err(e) => {
+            eprintln!("Failed to install file mod: {}", e);
+            return;
+        },
+    }
+
     // Create a temporary directory for extraction
     if let Ok(temp_dir) = tempdir() {
         if let Ok(file) = fs::File::open(path) {
@@ -397,7 +491,7 @@ fn handle_zip_file(mod_manager: &ModManager, path: &Path, list_box: &gtk::ListBo
                             if let Ok(mut outfile) = fs::File::create(&temp_path) {
                                 if let Ok(_) = std::io::copy(&mut file, &mut outfile) {
                                     // Install the extracted .pak file
-                                    handle_pak_file(mod_manager, &temp_path, list_box);
+                                    handle_pak_file(mod_manager, &temp_path, mod_list);
                                 }
                             }
                         }
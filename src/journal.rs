@@ -0,0 +1,160 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use crate::fs_ops;
+use crate::mod_info::ModInfo;
+
+/// A planned file operation. Everything is expressed as moves, which can be
+/// undone by moving back; new files are written to a staging folder first
+/// and moved into place.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// The record of a transaction in progress, written before anything is
+/// touched and updated after every step.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Journal {
+    pub renames: Vec<Rename>,
+    /// How many renames have completed.
+    pub done: usize,
+    /// Whether the target of the rename at `done` was free before it
+    /// started, so a copy found there after a crash is a leftover.
+    #[serde(default)]
+    pub target_was_free: bool,
+    /// The mod list to save once every rename is done.
+    pub mod_list: Option<Vec<ModInfo>>,
+}
+
+/// What `recover` did with an interrupted transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// Every file had been moved; the mod list was saved to match.
+    RolledForward,
+    /// Files moved before the interruption were put back.
+    RolledBack,
+}
+
+/// Applies `renames` and saves `mod_list` to `mod_list_path` as one
+/// transaction: if a rename fails the earlier ones are undone, and if the
+/// process dies `recover` finishes or undoes it on the next start.
+pub fn run(journal_path: &Path, renames: Vec<Rename>, mod_list_path: &Path, mod_list: Option<&[ModInfo]>) -> Result<()> {
+    if renames.is_empty() && mod_list.is_none() {
+        return Ok(());
+    }
+    if journal_path.exists() {
+        bail!("An interrupted operation has to be recovered first; restart the mod manager");
+    }
+
+    let mut journal = Journal {
+        target_was_free: renames.first().is_some_and(|rename| !rename.to.exists()),
+        renames,
+        done: 0,
+        mod_list: mod_list.map(<[ModInfo]>::to_vec),
+    };
+    save(journal_path, &journal)?;
+
+    while journal.done < journal.renames.len() {
+        let rename = &journal.renames[journal.done];
+        if let Err(e) = apply(rename) {
+            let error = anyhow::Error::from(e)
+                .context(format!("Failed to move {} to {}", rename.from.display(), rename.to.display()));
+            return match roll_back(&journal, false) {
+                Ok(()) => {
+                    fs::remove_file(journal_path)?;
+                    Err(error)
+                }
+                // Leave the journal so the next start tries again
+                Err(rollback) => Err(error.context(format!("Rolling back failed too: {}", rollback))),
+            };
+        }
+        journal.done += 1;
+        journal.target_was_free = journal.renames.get(journal.done).is_some_and(|rename| !rename.to.exists());
+        save(journal_path, &journal)?;
+    }
+
+    finish(journal_path, &journal, mod_list_path)
+}
+
+/// Finishes or undoes a transaction left behind by a crash. Once every
+/// rename is done the mod list is the only thing left, so it rolls forward;
+/// otherwise the moved files go back.
+pub fn recover(journal_path: &Path, mod_list_path: &Path) -> Result<Option<Recovery>> {
+    if !journal_path.exists() {
+        return Ok(None);
+    }
+    let journal: Journal = serde_json::from_str(&fs::read_to_string(journal_path)?)
+        .context("The journal of an interrupted operation is unreadable")?;
+
+    if journal.done >= journal.renames.len() {
+        finish(journal_path, &journal, mod_list_path)?;
+        Ok(Some(Recovery::RolledForward))
+    } else {
+        roll_back(&journal, true)?;
+        fs::remove_file(journal_path)?;
+        Ok(Some(Recovery::RolledBack))
+    }
+}
+
+/// Writes a file through a temporary one, so readers see either the old or
+/// the new content.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp = path.with_extension("tmp");
+    let mut file = fs::File::create(&temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+fn finish(journal_path: &Path, journal: &Journal, mod_list_path: &Path) -> Result<()> {
+    if let Some(mod_list) = &journal.mod_list {
+        write_atomic(mod_list_path, serde_json::to_string_pretty(mod_list)?.as_bytes())?;
+    }
+    fs::remove_file(journal_path)?;
+    Ok(())
+}
+
+fn apply(rename: &Rename) -> std::io::Result<()> {
+    if let Some(parent) = rename.to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs_ops::move_path(&rename.from, &rename.to)
+}
+
+/// Undoes the completed renames, newest first. A rename that failed while
+/// running cleaned up after itself, but the one in flight when a crash
+/// happened may or may not have gone through, so when `recovering` it is
+/// checked on disk. A move across filesystems stopped before removing the
+/// source leaves a copy behind, which is dropped if nothing was there before.
+fn roll_back(journal: &Journal, recovering: bool) -> Result<()> {
+    for (index, rename) in journal.renames.iter().enumerate().take(journal.done + 1).rev() {
+        if index == journal.done {
+            if !recovering {
+                continue;
+            }
+            if journal.target_was_free && rename.from.exists() && rename.to.exists() {
+                fs_ops::remove_path(&rename.to)
+                    .with_context(|| format!("Failed to remove the copy at {}", rename.to.display()))?;
+                continue;
+            }
+        }
+        let applied = index < journal.done || (rename.to.exists() && !rename.from.exists());
+        if applied {
+            apply(&Rename { from: rename.to.clone(), to: rename.from.clone() })
+                .with_context(|| format!("Failed to move {} back", rename.to.display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn save(journal_path: &Path, journal: &Journal) -> Result<()> {
+    write_atomic(journal_path, serde_json::to_string(journal)?.as_bytes())
+}
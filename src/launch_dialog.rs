@@ -0,0 +1,76 @@
+use gtk::prelude::*;
+use gtk::{MessageDialog, MessageType, ButtonsType, ResponseType, DialogFlags, Window};
+use gtk::glib;
+use crate::mod_info::ModInfo;
+use crate::mod_list::{list_mods, ModList};
+use crate::mod_manager::ModManager;
+use crate::settings::Settings;
+
+/// Runs the pre-launch checks and starts the game, asking first when
+/// something looks wrong.
+pub fn launch_game(parent: &impl IsA<Window>, mod_list: &ModList) {
+    let mod_manager = match ModManager::new(Settings::load()) {
+        Ok(mod_manager) => mod_manager,
+        Err(e) => {
+            show_error(parent, &format!("Failed to launch the game: {}", e));
+            return;
+        }
+    };
+
+    let mods: Vec<ModInfo> = list_mods(mod_list);
+    let issues = mod_manager.pre_launch_issues(&mods);
+    if issues.iter().any(|issue| issue.blocking) {
+        let messages: Vec<&str> = issues.iter()
+            .filter(|issue| issue.blocking)
+            .map(|issue| issue.message.as_str())
+            .collect();
+        show_error(parent, &format!("The game can't be started:\n\n• {}", messages.join("\n• ")));
+        return;
+    }
+    if issues.is_empty() {
+        start(parent, &mod_manager);
+        return;
+    }
+
+    let dialog = MessageDialog::new(
+        Some(parent),
+        DialogFlags::MODAL,
+        MessageType::Warning,
+        ButtonsType::None,
+        "Some mods may not work as expected",
+    );
+    let messages: Vec<&str> = issues.iter().map(|issue| issue.message.as_str()).collect();
+    dialog.set_secondary_text(Some(&format!("• {}", messages.join("\n• "))));
+    dialog.add_button("Cancel", ResponseType::Cancel);
+    dialog.add_button("Launch Anyway", ResponseType::Accept);
+    let parent = parent.upcast_ref::<Window>().clone();
+    dialog.connect_response(glib::clone!(@weak parent => move |dialog, response| {
+        dialog.close();
+        if response == ResponseType::Accept {
+            start(&parent, &mod_manager);
+        }
+    }));
+    dialog.present();
+}
+
+fn start(parent: &impl IsA<Window>, mod_manager: &ModManager) {
+    let result = mod_manager.launch_command().and_then(|command| {
+        eprintln!("Launching game: {:?}", command);
+        command.spawn()
+    });
+    if let Err(e) = result {
+        show_error(parent, &format!("Failed to launch the game: {}", e));
+    }
+}
+
+fn show_error(parent: &impl IsA<Window>, message: &str) {
+    let dialog = MessageDialog::new(
+        Some(parent),
+        DialogFlags::MODAL,
+        MessageType::Error,
+        ButtonsType::Close,
+        message,
+    );
+    dialog.connect_response(|dialog, _| dialog.close());
+    dialog.present();
+}
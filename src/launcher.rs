@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use crate::user_data::STEAM_APP_ID;
+
+/// Launcher in the game folder, which starts the shipping executable.
+pub const GAME_EXE: &str = "Stalker2.exe";
+
+/// How the Play button starts the game.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum LaunchMethod {
+    /// `steam://rungameid/1643320`, so Steam sets up Proton itself.
+    #[default]
+    Steam,
+    /// Heroic's `heroic://launch` link for the game's app name.
+    Heroic { app_name: String },
+    /// `lutris:rungame/<slug>`.
+    Lutris { slug: String },
+    /// The game executable, run through `wine` (e.g. `wine` or `umu-run`)
+    /// when not on Windows.
+    Executable { wine: String },
+}
+
+impl LaunchMethod {
+    pub const LABELS: [&'static str; 4] = ["Steam", "Heroic", "Lutris", "Executable"];
+
+    pub fn index(&self) -> usize {
+        match self {
+            LaunchMethod::Steam => 0,
+            LaunchMethod::Heroic { .. } => 1,
+            LaunchMethod::Lutris { .. } => 2,
+            LaunchMethod::Executable { .. } => 3,
+        }
+    }
+
+    /// Builds the method at `index` of `LABELS`; `value` is its app name,
+    /// slug or Wine command.
+    pub fn from_index(index: usize, value: &str) -> Self {
+        let value = value.trim().to_string();
+        match index {
+            1 => LaunchMethod::Heroic { app_name: value },
+            2 => LaunchMethod::Lutris { slug: value },
+            3 => LaunchMethod::Executable { wine: value },
+            _ => LaunchMethod::Steam,
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        match self {
+            LaunchMethod::Steam => "",
+            LaunchMethod::Heroic { app_name } => app_name,
+            LaunchMethod::Lutris { slug } => slug,
+            LaunchMethod::Executable { wine } => wine,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Windows,
+    Unix,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        if cfg!(windows) { Platform::Windows } else { Platform::Unix }
+    }
+}
+
+/// A command line to start the game, kept apart from `std::process::Command`
+/// so it can be inspected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub current_dir: Option<PathBuf>,
+}
+
+impl LaunchCommand {
+    pub fn spawn(&self) -> Result<()> {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        if let Some(dir) = &self.current_dir {
+            command.current_dir(dir);
+        }
+        command.spawn()?;
+        Ok(())
+    }
+}
+
+pub fn build_command(method: &LaunchMethod, game_path: &Path, platform: Platform) -> Result<LaunchCommand> {
+    match method {
+        LaunchMethod::Steam => Ok(open_url(&format!("steam://rungameid/{}", STEAM_APP_ID), platform)),
+        LaunchMethod::Heroic { app_name } => {
+            if app_name.is_empty() {
+                bail!("Set the game's Heroic app name in Settings");
+            }
+            Ok(open_url(&format!("heroic://launch/{}", app_name), platform))
+        }
+        LaunchMethod::Lutris { slug } => {
+            if slug.is_empty() {
+                bail!("Set the game's Lutris slug in Settings");
+            }
+            Ok(LaunchCommand {
+                program: "lutris".to_string(),
+                args: vec![format!("lutris:rungame/{}", slug)],
+                current_dir: None,
+            })
+        }
+        LaunchMethod::Executable { wine } => {
+            let exe = game_path.join(GAME_EXE).to_string_lossy().to_string();
+            let mut wine = wine.split_whitespace().map(String::from);
+            let (program, args) = match (platform, wine.next()) {
+                (Platform::Windows, _) => (exe, Vec::new()),
+                (Platform::Unix, Some(program)) => (program, wine.chain([exe]).collect()),
+                (Platform::Unix, None) => bail!("Set a Wine command in Settings to run the game executable"),
+            };
+            Ok(LaunchCommand { program, args, current_dir: Some(game_path.to_path_buf()) })
+        }
+    }
+}
+
+fn open_url(url: &str, platform: Platform) -> LaunchCommand {
+    let (program, args) = match platform {
+        // The empty argument is the window title `start` expects before a quoted target
+        Platform::Windows => ("cmd", vec!["/C".to_string(), "start".to_string(), String::new(), url.to_string()]),
+        Platform::Unix => ("xdg-open", vec![url.to_string()]),
+    };
+    LaunchCommand { program: program.to_string(), args, current_dir: None }
+}
+
+/// Something found before launching. Blocking issues stop the launch, the
+/// others can be ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchIssue {
+    pub message: String,
+    pub blocking: bool,
+}
+
+impl LaunchIssue {
+    pub fn blocking(message: impl Into<String>) -> Self {
+        Self { message: message.into(), blocking: true }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self { message: message.into(), blocking: false }
+    }
+}
+
+/// The game path has to be a Stalker 2 install, and the executable has to
+/// be there when it is started directly.
+pub fn check_game_path(method: &LaunchMethod, game_path: Option<&Path>) -> Vec<LaunchIssue> {
+    let Some(game_path) = game_path.filter(|path| !path.as_os_str().is_empty()) else {
+        return vec![LaunchIssue::blocking("The game path is not set")];
+    };
+    if !game_path.join("Stalker2").is_dir() {
+        return vec![LaunchIssue::blocking(format!(
+            "{} is not a S.T.A.L.K.E.R. 2 install",
+            game_path.display()
+        ))];
+    }
+    if matches!(method, LaunchMethod::Executable { .. }) && !game_path.join(GAME_EXE).is_file() {
+        return vec![LaunchIssue::blocking(format!("{} was not found in the game folder", GAME_EXE))];
+    }
+    Vec::new()
+}
@@ -2,4 +2,28 @@ pub mod settings;
 pub mod mod_manager;
 pub mod mod_info;
 pub mod nexus_api;
-pub mod install_dialog; 
\ No newline at end of file
+pub mod http;
+pub mod runtime;
+pub mod dependencies;
+pub mod rules;
+pub mod pak;
+pub mod load_order;
+pub mod ue4ss;
+pub mod file_deploy;
+pub mod ini;
+pub mod ini_tweaks;
+pub mod user_data;
+pub mod profiles;
+pub mod saves;
+pub mod launcher;
+pub mod game_logs;
+pub mod bisect;
+pub mod journal;
+pub mod fs_ops;
+pub mod staging;
+pub mod purge;
+pub mod mod_watcher;
+pub mod reconcile;
+pub mod mod_object;
+pub mod mod_list;
+pub mod install_dialog; pub mod bbcode;
@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+use anyhow::{bail, Result};
+use crate::mod_info::ModInfo;
+use crate::rules::{self, RuleKind};
+
+/// Sorted positions are written as a zero-padded prefix of this many digits.
+const PREFIX_DIGITS: usize = 3;
+
+/// Where a mod ends up in a sorted load order and why.
+#[derive(Debug, Clone)]
+pub struct Placement {
+    /// Index of the mod in the list the plan was computed from.
+    pub index: usize,
+    pub name: String,
+    pub current_file: String,
+    pub new_file: String,
+    pub reasons: Vec<String>,
+}
+
+impl Placement {
+    pub fn is_renamed(&self) -> bool {
+        self.current_file != self.new_file
+    }
+}
+
+/// A computed load order, kept separate from applying it so it can be
+/// shown as a dry run first.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOrderPlan {
+    pub placements: Vec<Placement>,
+}
+
+impl LoadOrderPlan {
+    pub fn changes(&self) -> impl Iterator<Item = &Placement> {
+        self.placements.iter().filter(|placement| placement.is_renamed())
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.changes().next().is_some()
+    }
+}
+
+/// `from` has to load before `to`.
+struct Edge {
+    from: usize,
+    to: usize,
+    reason: String,
+}
+
+/// Sorts the enabled mods. Load-after rules and dependencies are hard
+/// constraints; where two mods change the same assets the one changing more
+/// loads first, so the more targeted mod wins the overlap. Mods without
+/// constraints keep their current relative order.
+///
+/// `assets` holds the asset paths of each mod in `mods`, empty if unknown.
+pub fn plan(mods: &[ModInfo], assets: &[Vec<String>]) -> Result<LoadOrderPlan> {
+    let nodes = rules::load_order(mods);
+    let count = nodes.len();
+    if count >= 10usize.pow(PREFIX_DIGITS as u32) {
+        bail!("Too many enabled mods to sort ({})", count);
+    }
+    let name = |node: usize| mods[nodes[node]].name.as_str();
+
+    let mut edges = Vec::new();
+    for (node, &index) in nodes.iter().enumerate() {
+        let mod_info = &mods[index];
+        for (other, &other_index) in nodes.iter().enumerate() {
+            if other == node {
+                continue;
+            }
+            let other_mod = &mods[other_index];
+            if mod_info.rules.iter().any(|r| r.kind == RuleKind::LoadAfter && r.target.matches(other_mod)) {
+                edges.push(Edge {
+                    from: other,
+                    to: node,
+                    reason: format!("Loads after {}: load-after rule", other_mod.name),
+                });
+            }
+            if mod_info.dependencies.iter().any(|d| d.matches(other_mod)) {
+                edges.push(Edge {
+                    from: other,
+                    to: node,
+                    reason: format!("Loads after {}: requires it", other_mod.name),
+                });
+            }
+        }
+    }
+
+    if let Some(cycle) = find_cycle(count, &edges) {
+        let names: Vec<&str> = cycle.iter().map(|&node| name(node)).collect();
+        bail!("Load order rules form a cycle: {}", names.join(" → "));
+    }
+
+    let asset_sets: Vec<HashSet<&str>> = nodes.iter()
+        .map(|&index| {
+            assets.get(index)
+                .map(|paths| paths.iter().map(String::as_str).collect())
+                .unwrap_or_default()
+        })
+        .collect();
+    let mut notes: Vec<Vec<String>> = vec![Vec::new(); count];
+
+    for a in 0..count {
+        for b in a + 1..count {
+            let shared = asset_sets[a].intersection(&asset_sets[b]).count();
+            if shared == 0 {
+                continue;
+            }
+            // Ties keep the current order
+            let (first, last) = if asset_sets[a].len() >= asset_sets[b].len() { (a, b) } else { (b, a) };
+            if reaches(count, &edges, last, first) {
+                notes[last].push(format!(
+                    "Overlaps {} on {} assets, but a rule decides their order",
+                    name(first), shared
+                ));
+                continue;
+            }
+            edges.push(Edge {
+                from: first,
+                to: last,
+                reason: format!(
+                    "Loads after {}: both change {} assets and {} changes more ({} vs {}), so this mod's versions win",
+                    name(first), shared, name(first), asset_sets[first].len(), asset_sets[last].len()
+                ),
+            });
+        }
+    }
+
+    // Kahn's algorithm, always taking the earliest mod of the current order
+    let mut incoming = vec![0; count];
+    for edge in &edges {
+        incoming[edge.to] += 1;
+    }
+    let mut placed = vec![false; count];
+    let mut order = Vec::with_capacity(count);
+    while let Some(node) = (0..count).find(|&node| !placed[node] && incoming[node] == 0) {
+        placed[node] = true;
+        order.push(node);
+        for edge in edges.iter().filter(|edge| edge.from == node) {
+            incoming[edge.to] -= 1;
+        }
+    }
+
+    let placements = order.iter()
+        .enumerate()
+        .map(|(position, &node)| {
+            let index = nodes[node];
+            let current_file = mods[index].installed_path.as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let new_file = format!(
+                "{:0width$}_{}",
+                position + 1,
+                strip_order_prefix(&current_file),
+                width = PREFIX_DIGITS
+            );
+
+            let mut reasons: Vec<String> = edges.iter()
+                .filter(|edge| edge.to == node)
+                .map(|edge| edge.reason.clone())
+                .collect();
+            reasons.append(&mut notes[node]);
+            if reasons.is_empty() {
+                reasons.push("No rules or conflicts, keeps its place relative to the others".to_string());
+            }
+
+            Placement {
+                index,
+                name: mods[index].name.clone(),
+                current_file,
+                new_file,
+                reasons,
+            }
+        })
+        .collect();
+
+    Ok(LoadOrderPlan { placements })
+}
+
+/// Removes the position prefix a previous sort added to a file name.
+pub fn strip_order_prefix(file_name: &str) -> &str {
+    let bytes = file_name.as_bytes();
+    let has_prefix = bytes.len() > PREFIX_DIGITS + 1
+        && bytes[..PREFIX_DIGITS].iter().all(u8::is_ascii_digit)
+        && bytes[PREFIX_DIGITS] == b'_';
+    if has_prefix {
+        &file_name[PREFIX_DIGITS + 1..]
+    } else {
+        file_name
+    }
+}
+
+fn reaches(count: usize, edges: &[Edge], from: usize, to: usize) -> bool {
+    let mut seen = vec![false; count];
+    let mut stack = vec![from];
+    while let Some(node) = stack.pop() {
+        if node == to {
+            return true;
+        }
+        if std::mem::replace(&mut seen[node], true) {
+            continue;
+        }
+        stack.extend(edges.iter().filter(|edge| edge.from == node).map(|edge| edge.to));
+    }
+    false
+}
+
+/// Returns the nodes of a cycle, starting and ending with the same node.
+fn find_cycle(count: usize, edges: &[Edge]) -> Option<Vec<usize>> {
+    fn visit(node: usize, edges: &[Edge], state: &mut [u8], path: &mut Vec<usize>) -> Option<Vec<usize>> {
+        // 0 = unvisited, 1 = on the current path, 2 = done
+        state[node] = 1;
+        path.push(node);
+        for edge in edges.iter().filter(|edge| edge.from == node) {
+            match state[edge.to] {
+                1 => {
+                    let start = path.iter().position(|&n| n == edge.to).unwrap_or(0);
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(edge.to);
+                    return Some(cycle);
+                }
+                0 => {
+                    if let Some(cycle) = visit(edge.to, edges, state, path) {
+                        return Some(cycle);
+                    }
+                }
+                _ => {}
+            }
+        }
+        path.pop();
+        state[node] = 2;
+        None
+    }
+
+    let mut state = vec![0; count];
+    (0..count).find_map(|node| {
+        if state[node] == 0 {
+            visit(node, edges, &mut state, &mut Vec::new())
+        } else {
+            None
+        }
+    })
+}
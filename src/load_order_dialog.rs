@@ -0,0 +1,126 @@
+use gtk::prelude::*;
+use gtk::{Dialog, Box, Label, ScrolledWindow, ResponseType, Orientation, Window};
+use gtk::glib;
+use crate::mod_info::ModInfo;
+use crate::mod_list::{mod_objects, ModList};
+use crate::mod_manager::ModManager;
+use crate::settings::Settings;
+
+/// Shows the sorted load order as a dry run, renaming files only once the
+/// user applies it.
+pub fn show_load_order_dialog(parent: &impl IsA<Window>, mod_list: &ModList) {
+    let mod_manager = match ModManager::new(Settings::load()) {
+        Ok(mod_manager) => mod_manager,
+        Err(e) => {
+            show_message(parent, &format!("Failed to sort load order: {}", e));
+            return;
+        }
+    };
+
+    let objects = mod_objects(mod_list);
+    let mods: Vec<ModInfo> = objects.iter().map(|object| object.mod_info()).collect();
+    let plan = match mod_manager.plan_load_order(&mods) {
+        Ok(plan) => plan,
+        Err(e) => {
+            show_message(parent, &e.to_string());
+            return;
+        }
+    };
+
+    let dialog = Dialog::builder()
+        .title("Sort Load Order")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(560)
+        .default_height(480)
+        .build();
+
+    let content = dialog.content_area();
+    content.set_spacing(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+
+    let summary = Label::new(Some(&match plan.changes().count() {
+        0 => "The load order is already sorted.".to_string(),
+        1 => "1 file will be renamed.".to_string(),
+        n => format!("{} files will be renamed.", n),
+    }));
+    summary.set_xalign(0.0);
+    content.append(&summary);
+
+    let list = Box::new(Orientation::Vertical, 12);
+    for (position, placement) in plan.placements.iter().enumerate() {
+        let entry = Box::new(Orientation::Vertical, 2);
+
+        let title = Label::new(None);
+        title.set_markup(&format!("<b>{}. {}</b>", position + 1, glib::markup_escape_text(&placement.name)));
+        title.set_xalign(0.0);
+        entry.append(&title);
+
+        let file = if placement.is_renamed() {
+            format!("{} → {}", placement.current_file, placement.new_file)
+        } else {
+            format!("{} (unchanged)", placement.current_file)
+        };
+        let file_label = Label::new(Some(&file));
+        file_label.add_css_class("dim-label");
+        file_label.set_xalign(0.0);
+        file_label.set_wrap(true);
+        entry.append(&file_label);
+
+        for reason in &placement.reasons {
+            let reason_label = Label::new(Some(&format!("• {}", reason)));
+            reason_label.set_xalign(0.0);
+            reason_label.set_wrap(true);
+            entry.append(&reason_label);
+        }
+
+        list.append(&entry);
+    }
+
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+    scrolled.set_vexpand(true);
+    scrolled.set_child(Some(&list));
+    content.append(&scrolled);
+
+    dialog.add_button("Cancel", ResponseType::Cancel);
+    let apply_button = dialog.add_button("Apply", ResponseType::Accept);
+    apply_button.set_sensitive(plan.has_changes());
+
+    dialog.connect_response(move |dialog, response| {
+        if response == ResponseType::Accept {
+            let mut mods = mods.clone();
+            if let Err(e) = mod_manager.apply_load_order(&plan, &mut mods) {
+                if let Some(parent) = dialog.transient_for() {
+                    show_message(&parent, &format!("Failed to apply load order: {}", e));
+                }
+            }
+
+            // Files renamed before a failure still need their new paths
+            for placement in &plan.placements {
+                if let (Some(object), Some(mod_info)) = (objects.get(placement.index), mods.get(placement.index)) {
+                    object.set_mod_info(mod_info.clone());
+                }
+            }
+            let _ = mod_manager.save_mod_list(&mods);
+        }
+        dialog.close();
+    });
+
+    dialog.present();
+}
+
+fn show_message(parent: &impl IsA<Window>, message: &str) {
+    let dialog = gtk::MessageDialog::new(
+        Some(parent),
+        gtk::DialogFlags::MODAL,
+        gtk::MessageType::Error,
+        gtk::ButtonsType::Close,
+        message,
+    );
+    dialog.connect_response(|dialog, _| dialog.close());
+    dialog.present();
+}
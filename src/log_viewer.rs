@@ -0,0 +1,166 @@
+use gtk::prelude::*;
+use gtk::{Window, ScrolledWindow, Box, Label, Button, DropDown, TextView, TextBuffer, Orientation};
+use gtk::glib::{self, clone};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use crate::game_logs::{self, LogSource};
+use crate::mod_info::ModInfo;
+use crate::mod_list::{list_mods, ModList};
+use crate::mod_manager::ModManager;
+use crate::settings::Settings;
+
+const FILTERS: &[&str] = &["Problems and mod mentions", "All lines"];
+
+/// Shows the game and crash logs, marking lines that mention installed mods.
+pub fn show_log_viewer(parent: &impl IsA<Window>, mod_list: &ModList) {
+    let window = Window::builder()
+        .title("Game Logs")
+        .transient_for(parent)
+        .default_width(900)
+        .default_height(600)
+        .build();
+
+    let main_box = Box::new(Orientation::Vertical, 12);
+    main_box.set_margin_start(12);
+    main_box.set_margin_end(12);
+    main_box.set_margin_top(12);
+    main_box.set_margin_bottom(12);
+
+    let controls = Box::new(Orientation::Horizontal, 6);
+    let source_dropdown = DropDown::from_strings(&[]);
+    source_dropdown.set_hexpand(true);
+    let filter_dropdown = DropDown::from_strings(FILTERS);
+    let refresh_button = Button::from_icon_name("view-refresh-symbolic");
+    refresh_button.set_tooltip_text(Some("Look for new logs"));
+    controls.append(&source_dropdown);
+    controls.append(&filter_dropdown);
+    controls.append(&refresh_button);
+    main_box.append(&controls);
+
+    let summary = Label::new(None);
+    summary.set_xalign(0.0);
+    summary.set_wrap(true);
+    summary.set_selectable(true);
+    main_box.append(&summary);
+
+    let buffer = TextBuffer::new(None);
+    buffer.create_tag(Some("error"), &[("foreground", &"#e01b24")]);
+    buffer.create_tag(Some("warning"), &[("foreground", &"#c64600")]);
+    buffer.create_tag(Some("mention"), &[("weight", &700), ("background", &"rgba(246, 211, 45, 0.3)")]);
+    buffer.create_tag(Some("mod"), &[("style", &gtk::pango::Style::Italic)]);
+    let text_view = TextView::with_buffer(&buffer);
+    text_view.set_editable(false);
+    text_view.set_monospace(true);
+    text_view.set_wrap_mode(gtk::WrapMode::WordChar);
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_vexpand(true);
+    scrolled.set_child(Some(&text_view));
+    main_box.append(&scrolled);
+
+    window.set_child(Some(&main_box));
+
+    let mods: Rc<Vec<ModInfo>> = Rc::new(list_mods(mod_list));
+    let mod_manager = ModManager::new(Settings::load()).ok();
+    let index = Rc::new(mod_manager.as_ref().map(|m| m.log_index(&mods)).unwrap_or_default());
+    let sources: Rc<std::cell::RefCell<Vec<LogSource>>> = Rc::default();
+
+    let render = clone!(@weak source_dropdown, @weak filter_dropdown, @weak summary, @weak buffer, @strong sources, @strong mods, @strong index => move || {
+        buffer.set_text("");
+        let sources = sources.borrow();
+        let Some(source) = sources.get(source_dropdown.selected() as usize) else {
+            summary.set_text("No logs found. Check the user data folder in Settings, or start the game once.");
+            return;
+        };
+        let lines = match game_logs::read_log(&source.path) {
+            Ok(lines) => lines,
+            Err(e) => {
+                summary.set_text(&format!("Failed to read {}: {}", source.path.display(), e));
+                return;
+            }
+        };
+        let all_lines = filter_dropdown.selected() == 1;
+
+        // Mod name -> (lines mentioning it, how many of them are problems)
+        let mut mentioned: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        let mut problems = 0;
+        for line in &lines {
+            let mentions = index.mentions(&line.raw);
+            let is_problem = line.verbosity.is_problem();
+            if is_problem {
+                problems += 1;
+            }
+            for mention in &mentions {
+                if let Some(mod_info) = mods.get(mention.mod_index) {
+                    let counts = mentioned.entry(mod_info.name.as_str()).or_default();
+                    counts.0 += 1;
+                    if is_problem {
+                        counts.1 += 1;
+                    }
+                }
+            }
+            if !all_lines && !is_problem && mentions.is_empty() {
+                continue;
+            }
+
+            let mut tags = Vec::new();
+            match line.verbosity {
+                v if v <= game_logs::Verbosity::Error => tags.push("error"),
+                game_logs::Verbosity::Warning => tags.push("warning"),
+                _ => {}
+            }
+            if !mentions.is_empty() {
+                tags.push("mention");
+            }
+            let mut end = buffer.end_iter();
+            buffer.insert_with_tags_by_name(&mut end, &line.raw, &tags);
+            if !mentions.is_empty() {
+                let names: Vec<String> = mentions.iter()
+                    .filter_map(|mention| mods.get(mention.mod_index))
+                    .map(|mod_info| mod_info.name.clone())
+                    .collect();
+                buffer.insert_with_tags_by_name(&mut end, &format!("  ← {}", names.join(", ")), &["mod"]);
+            }
+            buffer.insert(&mut end, "\n");
+        }
+
+        let mut text = format!("{}: {} lines, {} errors and warnings.", source.title, lines.len(), problems);
+        if mentioned.is_empty() {
+            text.push_str(" No installed mods are mentioned.");
+        } else {
+            let parts: Vec<String> = mentioned.iter()
+                .map(|(name, (count, errors))| format!("{} ({} lines, {} problems)", name, count, errors))
+                .collect();
+            text.push_str(&format!(" Mentioned mods: {}.", parts.join(", ")));
+        }
+        summary.set_text(&text);
+    });
+
+    let reload = clone!(@weak source_dropdown, @strong sources, @strong render => move || {
+        let found = ModManager::new(Settings::load())
+            .map(|mod_manager| mod_manager.game_logs())
+            .unwrap_or_default();
+        let titles: Vec<String> = found.iter()
+            .map(|log| format!("{} ({})", log.title, format_time(log.modified)))
+            .collect();
+        *sources.borrow_mut() = found;
+        let titles: Vec<&str> = titles.iter().map(String::as_str).collect();
+        source_dropdown.set_model(Some(&gtk::StringList::new(&titles)));
+        source_dropdown.set_selected(0);
+        render();
+    });
+
+    source_dropdown.connect_selected_notify(clone!(@strong render => move |_| render()));
+    filter_dropdown.connect_selected_notify(clone!(@strong render => move |_| render()));
+    refresh_button.connect_clicked(clone!(@strong reload => move |_| reload()));
+
+    reload();
+    window.present();
+}
+
+fn format_time(time: std::time::SystemTime) -> String {
+    let unix = time.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    glib::DateTime::from_unix_local(unix as i64)
+        .and_then(|time| time.format("%Y-%m-%d %H:%M"))
+        .map(|text| text.to_string())
+        .unwrap_or_default()
+}
@@ -2,22 +2,71 @@ mod settings;
 mod mod_manager;
 mod mod_info;
 mod nexus_api;
+mod http;
 mod install_dialog;
 mod settings_dialog;
 mod docs_window;
+mod runtime;
+mod browse_window;
+mod bbcode;
+mod details_pane;
+mod endorsements;
+mod dependencies;
+mod rules;
+mod pak;
+mod load_order;
+mod load_order_dialog;
+mod ue4ss;
+mod file_deploy;
+mod ini;
+mod ini_tweaks;
+mod ini_editor;
+mod user_data;
+mod profiles;
+mod saves;
+mod launcher;
+mod game_logs;
+mod bisect;
+mod journal;
+mod fs_ops;
+mod staging;
+mod purge;
+mod mod_watcher;
+mod reconcile;
+mod mod_object;
+mod mod_list;
+mod bisect_window;
+mod log_viewer;
+mod launch_dialog;
+mod saves_window;
+mod purge_dialog;
+mod reconcile_dialog;
+mod bulk_actions;
 
 use gtk::prelude::*;
-use gtk::{Application, ApplicationWindow, HeaderBar, Button, Box, ScrolledWindow, 
-         Orientation};
+use gtk::{Application, ApplicationWindow, HeaderBar, Button, Box, Orientation};
 use crate::settings::Settings;
 use crate::mod_manager::ModManager;
 use crate::install_dialog::{show_install_dialog, show_install_dialog_with_nxm};
 use crate::settings_dialog::show_settings_dialog;
+use crate::browse_window::show_browse_window;
+use crate::details_pane::DetailsPane;
+use crate::load_order_dialog::show_load_order_dialog;
+use crate::ini_editor::show_ini_editor;
+use crate::saves_window::show_saves_window;
+use crate::launch_dialog::launch_game;
+use crate::log_viewer::show_log_viewer;
+use crate::bisect_window::show_bisect_window;
+use crate::reconcile_dialog::reconcile_list;
+use crate::purge_dialog::{purge_or_redeploy, update_button as update_purge_button, PURGE_LABEL};
+use crate::endorsements::sync_nexus_state;
 use crate::nexus_api::NxmLink;
+use crate::journal::Recovery;
 use std::rc::Rc;
 use std::cell::RefCell;
 use crate::mod_info::ModInfo;
-use std::path::PathBuf;
+use crate::mod_list::{append_mod, build_mod_list, connect_selection_changed, list_mods, replace_mods, ModList};
+use crate::bulk_actions::build_bulk_bar;
 use async_channel::bounded;
 
 const APP_ID: &str = "org.stalker2.mod.manager";
@@ -51,12 +100,13 @@ fn main() -> glib::ExitCode {
 fn handle_nxm_link(app: &Application, uri: &str) {
     if let Ok(nxm) = NxmLink::parse(uri) {
         if let Some(window) = app.active_window() {
-            if let Some(main_box) = window.child() {
-                if let Some(scrolled) = main_box.first_child() {
-                    if let Some(list_box) = scrolled.first_child().and_downcast::<gtk::ListBox>() {
-                        show_install_dialog_with_nxm(&window, &list_box, nxm);
-                    }
-                }
+            let mod_list = window.child()
+                .and_then(|main_box| main_box.last_child())
+                .and_downcast::<gtk::Paned>()
+                .and_then(|paned| paned.start_child())
+                .and_downcast::<ModList>();
+            if let Some(mod_list) = mod_list {
+                show_install_dialog_with_nxm(&window, &mod_list, nxm);
             }
         }
     }
@@ -75,14 +125,12 @@ fn build_ui(app: &Application) {
 
     let main_box = Box::new(Orientation::Vertical, 0);
     
-    // Create scrolled window and list box first
-    let scrolled = ScrolledWindow::new();
-    scrolled.set_vexpand(true);
-    scrolled.set_hexpand(true);
-
-    let list_box = gtk::ListBox::new();
-    list_box.set_selection_mode(gtk::SelectionMode::None);
-    list_box.add_css_class("mod-list");
+    // Create the mod list first
+    let (mod_list, filter_bar) = build_mod_list();
+    mod_list.set_vexpand(true);
+    mod_list.set_hexpand(true);
+    main_box.append(&filter_bar);
+    main_box.append(&build_bulk_bar(&mod_list));
 
     // Add CSS styling
     let provider = gtk::CssProvider::new();
@@ -127,6 +175,14 @@ fn build_ui(app: &Application) {
         switch:disabled slider {
             background-color: mix(@theme_bg_color, @theme_fg_color, 0.95);
         }
+        .details-pane .title-2 {
+            font-size: 20px;
+            font-weight: bold;
+        }
+        .details-pane .title-3 {
+            font-size: 16px;
+            font-weight: bold;
+        }
         "
     );
 
@@ -139,27 +195,105 @@ fn build_ui(app: &Application) {
         );
     }
 
+    // Finish or undo whatever a crash interrupted before reading the list
+    let recovery_message = match mod_manager.borrow().recover_journal() {
+        Ok(Some(Recovery::RolledForward)) => Some("An interrupted operation was completed.".to_string()),
+        Ok(Some(Recovery::RolledBack)) => Some("An interrupted operation was undone; your mods are as they were before it.".to_string()),
+        Ok(None) => None,
+        Err(e) => Some(format!("An interrupted operation could not be recovered: {}", e)),
+    };
+
+    // Older versions kept paks in the game folder only
+    if let Err(e) = mod_manager.borrow().migrate_to_staging() {
+        eprintln!("Failed to move mods into the store: {}", e);
+    }
+
     // Load existing mods
     if let Ok(mods) = mod_manager.borrow().load_mod_list() {
-        for mod_info in mods {
-            list_box.append(&mod_info.to_list_box_row());
-        }
+        replace_mods(&mod_list, &mods);
     }
 
-    scrolled.set_child(Some(&list_box));
-    main_box.append(&scrolled);
+    // Details of the selected mod next to the list
+    let details = Rc::new(DetailsPane::new());
+    let paned = gtk::Paned::new(Orientation::Horizontal);
+    paned.set_start_child(Some(&mod_list));
+    paned.set_end_child(Some(details.widget()));
+    paned.set_resize_end_child(false);
+    paned.set_shrink_end_child(false);
+    paned.set_position(620);
+    paned.set_vexpand(true);
+    main_box.append(&paned);
+
+    let details_select = Rc::clone(&details);
+    // Details are shown for a single mod only
+    connect_selection_changed(&mod_list, glib::clone!(@weak mod_list => move |selected| {
+        match selected.as_slice() {
+            [object] => details_select.show_mod(&mod_list, object),
+            _ => details_select.clear(),
+        }
+    }));
 
     // Create header bar with buttons
     let header = HeaderBar::new();
     let install_button = Button::with_label("Install Mod");
+    let browse_button = Button::with_label("Browse Nexus");
+    let sort_button = Button::with_label("Sort Load Order");
+    let tweaks_button = Button::with_label("INI Tweaks");
+    let saves_button = Button::with_label("Profiles & Saves");
+    let logs_button = Button::with_label("Logs");
+    let bisect_button = Button::with_label("Find Problem Mod");
+    let purge_button = Button::with_label(PURGE_LABEL);
+    update_purge_button(&purge_button, &mod_manager.borrow());
     let settings_button = Button::from_icon_name("emblem-system-symbolic");
+    let play_button = Button::with_label("Play");
+    play_button.add_css_class("suggested-action");
     header.pack_start(&install_button);
+    header.pack_start(&browse_button);
+    header.pack_start(&sort_button);
+    header.pack_start(&tweaks_button);
+    header.pack_start(&saves_button);
+    header.pack_start(&logs_button);
+    header.pack_start(&bisect_button);
+    header.pack_start(&purge_button);
     header.pack_end(&settings_button);
+    header.pack_end(&play_button);
     window.set_titlebar(Some(&header));
 
-    // Now connect button handlers after list_box is created
-    install_button.connect_clicked(glib::clone!(@weak window, @weak list_box => move |_| {
-        show_install_dialog(&window, &list_box);
+    // Now connect button handlers after mod_list is created
+    install_button.connect_clicked(glib::clone!(@weak window, @weak mod_list => move |_| {
+        show_install_dialog(&window, &mod_list);
+    }));
+
+    browse_button.connect_clicked(glib::clone!(@weak window, @weak mod_list => move |_| {
+        show_browse_window(&window, &mod_list);
+    }));
+
+    sort_button.connect_clicked(glib::clone!(@weak window, @weak mod_list => move |_| {
+        show_load_order_dialog(&window, &mod_list);
+    }));
+
+    tweaks_button.connect_clicked(glib::clone!(@weak window, @weak mod_list => move |_| {
+        show_ini_editor(&window, &mod_list);
+    }));
+
+    saves_button.connect_clicked(glib::clone!(@weak window, @weak mod_list => move |_| {
+        show_saves_window(&window, &mod_list);
+    }));
+
+    logs_button.connect_clicked(glib::clone!(@weak window, @weak mod_list => move |_| {
+        show_log_viewer(&window, &mod_list);
+    }));
+
+    bisect_button.connect_clicked(glib::clone!(@weak window, @weak mod_list => move |_| {
+        show_bisect_window(&window, &mod_list);
+    }));
+
+    purge_button.connect_clicked(glib::clone!(@weak window, @weak mod_list => move |button| {
+        purge_or_redeploy(&window, &mod_list, button);
+    }));
+
+    play_button.connect_clicked(glib::clone!(@weak window, @weak mod_list => move |_| {
+        launch_game(&window, &mod_list);
     }));
 
     settings_button.connect_clicked(glib::clone!(@weak window => move |_| {
@@ -170,74 +304,14 @@ fn build_ui(app: &Application) {
 
     // Clone Rc for closures
     let mod_manager_close = Rc::clone(&mod_manager);
-    let list_box_close = list_box.clone();
+    let mod_list_close = mod_list.clone();
 
     // Save mods when the window is closed
     window.connect_close_request(move |window| {
-        let mut mods = Vec::new();
-        let mut row = list_box_close.first_child();
-        let mod_manager = mod_manager_close.borrow();
-        
-        while let Some(widget) = row {
-            if let Some(list_box_row) = widget.downcast_ref::<gtk::ListBoxRow>() {
-                if let Some(box_) = list_box_row.child().and_downcast::<gtk::Box>() {
-                    // Get all children first
-                    let mut children = Vec::new();
-                    let mut child = box_.first_child();
-                    while let Some(widget) = child {
-                        children.push(widget.clone());
-                        child = widget.next_sibling();
-                    }
-
-                    // Process children
-                    let name = children.get(0)
-                        .and_then(|w| w.downcast_ref::<gtk::Label>())
-                        .map(|l| l.text().to_string())
-                        .unwrap_or_default();
-                    
-                    let version = children.get(1)
-                        .and_then(|w| w.downcast_ref::<gtk::Label>())
-                        .map(|l| l.text().to_string())
-                        .unwrap_or_default();
-                    
-                    let author = children.get(2)
-                        .and_then(|w| w.downcast_ref::<gtk::Label>())
-                        .map(|l| l.text().to_string())
-                        .unwrap_or_default();
-                    
-                    let enabled = box_.last_child()
-                        .and_downcast::<gtk::Switch>()
-                        .map(|s| s.is_active())
-                        .unwrap_or_default();
-
-                    let installed_path = if enabled {
-                        Some(mod_manager.mods_path().join(format!("{}.pak", name)))
-                    } else {
-                        Some(mod_manager.settings().game_path.clone()
-                            .unwrap_or_else(|| PathBuf::new())
-                            .join("Stalker2")
-                            .join("ModManager")
-                            .join("unloaded_mods")
-                            .join(format!("{}.pak", name)))
-                    };
-
-                    let mod_info = ModInfo {
-                        name,
-                        version,
-                        author,
-                        description: String::new(),
-                        nexus_mod_id: None,
-                        installed_path,
-                        enabled,
-                    };
-                    mods.push(mod_info);
-                }
-            }
-            row = widget.next_sibling();
-        }
-
-        drop(mod_manager);  // Release the borrow before mutating
-        let _ = mod_manager_close.borrow_mut().save_mod_list(&mods);
+        // Rows keep their records up to date, nothing has to be read back
+        // from the widgets
+        let mods: Vec<ModInfo> = list_mods(&mod_list_close);
+        let _ = mod_manager_close.borrow().save_mod_list(&mods);
         window.destroy();
         glib::Propagation::Stop
     });
@@ -246,7 +320,7 @@ fn build_ui(app: &Application) {
     let drop_target = gtk::DropTarget::new(gtk::glib::Type::STRING, gtk::gdk::DragAction::COPY);
     drop_target.set_types(&[gtk::glib::Type::STRING, gtk::gio::File::static_type()]);
     
-    let list_box_drop = list_box.clone();
+    let mod_list_drop = mod_list.clone();
 
     drop_target.connect_drop(move |_, value, _, _| {
         if let Ok(files) = value.get::<gtk::gio::ListModel>() {
@@ -268,9 +342,10 @@ fn build_ui(app: &Application) {
                                     nexus_mod_id: None,
                                     installed_path: Some(path.clone()),
                                     enabled: true,
+                                    ..Default::default()
                                 };
                                 
-                                list_box_drop.append(&mod_info.to_list_box_row());
+                                append_mod(&mod_list_drop, &mod_info);
                             }
                         }
                     }
@@ -283,6 +358,37 @@ fn build_ui(app: &Application) {
     window.add_controller(drop_target);
     window.present();
 
+    if let Some(message) = recovery_message {
+        let dialog = gtk::MessageDialog::new(
+            Some(&window),
+            gtk::DialogFlags::MODAL,
+            gtk::MessageType::Info,
+            gtk::ButtonsType::Close,
+            &message,
+        );
+        dialog.connect_response(|dialog, _| dialog.close());
+        dialog.present();
+    }
+
+    // Paks added or removed while the manager wasn't running
+    reconcile_list(&mod_list, &mod_manager.borrow());
+    sync_nexus_state(&window, &mod_list);
+
+    // Mod files added or removed by other programs show up in the list
+    let (watch_sender, watch_receiver) = bounded::<()>(1);
+    let watcher = Rc::new(RefCell::new(start_watcher(&mod_manager.borrow(), &watch_sender)));
+    let mod_manager_watch = Rc::clone(&mod_manager);
+    let mod_list_watch = mod_list.clone();
+    glib::spawn_future_local(async move {
+        while watch_receiver.recv().await.is_ok() {
+            // Let a burst of changes settle before looking
+            glib::timeout_future(std::time::Duration::from_millis(500)).await;
+            while watch_receiver.try_recv().is_ok() {}
+
+            reconcile_list(&mod_list_watch, &mod_manager_watch.borrow());
+        }
+    });
+
     let (sender, receiver) = bounded::<()>(1);
 
     unsafe {
@@ -291,17 +397,28 @@ fn build_ui(app: &Application) {
 
     glib::spawn_future_local(async move {
         while receiver.recv().await.is_ok() {
+            // Settings may have changed where mods are kept
+            if let Ok(updated) = ModManager::new(Settings::load()) {
+                *watcher.borrow_mut() = start_watcher(&updated, &watch_sender);
+                *mod_manager.borrow_mut() = updated;
+            }
             if let Ok(mods) = mod_manager.borrow().load_mod_list() {
-                // Clear existing items
-                while let Some(child) = list_box.first_child() {
-                    list_box.remove(&child);
-                }
-                
-                // Add updated items
-                for mod_info in mods {
-                    list_box.append(&mod_info.to_list_box_row());
-                }
+                replace_mods(&mod_list, &mods);
             }
+            reconcile_list(&mod_list, &mod_manager.borrow());
         }
     });
 }
+
+/// Watches the mod folders of `mod_manager`; the list just won't update
+/// live if that fails.
+fn start_watcher(mod_manager: &ModManager, sender: &async_channel::Sender<()>) -> Option<notify::RecommendedWatcher> {
+    let paths = [mod_manager.mods_path().to_path_buf(), mod_manager.staging_path().to_path_buf()];
+    match mod_watcher::watch(&paths, sender.clone()) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            eprintln!("Failed to watch the mod folders: {}", e);
+            None
+        }
+    }
+}
@@ -1,12 +1,61 @@
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
-use gtk::prelude::*;
-use gtk::glib;
-use crate::settings::Settings;
-use crate::mod_manager::ModManager;
+use uuid::Uuid;
+use crate::dependencies::Dependency;
+use crate::file_deploy::FileMapping;
+use crate::ini_tweaks::IniTweak;
+use crate::rules::ModRule;
+use crate::nexus_api::{compare_versions, EndorsementStatus, TrackedMod, UserEndorsement};
+
+/// How long a mod has to stay enabled before we suggest endorsing it.
+const ENDORSE_PROMPT_AFTER_SECS: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModKind {
+    /// A pak in `Content/Paks/~mods`.
+    #[default]
+    Pak,
+    /// The UE4SS runtime in `Binaries/Win64`.
+    Ue4ss,
+    /// A UE4SS Lua or C++ mod in `ue4ss/Mods`.
+    Lua,
+    /// Loose files copied into the game or user config folders.
+    Files,
+    /// Entries written into `Engine.ini` or `GameUserSettings.ini`.
+    IniTweak,
+}
+
+impl ModKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModKind::Pak => "Pak",
+            ModKind::Ue4ss => "UE4SS",
+            ModKind::Lua => "Lua",
+            ModKind::Files => "Files",
+            ModKind::IniTweak => "Tweak",
+        }
+    }
+}
+
+/// Identifies a list entry for good. File names can change and clash, so
+/// everything that refers to a particular mod uses this instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModId(Uuid);
+
+impl Default for ModId {
+    /// A fresh ID, so entries made with `..Default::default()` and entries
+    /// from lists saved before IDs existed are all distinct.
+    fn default() -> Self {
+        ModId(Uuid::new_v4())
+    }
+}
 
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct ModInfo {
+    #[serde(default)]
+    pub id: ModId,
     pub name: String,
     pub version: String,
     pub author: String,
@@ -14,86 +63,105 @@ pub struct ModInfo {
     pub nexus_mod_id: Option<i32>,
     pub installed_path: Option<PathBuf>,
     pub enabled: bool,
+    #[serde(default)]
+    pub kind: ModKind,
+    /// Where each file of a `Files` mod is deployed.
+    #[serde(default)]
+    pub file_mappings: Vec<FileMapping>,
+    /// Entries an `IniTweak` mod writes, copied for the details pane. The
+    /// mod's `TweakManifest` is what gets applied and reverted.
+    #[serde(default)]
+    pub ini_tweaks: Vec<IniTweak>,
+    /// Unix time the mod was last enabled, used to suggest endorsing it.
+    #[serde(default)]
+    pub enabled_since: Option<u64>,
+    #[serde(default)]
+    pub endorsement: Option<EndorsementStatus>,
+    #[serde(default)]
+    pub tracked: bool,
+    #[serde(default)]
+    pub endorse_prompted: bool,
+    /// Mods that have to be enabled for this one to work.
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+    /// Incompatibilities and load-after rules set by the user.
+    #[serde(default)]
+    pub rules: Vec<ModRule>,
+    /// Set by the user for mods that invalidate saves made without them.
+    #[serde(default)]
+    pub save_breaking: bool,
+    /// Unix time the mod was installed. Not known for entries made before
+    /// it was recorded.
+    #[serde(default)]
+    pub installed_at: Option<u64>,
+    /// Newest version on Nexus as of the last sync.
+    #[serde(default)]
+    pub latest_version: Option<String>,
+    /// Size of a pak as last seen on disk, to recognise it after it was
+    /// renamed outside the manager.
+    #[serde(default)]
+    pub file_size: Option<u64>,
 }
 
 impl ModInfo {
-    pub fn to_list_box_row(&self) -> gtk::ListBoxRow {
-        let row = gtk::ListBoxRow::new();
-        let box_ = gtk::Box::new(gtk::Orientation::Horizontal, 12);
-        
-        // Set box margins and make it expand
-        box_.set_margin_start(12);
-        box_.set_margin_end(12);
-        box_.set_margin_top(8);
-        box_.set_margin_bottom(8);
-        box_.set_hexpand(true);
-        box_.set_vexpand(true);
-
-        // Name label with ellipsization
-        let name_label = gtk::Label::new(Some(&self.name));
-        name_label.set_xalign(0.0);
-        name_label.set_hexpand(true);
-        name_label.set_ellipsize(gtk::pango::EllipsizeMode::End);
-        name_label.set_width_chars(30);
-
-        // Version label
-        let version_label = gtk::Label::new(Some(&self.version));
-        version_label.set_width_chars(10);
-        version_label.set_xalign(0.5);
-
-        // Author label
-        let author_label = gtk::Label::new(Some(&self.author));
-        author_label.set_width_chars(15);
-        author_label.set_xalign(0.5);
-        
-        // Enable switch
-        let enable_switch = gtk::Switch::new();
-        enable_switch.set_active(self.enabled);
-        enable_switch.set_valign(gtk::Align::Center);
+    /// The installed file or folder name, which stays the same while the
+    /// mod is enabled and disabled.
+    pub fn file_name(&self) -> Option<String> {
+        self.installed_path.as_ref()?
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    }
 
-        // Add widgets to box
-        box_.append(&name_label);
-        box_.append(&version_label);
-        box_.append(&author_label);
-        box_.append(&enable_switch);
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled && self.enabled_since.is_none() {
+            self.enabled_since = Some(unix_now());
+        } else if !enabled {
+            self.enabled_since = None;
+        }
+        self.enabled = enabled;
+    }
 
-        // Keep existing switch functionality
-        if let Some(path) = self.installed_path.clone() {
-            let path = path.clone();
-            enable_switch.connect_state_set(move |switch, state| {
-                eprintln!("Toggling mod state: {:?} -> {}", path, state);
-                
-                let settings = Settings::load();
-                if let Ok(mod_manager) = ModManager::new(settings.clone()) {
-                    // Convert to absolute path if needed
-                    let absolute_path = if path.is_absolute() {
-                        path.clone()
-                    } else {
-                        settings.game_path.clone()
-                            .unwrap_or_else(|| PathBuf::new())
-                            .join(&path)
-                    };
-                    
-                    let result = if state {
-                        mod_manager.enable_mod(&absolute_path)
-                    } else {
-                        mod_manager.disable_mod(&absolute_path)
-                    };
+    /// Updates the cached endorsement and tracking state from the user's Nexus account.
+    pub fn apply_nexus_state(&mut self, endorsements: &[UserEndorsement], tracked: &[TrackedMod]) {
+        let Some(mod_id) = self.nexus_mod_id else {
+            return;
+        };
+        self.endorsement = endorsements.iter()
+            .find(|e| e.mod_id == mod_id)
+            .map(|e| e.status);
+        self.tracked = tracked.iter().any(|t| t.mod_id == mod_id);
+    }
 
-                    if let Err(e) = result {
-                        eprintln!("Failed to toggle mod state: {} (path: {:?})", e, absolute_path);
-                        switch.set_active(!state);
-                    }
-                }
-                glib::Propagation::Stop
-            });
+    /// Records the Nexus version from `(mod ID, version)` pairs such as
+    /// `NexusClient::latest_versions` gives.
+    pub fn apply_latest_version(&mut self, versions: &[(i32, String)]) {
+        if let Some((_, version)) = versions.iter().find(|(mod_id, _)| Some(*mod_id) == self.nexus_mod_id) {
+            self.latest_version = Some(version.clone());
         }
+    }
+
+    /// Whether Nexus has a newer version than the installed one.
+    pub fn is_outdated(&self) -> bool {
+        self.latest_version.as_deref()
+            .is_some_and(|latest| compare_versions(latest, &self.version) == std::cmp::Ordering::Greater)
+    }
 
-        row.set_child(Some(&box_));
-        row.set_selectable(true);
-        row.set_activatable(true);
-        row.set_can_focus(true);
-        
-        row
+    pub fn should_prompt_endorsement(&self, now: u64) -> bool {
+        let voted = matches!(
+            self.endorsement,
+            Some(EndorsementStatus::Endorsed) | Some(EndorsementStatus::Abstained)
+        );
+        self.nexus_mod_id.is_some()
+            && self.enabled
+            && !voted
+            && !self.endorse_prompted
+            && self.enabled_since.is_some_and(|since| now.saturating_sub(since) >= ENDORSE_PROMPT_AFTER_SECS)
     }
-} 
\ No newline at end of file
+}
+
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
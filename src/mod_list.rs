@@ -0,0 +1,760 @@
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::rc::Rc;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib, ColumnView, ColumnViewColumn};
+use gtk::glib::clone;
+use crate::dependencies::{DependencyFix, DependencyIssue};
+use crate::mod_info::{unix_now, ModId, ModInfo, ModKind};
+use crate::mod_manager::ModManager;
+use crate::mod_object::ModObject;
+use crate::nexus_api::{compare_versions, EndorsementStatus};
+use crate::rules::{self, RuleViolation};
+use crate::settings::Settings;
+use crate::runtime;
+
+thread_local! {
+    /// Set while `refresh` updates the objects, whose change signals would
+    /// otherwise start it again.
+    static REFRESHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The chips above the list. Each active chip narrows it further.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModFilter {
+    Enabled,
+    Disabled,
+    /// Part of a broken incompatibility or load-after rule.
+    Conflicting,
+    /// Nexus has a newer version.
+    Outdated,
+    /// From Nexus but not tracked there, so updates don't show up in
+    /// notifications.
+    Untracked,
+}
+
+impl ModFilter {
+    pub const ALL: [ModFilter; 5] = [
+        ModFilter::Enabled,
+        ModFilter::Disabled,
+        ModFilter::Conflicting,
+        ModFilter::Outdated,
+        ModFilter::Untracked,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModFilter::Enabled => "Enabled",
+            ModFilter::Disabled => "Disabled",
+            ModFilter::Conflicting => "Conflicting",
+            ModFilter::Outdated => "Outdated",
+            ModFilter::Untracked => "Untracked",
+        }
+    }
+
+    pub fn matches(&self, object: &ModObject) -> bool {
+        let mod_info = object.mod_info();
+        match self {
+            ModFilter::Enabled => mod_info.enabled,
+            ModFilter::Disabled => !mod_info.enabled,
+            ModFilter::Conflicting => object.is_conflicting(),
+            ModFilter::Outdated => mod_info.is_outdated(),
+            ModFilter::Untracked => mod_info.nexus_mod_id.is_some() && !mod_info.tracked,
+        }
+    }
+}
+
+#[derive(Default)]
+struct FilterState {
+    /// Lowercase text from the search entry.
+    search: String,
+    chips: Vec<ModFilter>,
+}
+
+impl FilterState {
+    fn matches(&self, object: &ModObject) -> bool {
+        let mod_info = object.mod_info();
+        let found = self.search.is_empty()
+            || mod_info.name.to_lowercase().contains(&self.search)
+            || mod_info.author.to_lowercase().contains(&self.search);
+        found && self.chips.iter().all(|chip| chip.matches(object))
+    }
+}
+
+mod imp {
+    use super::*;
+
+    pub struct ModList {
+        pub(super) view: ColumnView,
+        /// Every mod in list order; the view shows it filtered and sorted.
+        pub(super) store: gio::ListStore,
+        pub(super) filter: gtk::CustomFilter,
+        pub(super) filter_state: Rc<RefCell<FilterState>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ModList {
+        const NAME: &'static str = "Stalker2ModList";
+        type Type = super::ModList;
+        type ParentType = gtk::Widget;
+
+        fn new() -> Self {
+            let store = gio::ListStore::new::<ModObject>();
+            let filter_state = Rc::new(RefCell::new(FilterState::default()));
+            let filter = gtk::CustomFilter::new(clone!(@strong filter_state => move |item| {
+                item.downcast_ref::<ModObject>().is_some_and(|object| filter_state.borrow().matches(object))
+            }));
+
+            let view = ColumnView::new(None::<gtk::SelectionModel>);
+            let filtered = gtk::FilterListModel::new(Some(store.clone()), Some(filter.clone()));
+            let sorted = gtk::SortListModel::new(Some(filtered), view.sorter());
+            view.set_model(Some(&gtk::MultiSelection::new(Some(sorted))));
+
+            Self { view, store, filter, filter_state }
+        }
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_layout_manager_type::<gtk::BinLayout>();
+        }
+    }
+
+    impl ObjectImpl for ModList {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let scrolled = gtk::ScrolledWindow::new();
+            scrolled.set_child(Some(&self.view));
+            scrolled.set_parent(&*self.obj());
+        }
+
+        fn dispose(&self) {
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for ModList {}
+}
+
+glib::wrapper! {
+    /// The mod list: a scrolled column view over a store of `ModObject`s,
+    /// along with the filter the search bar drives.
+    pub struct ModList(ObjectSubclass<imp::ModList>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+/// Builds the mod list, sorted by clicking the column headers. The returned
+/// bar holds the search entry and filter chips that narrow it.
+pub fn build_mod_list() -> (ModList, gtk::Box) {
+    let mod_list: ModList = glib::Object::new();
+    let view = &mod_list.imp().view;
+    view.add_css_class("mod-list");
+    let state = mod_list.imp().filter_state.clone();
+    let filter = mod_list.imp().filter.clone();
+
+    let name = label_column("Name", |mod_info| mod_info.name.clone(), |a, b| {
+        a.mod_info().name.to_lowercase().cmp(&b.mod_info().name.to_lowercase())
+    });
+    name.set_expand(true);
+    view.append_column(&name);
+    view.append_column(&label_column("Type", |mod_info| mod_info.kind.label().to_string(), |a, b| {
+        a.mod_info().kind.label().cmp(b.mod_info().kind.label())
+    }));
+    view.append_column(&version_column());
+    view.append_column(&label_column("Author", |mod_info| mod_info.author.clone(), |a, b| {
+        a.mod_info().author.to_lowercase().cmp(&b.mod_info().author.to_lowercase())
+    }));
+    view.append_column(&object_column(
+        "Size",
+        |_| dim_label(1.0),
+        |label: &gtk::Label, object| label.set_text(&glib::format_size(object.size())),
+        Some(|a: &ModObject, b: &ModObject| a.size().cmp(&b.size())),
+    ));
+    view.append_column(&object_column(
+        "Installed",
+        |_| dim_label(0.5),
+        |label: &gtk::Label, object| label.set_text(&object.installed_at().map(format_date).unwrap_or_default()),
+        Some(|a: &ModObject, b: &ModObject| a.installed_at().cmp(&b.installed_at())),
+    ));
+    view.append_column(&object_column(
+        "Load Order",
+        |_| dim_label(0.5),
+        |label: &gtk::Label, object| {
+            label.set_text(&object.load_order().map(|position| position.to_string()).unwrap_or_default());
+        },
+        // Mods outside the load order go last
+        Some(|a: &ModObject, b: &ModObject| match (a.load_order(), b.load_order()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        }),
+    ));
+    view.append_column(&endorse_column());
+    view.append_column(&enabled_column());
+
+    let bar = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    bar.set_margin_start(6);
+    bar.set_margin_end(6);
+    bar.set_margin_top(6);
+    bar.set_margin_bottom(6);
+    let search = gtk::SearchEntry::new();
+    search.set_placeholder_text(Some("Search mods"));
+    search.set_hexpand(true);
+    search.connect_search_changed(clone!(@strong state, @weak filter => move |entry| {
+        state.borrow_mut().search = entry.text().to_lowercase();
+        filter.changed(gtk::FilterChange::Different);
+    }));
+    bar.append(&search);
+    for chip in ModFilter::ALL {
+        let button = gtk::ToggleButton::with_label(chip.label());
+        button.connect_toggled(clone!(@strong state, @weak filter => move |button| {
+            let mut state = state.borrow_mut();
+            state.chips.retain(|active| *active != chip);
+            if button.is_active() {
+                state.chips.push(chip);
+            }
+            drop(state);
+            filter.changed(gtk::FilterChange::Different);
+        }));
+        bar.append(&button);
+    }
+
+    (mod_list, bar)
+}
+
+/// Every mod in list order, regardless of sorting and filtering.
+pub fn list_mods(mod_list: &ModList) -> Vec<ModInfo> {
+    mod_objects(mod_list).iter().map(ModObject::mod_info).collect()
+}
+
+pub fn mod_objects(mod_list: &ModList) -> Vec<ModObject> {
+    let store = &mod_list.imp().store;
+    (0..store.n_items())
+        .filter_map(|position| store.item(position).and_downcast::<ModObject>())
+        .collect()
+}
+
+pub fn find_mod_object(mod_list: &ModList, id: ModId) -> Option<ModObject> {
+    mod_objects(mod_list).into_iter().find(|object| object.id() == id)
+}
+
+/// Replaces every mod in the list, after many changed at once.
+pub fn replace_mods(mod_list: &ModList, mods: &[ModInfo]) {
+    let objects: Vec<ModObject> = mods.iter().map(|mod_info| watched_object(mod_list, mod_info.clone())).collect();
+    let store = &mod_list.imp().store;
+    store.splice(0, store.n_items(), &objects);
+    refresh(mod_list);
+}
+
+/// Adds a newly installed or found mod to the end of the list.
+pub fn append_mod(mod_list: &ModList, mod_info: &ModInfo) {
+    let mut mod_info = mod_info.clone();
+    mod_info.installed_at.get_or_insert_with(unix_now);
+    mod_list.imp().store.append(&watched_object(mod_list, mod_info));
+    refresh(mod_list);
+}
+
+/// The selected mods that pass the filters, in list order as shown.
+pub fn selected_mods(mod_list: &ModList) -> Vec<ModObject> {
+    let Some(selection) = mod_list.imp().view.model() else {
+        return Vec::new();
+    };
+    (0..selection.n_items())
+        .filter(|&position| selection.is_selected(position))
+        .filter_map(|position| selection.item(position).and_downcast::<ModObject>())
+        .collect()
+}
+
+/// Calls `f` with the selected mods whenever the selection changes.
+pub fn connect_selection_changed<F: Fn(Vec<ModObject>) + 'static>(mod_list: &ModList, f: F) {
+    if let Some(selection) = mod_list.imp().view.model() {
+        selection.connect_selection_changed(clone!(@weak mod_list => move |_, _, _| {
+            f(selected_mods(&mod_list));
+        }));
+    }
+}
+
+/// Brings the list in line with `mods` after an operation on several mods:
+/// records are updated and mods that are gone removed, keeping the
+/// selection of the rest.
+pub fn update_mods(mod_list: &ModList, mods: &[ModInfo]) {
+    let store = &mod_list.imp().store;
+    REFRESHING.with(|refreshing| refreshing.set(true));
+    for position in (0..store.n_items()).rev() {
+        let Some(object) = store.item(position).and_downcast::<ModObject>() else {
+            continue;
+        };
+        match mods.iter().find(|mod_info| mod_info.id == object.id()) {
+            Some(mod_info) => object.set_mod_info(mod_info.clone()),
+            None => store.remove(position),
+        }
+    }
+    REFRESHING.with(|refreshing| refreshing.set(false));
+    refresh(mod_list);
+}
+
+/// An object for the list whose changes update the state that depends on
+/// the whole list.
+fn watched_object(mod_list: &ModList, mod_info: ModInfo) -> ModObject {
+    let object = ModObject::new(mod_info);
+    object.connect_changed(clone!(@weak mod_list => move |_| refresh(&mod_list)));
+    object
+}
+
+/// Works out the load order and rule conflicts, which depend on every mod,
+/// then sorts and filters the list again.
+fn refresh(mod_list: &ModList) {
+    if REFRESHING.with(Cell::get) {
+        return;
+    }
+    REFRESHING.with(|refreshing| refreshing.set(true));
+
+    let objects = mod_objects(mod_list);
+    let mods: Vec<ModInfo> = objects.iter().map(ModObject::mod_info).collect();
+    let order = rules::load_order(&mods);
+    let violations = rules::validate(&mods);
+    for (index, object) in objects.iter().enumerate() {
+        let load_order = order.iter().position(|&i| i == index).map(|position| position as u32 + 1);
+        let conflicting = violations.iter().any(|violation| violation.involves(mods[index].id));
+        object.set_list_state(load_order, conflicting);
+    }
+
+    REFRESHING.with(|refreshing| refreshing.set(false));
+    if let Some(sorter) = mod_list.imp().view.sorter() {
+        sorter.changed(gtk::SorterChange::Different);
+    }
+    mod_list.imp().filter.changed(gtk::FilterChange::Different);
+}
+
+/// A column of widgets made by `setup` for a list item and filled by
+/// `bind`, again whenever the mod changes.
+fn object_column<W, S>(
+    title: &str,
+    setup: impl Fn(&gtk::ListItem) -> W + 'static,
+    bind: impl Fn(&W, &ModObject) + 'static,
+    sort: Option<S>,
+) -> ColumnViewColumn
+where
+    W: IsA<gtk::Widget>,
+    S: Fn(&ModObject, &ModObject) -> Ordering + 'static,
+{
+    let factory = gtk::SignalListItemFactory::new();
+    factory.connect_setup(move |_, item| {
+        if let Some(item) = item.downcast_ref::<gtk::ListItem>() {
+            item.set_child(Some(&setup(item)));
+        }
+    });
+
+    // Each bound item's "changed" handler, disconnected on unbind
+    let handlers: Rc<RefCell<HashMap<gtk::ListItem, glib::SignalHandlerId>>> = Rc::default();
+    let bind = Rc::new(bind);
+    factory.connect_bind(clone!(@strong handlers => move |_, item| {
+        let Some(item) = item.downcast_ref::<gtk::ListItem>() else {
+            return;
+        };
+        let (Some(widget), Some(object)) = (item.child().and_downcast::<W>(), item.item().and_downcast::<ModObject>()) else {
+            return;
+        };
+        bind(&widget, &object);
+
+        let weak_widget = widget.downgrade();
+        let handler = object.connect_changed(clone!(@strong bind => move |object| {
+            if let Some(widget) = weak_widget.upgrade() {
+                bind(&widget, object);
+            }
+        }));
+        handlers.borrow_mut().insert(item.clone(), handler);
+    }));
+    factory.connect_unbind(move |_, item| {
+        let Some(item) = item.downcast_ref::<gtk::ListItem>() else {
+            return;
+        };
+        let handler = handlers.borrow_mut().remove(item);
+        if let (Some(object), Some(handler)) = (item.item().and_downcast::<ModObject>(), handler) {
+            object.disconnect(handler);
+        }
+    });
+
+    let column = ColumnViewColumn::new(Some(title), Some(factory));
+    column.set_resizable(true);
+    if let Some(sort) = sort {
+        column.set_sorter(Some(&gtk::CustomSorter::new(move |a, b| {
+            match (a.downcast_ref::<ModObject>(), b.downcast_ref::<ModObject>()) {
+                (Some(a), Some(b)) => sort(a, b).into(),
+                _ => gtk::Ordering::Equal,
+            }
+        })));
+    }
+    column
+}
+
+fn label_column(
+    title: &str,
+    text: impl Fn(&ModInfo) -> String + 'static,
+    sort: impl Fn(&ModObject, &ModObject) -> Ordering + 'static,
+) -> ColumnViewColumn {
+    object_column(
+        title,
+        |_| {
+            let label = gtk::Label::new(None);
+            label.set_xalign(0.0);
+            label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+            label
+        },
+        move |label: &gtk::Label, object| label.set_text(&text(&object.mod_info())),
+        Some(sort),
+    )
+}
+
+/// Versions, marked when Nexus has a newer one.
+fn version_column() -> ColumnViewColumn {
+    object_column(
+        "Version",
+        |_| {
+            let label = gtk::Label::new(None);
+            label.set_xalign(0.0);
+            label
+        },
+        |label: &gtk::Label, object| {
+            let mod_info = object.mod_info();
+            match mod_info.latest_version.as_deref().filter(|_| mod_info.is_outdated()) {
+                Some(latest) => {
+                    label.set_text(&format!("{} → {}", mod_info.version, latest));
+                    label.set_tooltip_text(Some(&format!("Version {} is available on Nexus Mods", latest)));
+                    label.add_css_class("warning");
+                }
+                None => {
+                    label.set_text(&mod_info.version);
+                    label.set_tooltip_text(None);
+                    label.remove_css_class("warning");
+                }
+            }
+        },
+        Some(|a: &ModObject, b: &ModObject| compare_versions(&a.mod_info().version, &b.mod_info().version)),
+    )
+}
+
+/// Endorsement state, only meaningful for mods from Nexus.
+fn endorse_column() -> ColumnViewColumn {
+    object_column(
+        "",
+        |item| {
+            let button = gtk::Button::new();
+            button.add_css_class("flat");
+            button.set_valign(gtk::Align::Center);
+            button.connect_clicked(clone!(@weak item => move |button| {
+                if let Some(object) = item.item().and_downcast::<ModObject>() {
+                    endorse_clicked(button, &object);
+                }
+            }));
+            button
+        },
+        |button: &gtk::Button, object| update_endorse_button(button, &object.mod_info()),
+        None::<fn(&ModObject, &ModObject) -> Ordering>,
+    )
+}
+
+fn enabled_column() -> ColumnViewColumn {
+    object_column(
+        "Enabled",
+        |item| {
+            let switch = gtk::Switch::new();
+            switch.set_valign(gtk::Align::Center);
+            switch.connect_state_set(clone!(@weak item => @default-return glib::Propagation::Stop, move |switch, state| {
+                let Some(object) = item.item().and_downcast::<ModObject>() else {
+                    return glib::Propagation::Stop;
+                };
+                // Binding the switch or putting it back matches the record
+                if object.mod_info().enabled == state {
+                    return glib::Propagation::Stop;
+                }
+                let toggled = switch.ancestor(ModList::static_type())
+                    .and_downcast::<ModList>()
+                    .is_some_and(|mod_list| toggle_mod(&mod_list, &object, state));
+                if !toggled {
+                    switch.set_active(!state);
+                }
+                glib::Propagation::Stop
+            }));
+            switch
+        },
+        |switch: &gtk::Switch, object| {
+            let mod_info = object.mod_info();
+            switch.set_sensitive(mod_info.installed_path.is_some());
+            switch.set_active(mod_info.enabled);
+        },
+        Some(|a: &ModObject, b: &ModObject| a.mod_info().enabled.cmp(&b.mod_info().enabled)),
+    )
+}
+
+/// Enables or disables a mod like its switch does: incompatible mods are
+/// refused, and unmet dependencies are shown before anything is moved so
+/// the toggle can be cancelled, forced or fixed. Returns whether the mod
+/// was toggled right away.
+fn toggle_mod(mod_list: &ModList, object: &ModObject, state: bool) -> bool {
+    let mod_info = object.mod_info();
+    let mod_manager = match ModManager::new(Settings::load()) {
+        Ok(mod_manager) => mod_manager,
+        Err(e) => {
+            eprintln!("Failed to toggle mod state: {}", e);
+            return false;
+        }
+    };
+    let mods = list_mods(mod_list);
+
+    // Incompatible mods are refused before anything is moved
+    let violations = if state {
+        mod_manager.rule_violations_for_enable(&mods, mod_info.id)
+    } else {
+        Vec::new()
+    };
+    if violations.iter().any(RuleViolation::is_blocking) {
+        eprintln!("Refusing to enable mod: {}", mod_info.name);
+        let messages = violations.iter()
+            .filter(|violation| violation.is_blocking())
+            .map(ToString::to_string)
+            .collect();
+        show_toggle_warning(mod_list, gtk::MessageType::Error, messages, Vec::new());
+        return false;
+    }
+    let warnings: Vec<String> = violations.iter().map(ToString::to_string).collect();
+
+    let issues = mod_manager.dependency_issues(&mods, mod_info.id, state);
+    if issues.is_empty() {
+        return apply_toggle(mod_list, object, state, warnings);
+    }
+    confirm_dependency_issues(mod_list, state, &issues, warnings, clone!(@weak mod_list, @weak object => move || {
+        apply_toggle(&mod_list, &object, state, Vec::new());
+    }));
+    false
+}
+
+/// Backs up saves if needed, moves the mod's files and updates its record,
+/// then shows `warnings`. Returns whether the mod was toggled.
+fn apply_toggle(mod_list: &ModList, object: &ModObject, state: bool, warnings: Vec<String>) -> bool {
+    let mut mod_info = object.mod_info();
+    eprintln!("Toggling mod state: {} ({:?}) -> {}", mod_info.name, mod_info.installed_path, state);
+
+    let settings = Settings::load();
+    let mod_manager = match ModManager::new(settings.clone()) {
+        Ok(mod_manager) => mod_manager,
+        Err(e) => {
+            eprintln!("Failed to toggle mod state: {}", e);
+            return false;
+        }
+    };
+    // Convert to absolute path if needed
+    if let Some(path) = mod_info.installed_path.as_ref().filter(|path| !path.is_absolute()) {
+        mod_info.installed_path = Some(settings.game_path.clone().unwrap_or_default().join(path));
+    }
+
+    // Keep a way back to saves made without a save-breaking mod
+    if state {
+        if let Err(e) = mod_manager.backup_before_enable(&mod_info, &list_mods(mod_list)) {
+            eprintln!("Failed to back up saves: {}", e);
+            let messages = vec![format!("The saves could not be backed up, so the mod was left disabled: {}", e)];
+            show_toggle_warning(mod_list, gtk::MessageType::Error, messages, Vec::new());
+            return false;
+        }
+    }
+
+    if let Err(e) = mod_manager.set_mod_enabled(&mod_info, state) {
+        eprintln!("Failed to toggle mod state: {} (path: {:?})", e, mod_info.installed_path);
+        return false;
+    }
+    mod_info.set_enabled(state);
+    // Enabled paks are deployed, the record keeps pointing at the store
+    if let Some(file_name) = mod_info.installed_path.as_deref()
+        .filter(|_| mod_info.kind == ModKind::Pak)
+        .and_then(|path| path.file_name())
+    {
+        mod_info.installed_path = Some(mod_manager.pak_path(file_name));
+    }
+    object.set_mod_info(mod_info);
+
+    if !warnings.is_empty() {
+        show_toggle_warning(mod_list, gtk::MessageType::Warning, warnings, Vec::new());
+    }
+    true
+}
+
+/// Asks whether to go ahead with a toggle that leaves dependencies unmet.
+/// `proceed` does the toggle; "Fix" also applies the fixes afterwards.
+/// `warnings` are shown along with the issues.
+pub fn confirm_dependency_issues(
+    mod_list: &ModList,
+    enabling: bool,
+    issues: &[DependencyIssue],
+    warnings: Vec<String>,
+    proceed: impl Fn() + 'static,
+) {
+    let fixes: Vec<DependencyFix> = issues.iter().filter_map(DependencyIssue::fix).collect();
+    let mut messages: Vec<String> = issues.iter()
+        .map(ToString::to_string)
+        .chain(warnings)
+        .collect();
+    messages.dedup();
+
+    let window = mod_list.root().and_downcast::<gtk::Window>();
+    let dialog = gtk::MessageDialog::new(
+        window.as_ref(),
+        gtk::DialogFlags::MODAL,
+        gtk::MessageType::Warning,
+        gtk::ButtonsType::None,
+        messages.join("\n"),
+    );
+    dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+    dialog.add_button(if enabling { "Enable Anyway" } else { "Disable Anyway" }, gtk::ResponseType::Yes);
+    if !fixes.is_empty() {
+        dialog.add_button("Fix", gtk::ResponseType::Accept);
+        dialog.set_default_response(gtk::ResponseType::Accept);
+    }
+
+    dialog.connect_response(clone!(@weak mod_list => move |dialog, response| {
+        dialog.close();
+        match response {
+            gtk::ResponseType::Yes => proceed(),
+            gtk::ResponseType::Accept => {
+                proceed();
+                apply_dependency_fixes(&mod_list, &fixes);
+            }
+            _ => {}
+        }
+    }));
+
+    dialog.present();
+}
+
+/// Shows problems found when toggling mods, offering `fixes` if any.
+pub fn show_toggle_warning(
+    mod_list: &ModList,
+    message_type: gtk::MessageType,
+    messages: Vec<String>,
+    fixes: Vec<DependencyFix>,
+) {
+    let window = mod_list.root().and_downcast::<gtk::Window>();
+    let dialog = gtk::MessageDialog::new(
+        window.as_ref(),
+        gtk::DialogFlags::MODAL,
+        message_type,
+        gtk::ButtonsType::None,
+        messages.join("\n"),
+    );
+    if fixes.is_empty() {
+        dialog.add_button("Close", gtk::ResponseType::Cancel);
+    } else {
+        dialog.add_button("Ignore", gtk::ResponseType::Cancel);
+        dialog.add_button("Fix", gtk::ResponseType::Accept);
+        dialog.set_default_response(gtk::ResponseType::Accept);
+    }
+
+    dialog.connect_response(clone!(@weak mod_list => move |dialog, response| {
+        dialog.close();
+        if response == gtk::ResponseType::Accept {
+            apply_dependency_fixes(&mod_list, &fixes);
+        }
+    }));
+
+    dialog.present();
+}
+
+/// Toggles the affected mods so each fix goes through the same checks as
+/// a manual toggle, and offers to install missing mods.
+fn apply_dependency_fixes(mod_list: &ModList, fixes: &[DependencyFix]) {
+    let mut missing = Vec::new();
+    for fix in fixes {
+        let (id, enable) = match fix {
+            DependencyFix::Enable(id) => (*id, true),
+            DependencyFix::Disable(id) => (*id, false),
+            DependencyFix::Install(mod_id) => {
+                missing.push(*mod_id);
+                continue;
+            }
+        };
+
+        if let Some(object) = find_mod_object(mod_list, id).filter(|object| object.mod_info().enabled != enable) {
+            toggle_mod(mod_list, &object, enable);
+        }
+    }
+
+    if !missing.is_empty() {
+        if let Some(window) = mod_list.root().and_downcast::<gtk::Window>() {
+            crate::install_dialog::show_install_dialog_with_ids(&window, mod_list, &missing);
+        }
+    }
+}
+
+fn endorse_clicked(button: &gtk::Button, object: &ModObject) {
+    let mod_info = object.mod_info();
+    let (Some(mod_id), Some(client)) = (
+        mod_info.nexus_mod_id,
+        ModManager::new(Settings::load())
+            .ok()
+            .and_then(|mod_manager| mod_manager.nexus_client().cloned()),
+    ) else {
+        return;
+    };
+
+    let endorse = mod_info.endorsement != Some(EndorsementStatus::Endorsed);
+    let version = mod_info.version.clone();
+    button.set_sensitive(false);
+
+    let receiver = runtime::spawn(async move {
+        if endorse {
+            client.endorse_mod(mod_id, &version).await
+        } else {
+            client.abstain_mod(mod_id, &version).await
+        }
+    });
+    glib::spawn_future_local(clone!(@weak object, @weak button => async move {
+        match receiver.recv().await {
+            Ok(Ok(())) => object.update(|mod_info| {
+                mod_info.endorsement = Some(if endorse {
+                    EndorsementStatus::Endorsed
+                } else {
+                    EndorsementStatus::Abstained
+                });
+            }),
+            Ok(Err(e)) => eprintln!("Failed to change endorsement: {}", e),
+            Err(_) => {}
+        }
+        button.set_sensitive(true);
+    }));
+}
+
+fn update_endorse_button(button: &gtk::Button, mod_info: &ModInfo) {
+    if mod_info.nexus_mod_id.is_none() {
+        // Keep the column aligned for local mods
+        button.set_opacity(0.0);
+        button.set_sensitive(false);
+        button.set_icon_name("non-starred-symbolic");
+        button.set_tooltip_text(None);
+        return;
+    }
+
+    let (icon, tooltip) = match mod_info.endorsement {
+        Some(EndorsementStatus::Endorsed) => ("starred-symbolic", "Endorsed (click to abstain)"),
+        Some(EndorsementStatus::Abstained) => ("non-starred-symbolic", "Abstained (click to endorse)"),
+        _ => ("non-starred-symbolic", "Endorse on Nexus Mods"),
+    };
+    button.set_opacity(1.0);
+    button.set_sensitive(true);
+    button.set_icon_name(icon);
+    button.set_tooltip_text(Some(tooltip));
+}
+
+fn dim_label(xalign: f32) -> gtk::Label {
+    let label = gtk::Label::new(None);
+    label.set_xalign(xalign);
+    label.add_css_class("dim-label");
+    label
+}
+
+fn format_date(unix: u64) -> String {
+    glib::DateTime::from_unix_local(unix as i64)
+        .and_then(|time| time.format("%Y-%m-%d"))
+        .map(|text| text.to_string())
+        .unwrap_or_default()
+}
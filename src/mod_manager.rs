@@ -1,8 +1,25 @@
 use std::path::{Path, PathBuf};
 use anyhow::Result;
-use crate::nexus_api::NexusClient;
+use crate::nexus_api::{NexusClient, TrackedMod};
 use crate::settings::Settings;
-use crate::mod_info::ModInfo;
+use crate::mod_info::{unix_now, ModId, ModInfo, ModKind};
+use crate::ue4ss::{self, ArchiveKind};
+use crate::file_deploy::{self, DeployRoots};
+use crate::ini_tweaks::{self, ConfigFile, IniTweak, TweakManifest};
+use crate::dependencies::{self, DependencyIssue};
+use crate::rules::{self, RuleViolation};
+use crate::load_order::{self, LoadOrderPlan};
+use crate::pak;
+use crate::user_data;
+use crate::journal::{self, Recovery, Rename};
+use crate::bisect::{BisectSession, BisectState};
+use crate::game_logs::{self, LogSource, ModIndex};
+use crate::launcher::{self, LaunchCommand, LaunchIssue, Platform};
+use crate::profiles::Profiles;
+use crate::purge::PurgeState;
+use crate::reconcile::{Change, Reconciliation};
+use crate::saves::{self, Snapshot, SnapshotMod};
+use crate::staging;
 use std::fs;
 use serde_json;
 use zip::{ZipWriter, write::FileOptions};
@@ -12,30 +29,36 @@ pub struct ModManager {
     settings: Settings,
     nexus_client: Option<NexusClient>,
     mods_path: PathBuf,
-    unloaded_mods_path: PathBuf,
+    staging_path: PathBuf,
+    ue4ss_path: PathBuf,
 }
 
 impl ModManager {
     pub fn new(settings: Settings) -> Result<Self> {
         let nexus_client = if let Some(api_key) = &settings.nexus_api_key {
-            Some(NexusClient::new(api_key)?)
+            Some(NexusClient::builder(api_key)
+                .preferred_cdn(settings.preferred_cdn.as_deref())
+                .build()?)
         } else {
             None
         };
 
         let game_path = settings.game_path.clone().unwrap_or_else(|| PathBuf::new());
         let mods_path = game_path.join("Stalker2").join("Content").join("Paks").join("~mods");
-        let unloaded_mods_path = game_path.join("Stalker2").join("ModManager").join("unloaded_mods");
+        let staging_path = settings.mod_storage_path.clone()
+            .unwrap_or_else(|| default_staging_path(&game_path));
+        let ue4ss_path = game_path.join("Stalker2").join("Binaries").join("Win64").join("ue4ss");
 
         // Create both directories if they don't exist
         std::fs::create_dir_all(&mods_path)?;
-        std::fs::create_dir_all(&unloaded_mods_path)?;
+        std::fs::create_dir_all(&staging_path)?;
 
         Ok(Self {
             settings,
             nexus_client,
             mods_path,
-            unloaded_mods_path,
+            staging_path,
+            ue4ss_path,
         })
     }
 
@@ -55,14 +78,13 @@ impl ModManager {
         // Download and save the mod
         let mod_data = client.download_mod(mod_id, file_id, None).await?;
         
-        // Ensure mods directory exists
-        std::fs::create_dir_all(&self.mods_path)?;
+        // Write mod file to the store and deploy it
+        std::fs::create_dir_all(&self.staging_path)?;
+        self.check_pak_name_free(std::ffi::OsStr::new(&file.file_name))?;
+        let mod_path = self.staging_path.join(&file.file_name);
+        std::fs::write(&mod_path, mod_data)?;
 
-        // Write mod file
-        let mod_path = self.mods_path.join(&file.file_name);
-        std::fs::write(mod_path, mod_data)?;
-
-        Ok(())
+        self.enable_mod(&mod_path)
     }
 
     pub fn get_installed_mods(&self) -> Result<Vec<PathBuf>> {
@@ -91,9 +113,9 @@ impl ModManager {
         }
 
         // Check unloaded mods directory for untracked mods
-        if self.unloaded_mods_path.exists() {
+        if self.staging_path.exists() {
             mods.extend(
-                std::fs::read_dir(&self.unloaded_mods_path)?
+                std::fs::read_dir(&self.staging_path)?
                     .filter_map(|entry| entry.ok())
                     .map(|entry| entry.path())
                     .filter(|path| {
@@ -112,28 +134,17 @@ impl ModManager {
         let file_name = mod_path.file_name()
             .ok_or_else(|| anyhow::anyhow!("Invalid mod file name"))?;
         
-        // Get absolute paths
-        let game_path = self.settings.game_path.clone()
-            .ok_or_else(|| anyhow::anyhow!("Game path not set"))?;
-        
-        // Check if the file is in the unloaded mods directory
-        let unloaded_path = self.unloaded_mods_path.join(file_name);
-        let enabled_path = self.mods_path.join(file_name);
-        
-        eprintln!("Checking unloaded path: {:?}", unloaded_path);
-        eprintln!("Checking enabled path: {:?}", enabled_path);
-        
         // If it's already in the enabled directory, nothing to do
-        if enabled_path.exists() {
+        if self.mods_path.join(file_name).exists() {
             eprintln!("Mod is already enabled");
             return Ok(());
         }
         
-        // If it's in the unloaded directory, move it
-        if unloaded_path.exists() {
-            std::fs::create_dir_all(&self.mods_path)?;
-            std::fs::rename(&unloaded_path, &enabled_path)?;
-            eprintln!("Moved mod from unloaded to enabled directory");
+        // If it's in the store, deploy it with its companions
+        if self.staging_path.join(file_name).exists() {
+            let renames = self.deploy_renames(file_name)?;
+            self.run_journal(renames, None)?;
+            eprintln!("Deployed mod from the store");
             return Ok(());
         }
         
@@ -146,30 +157,286 @@ impl ModManager {
         let file_name = mod_path.file_name()
             .ok_or_else(|| anyhow::anyhow!("Invalid mod file name"))?;
         
-        // Check if the file is in the enabled mods directory
-        let unloaded_path = self.unloaded_mods_path.join(file_name);
-        let enabled_path = self.mods_path.join(file_name);
-        
-        eprintln!("Checking unloaded path: {:?}", unloaded_path);
-        eprintln!("Checking enabled path: {:?}", enabled_path);
-        
-        // If it's already in the unloaded directory, nothing to do
-        if unloaded_path.exists() {
-            eprintln!("Mod is already disabled");
+        // If it's in the enabled directory, take it out with its companions
+        if self.mods_path.join(file_name).exists() {
+            let renames = self.undeploy_renames(file_name)?;
+            self.run_journal(renames, None)?;
+            eprintln!("Removed mod from the enabled directory");
             return Ok(());
         }
-        
-        // If it's in the enabled directory, move it
-        if enabled_path.exists() {
-            std::fs::create_dir_all(&self.unloaded_mods_path)?;
-            std::fs::rename(&enabled_path, &unloaded_path)?;
-            eprintln!("Moved mod from enabled to unloaded directory");
+
+        // If it's only in the store, nothing to do
+        if self.staging_path.join(file_name).exists() {
+            eprintln!("Mod is already disabled");
             return Ok(());
         }
         
         Err(anyhow::anyhow!("Mod file not found in expected locations"))
     }
 
+    /// Deploys a stored pak and its companions into `~mods` under temporary
+    /// names, returning the renames that put them in place.
+    fn deploy_renames(&self, file_name: &std::ffi::OsStr) -> Result<Vec<Rename>> {
+        let mut renames = Vec::new();
+        for name in pak_files(&self.staging_path, file_name) {
+            let partial = self.mods_path.join(partial_name(&name));
+            if partial.exists() {
+                fs::remove_file(&partial)?;
+            }
+            if let Err(e) = staging::deploy_file(&self.staging_path.join(&name), &partial, self.settings.deploy_method) {
+                remove_partials(&renames);
+                return Err(anyhow::Error::from(e).context(format!("Failed to deploy {}", name.display())));
+            }
+            renames.push(Rename { from: partial, to: self.mods_path.join(&name) });
+        }
+        Ok(renames)
+    }
+
+    /// Takes a pak and its companions out of `~mods`. Deployed files go to
+    /// the trash, emptied once the transaction is done; files that were
+    /// never stored go to the store. A file changed by hand never replaces
+    /// the stored copy: it is kept next to it as `<name>.modified.pak`.
+    fn undeploy_renames(&self, file_name: &std::ffi::OsStr) -> Result<Vec<Rename>> {
+        pak_files(&self.mods_path, file_name).into_iter()
+            .map(|name| {
+                let deployed = self.mods_path.join(&name);
+                let stored = self.staging_path.join(&name);
+                let to = if !stored.exists() {
+                    stored
+                } else if staging::is_deployment_of(&deployed, &stored)?
+                    // A link to anything else holds no data of its own
+                    || fs::symlink_metadata(&deployed)?.file_type().is_symlink()
+                {
+                    self.trash_path().join(&name)
+                } else {
+                    let kept = modified_name(&self.staging_path, &name);
+                    eprintln!("{} was changed by hand, keeping it as {}", name.display(), kept.display());
+                    kept
+                };
+                Ok(Rename { from: deployed, to })
+            })
+            .collect()
+    }
+
+    /// Runs a journal transaction, then empties the trash or, if it failed,
+    /// removes deployments that never made it into place.
+    fn run_journal(&self, renames: Vec<Rename>, mod_list: Option<&[ModInfo]>) -> Result<()> {
+        let result = journal::run(&self.journal_path(), renames.clone(), &self.mod_list_path(), mod_list);
+        // A journal left behind still needs the trash to recover
+        if !self.journal_path().exists() {
+            let _ = fs::remove_dir_all(self.trash_path());
+        }
+        if result.is_err() {
+            remove_partials(&renames);
+        }
+        result
+    }
+
+    /// Enables or disables a mod of any kind. Paks are deployed from the
+    /// store into `~mods`, UE4SS mods are toggled in `mods.txt`.
+    pub fn set_mod_enabled(&self, mod_info: &ModInfo, enabled: bool) -> Result<()> {
+        let mod_path = mod_info.installed_path.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("{} has no installed files", mod_info.name))?;
+        match mod_info.kind {
+            ModKind::Pak if enabled => self.enable_mod(mod_path),
+            ModKind::Pak => self.disable_mod(mod_path),
+            ModKind::Ue4ss => ue4ss::set_runtime_enabled(&self.ue4ss_path, enabled),
+            ModKind::Lua => {
+                let name = mod_path.file_name()
+                    .ok_or_else(|| anyhow::anyhow!("Invalid mod folder name"))?;
+                ue4ss::set_mod_enabled(&self.ue4ss_mods_path(), &name.to_string_lossy(), enabled)
+            }
+            ModKind::Files if enabled => file_deploy::deploy(mod_path, &self.deploy_roots()),
+            ModKind::Files => file_deploy::undeploy(mod_path, &self.deploy_roots()),
+            ModKind::IniTweak => {
+                let config_dir = self.user_config_path()
+                    .ok_or_else(|| anyhow::anyhow!("The game's user config folder was not found"))?;
+                if enabled {
+                    ini_tweaks::apply(mod_path, &config_dir)
+                } else {
+                    ini_tweaks::revert(mod_path, &config_dir)
+                }
+            }
+        }
+    }
+
+    pub fn is_enabled_on_disk(&self, mod_info: &ModInfo) -> bool {
+        let Some(mod_path) = mod_info.installed_path.as_deref() else {
+            return false;
+        };
+        match mod_info.kind {
+            ModKind::Pak => self.is_mod_enabled(mod_path),
+            ModKind::Ue4ss => ue4ss::is_runtime_enabled(&self.ue4ss_path),
+            ModKind::Lua => mod_path.file_name().is_some_and(|name| {
+                ue4ss::is_mod_enabled(&self.ue4ss_mods_path(), &name.to_string_lossy())
+            }),
+            ModKind::Files => file_deploy::is_deployed(mod_path),
+            ModKind::IniTweak => ini_tweaks::is_applied(mod_path),
+        }
+    }
+
+    /// Installs a UE4SS release or UE4SS mods from a zip, returning the new
+    /// list entries. Other archives give `None` so the caller can look for paks.
+    pub fn install_ue4ss_archive(&self, archive_path: &Path) -> Result<Option<Vec<ModInfo>>> {
+        match ue4ss::classify_archive(archive_path)? {
+            ArchiveKind::Runtime => {
+                ue4ss::install_runtime(archive_path, &self.ue4ss_path)?;
+                Ok(Some(vec![self.ue4ss_mod_info()]))
+            }
+            ArchiveKind::Mods => {
+                let mods_path = self.ue4ss_mods_path();
+                let mut installed = Vec::new();
+                for name in ue4ss::install_mods(archive_path, &mods_path)? {
+                    ue4ss::set_mod_enabled(&mods_path, &name, true)?;
+                    installed.push(ModInfo {
+                        name: name.clone(),
+                        version: "Unknown".to_string(),
+                        author: "Unknown".to_string(),
+                        installed_path: Some(mods_path.join(&name)),
+                        enabled: true,
+                        kind: ModKind::Lua,
+                        ..Default::default()
+                    });
+                }
+                Ok(Some(installed))
+            }
+            ArchiveKind::Other => Ok(None),
+        }
+    }
+
+    /// Installs and deploys an archive of loose files such as DLL loaders or
+    /// config tweaks. Archives containing paks give `None`, they are pak mods.
+    pub fn install_file_mod(&self, archive_path: &Path) -> Result<Option<ModInfo>> {
+        let archive = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+        let names: Vec<PathBuf> = archive.file_names()
+            .filter(|name| !name.ends_with('/'))
+            .map(PathBuf::from)
+            .collect();
+        if names.iter().any(|name| name.extension().is_some_and(|ext| ext == "pak")) {
+            return Ok(None);
+        }
+        let mappings = file_deploy::guess_mappings(&names);
+        if mappings.is_empty() {
+            return Ok(None);
+        }
+
+        let name = archive_path.file_stem()
+            .ok_or_else(|| anyhow::anyhow!("Invalid archive name"))?
+            .to_string_lossy()
+            .to_string();
+        let store = self.files_store_path().join(&name);
+        if store.exists() {
+            // Reinstalling replaces the previous copy
+            if file_deploy::is_deployed(&store) {
+                file_deploy::undeploy(&store, &self.deploy_roots())?;
+            }
+            fs::remove_dir_all(&store)?;
+        }
+        fs::create_dir_all(&store)?;
+
+        let manifest = file_deploy::store_archive(archive_path, &store, mappings)?;
+        if let Err(e) = file_deploy::deploy(&store, &self.deploy_roots()) {
+            let _ = file_deploy::undeploy(&store, &self.deploy_roots());
+            let _ = fs::remove_dir_all(&store);
+            return Err(e);
+        }
+
+        Ok(Some(ModInfo {
+            name,
+            version: "Unknown".to_string(),
+            author: "Unknown".to_string(),
+            installed_path: Some(store),
+            enabled: true,
+            kind: ModKind::Files,
+            file_mappings: manifest.mappings,
+            ..Default::default()
+        }))
+    }
+
+    /// Destinations of `Files` mods.
+    pub fn deploy_roots(&self) -> DeployRoots {
+        let game_root = self.settings.game_path.clone().unwrap_or_default();
+        DeployRoots {
+            win64: game_root.join("Stalker2").join("Binaries").join("Win64"),
+            user_config: self.user_config_path(),
+            game_root,
+        }
+    }
+
+    /// Saves a set of INI entries as a tweak mod and applies it.
+    pub fn create_tweak_mod(&self, name: &str, tweaks: Vec<IniTweak>) -> Result<ModInfo> {
+        let file_name: String = name.chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let path = self.tweaks_store_path().join(format!("{}.json", file_name));
+        if path.exists() {
+            return Err(anyhow::anyhow!("A tweak named {} already exists", name));
+        }
+
+        ini_tweaks::save(&path, &TweakManifest { tweaks: tweaks.clone(), applied: Vec::new() })?;
+        let mod_info = ModInfo {
+            name: name.to_string(),
+            version: "1.0".to_string(),
+            author: "You".to_string(),
+            installed_path: Some(path.clone()),
+            enabled: true,
+            kind: ModKind::IniTweak,
+            ini_tweaks: tweaks,
+            ..Default::default()
+        };
+        if let Err(e) = self.set_mod_enabled(&mod_info, true) {
+            let _ = fs::remove_file(&path);
+            return Err(e);
+        }
+        Ok(mod_info)
+    }
+
+    pub fn config_file_path(&self, file: ConfigFile) -> Option<PathBuf> {
+        self.user_config_path().map(|dir| dir.join(file.file_name()))
+    }
+
+    pub fn tweaks_store_path(&self) -> PathBuf {
+        self.settings.game_path.clone()
+            .unwrap_or_default()
+            .join("Stalker2")
+            .join("ModManager")
+            .join("tweaks")
+    }
+
+    /// The game's `AppData/Local/Stalker2` folder holding saves, config and
+    /// logs: the one set in settings, otherwise detected from the game path.
+    pub fn user_data_path(&self) -> Option<PathBuf> {
+        self.settings.user_data_path.clone()
+            .or_else(|| user_data::detect(self.settings.game_path.as_deref()?))
+    }
+
+    /// The game's `Saved/Config/Windows` folder (`WinGDK` for the Game Pass
+    /// version).
+    pub fn user_config_path(&self) -> Option<PathBuf> {
+        user_data::config_dir(&self.user_data_path()?)
+    }
+
+    pub fn files_store_path(&self) -> PathBuf {
+        self.settings.game_path.clone()
+            .unwrap_or_default()
+            .join("Stalker2")
+            .join("ModManager")
+            .join("files")
+    }
+
+    fn ue4ss_mod_info(&self) -> ModInfo {
+        ModInfo {
+            name: ue4ss::NAME.to_string(),
+            version: "Unknown".to_string(),
+            author: "UE4SS Team".to_string(),
+            description: "Unreal Engine scripting system that loads Lua and C++ mods.".to_string(),
+            installed_path: Some(self.ue4ss_path.clone()),
+            enabled: ue4ss::is_runtime_enabled(&self.ue4ss_path),
+            kind: ModKind::Ue4ss,
+            ..Default::default()
+        }
+    }
+
     pub fn nexus_client(&self) -> Option<&NexusClient> {
         self.nexus_client.as_ref()
     }
@@ -178,12 +445,427 @@ impl ModManager {
         &self.mods_path
     }
 
+    pub fn profiles_path(&self) -> PathBuf {
+        self.settings.game_path.clone()
+            .unwrap_or_default()
+            .join("Stalker2")
+            .join("ModManager")
+            .join("profiles.json")
+    }
+
+    pub fn saves_path(&self) -> Option<PathBuf> {
+        Some(user_data::saves_dir(&self.user_data_path()?))
+    }
+
+    /// Save snapshots are kept with the manager's own data so they survive
+    /// reinstalling the game.
+    pub fn save_backups_path(&self) -> Result<PathBuf> {
+        directories::ProjectDirs::from("", "", "Stalker2ModManager")
+            .map(|dirs| dirs.data_dir().join("save_backups"))
+            .ok_or_else(|| anyhow::anyhow!("No data folder for save backups"))
+    }
+
+    /// Snapshots the save folder together with the current mod list.
+    pub fn backup_saves(&self, reason: &str, mods: &[ModInfo]) -> Result<Snapshot> {
+        let saves_path = self.saves_path()
+            .ok_or_else(|| anyhow::anyhow!("The game's user data folder was not found"))?;
+        let snapshot = Snapshot {
+            path: PathBuf::new(),
+            created: unix_now(),
+            reason: reason.to_string(),
+            profile: Profiles::load(&self.profiles_path())?.active,
+            mods: mods.iter()
+                .map(|mod_info| SnapshotMod {
+                    name: mod_info.name.clone(),
+                    version: mod_info.version.clone(),
+                    enabled: mod_info.enabled,
+                })
+                .collect(),
+        };
+        saves::create(&saves_path, &self.save_backups_path()?, snapshot)
+    }
+
+    /// Backs up the current saves, then replaces them with a snapshot.
+    pub fn restore_saves(&self, snapshot: &Snapshot, mods: &[ModInfo]) -> Result<()> {
+        let saves_path = self.saves_path()
+            .ok_or_else(|| anyhow::anyhow!("The game's user data folder was not found"))?;
+        if saves_path.is_dir() {
+            self.backup_saves("Before restoring a backup", mods)?;
+        }
+        saves::restore(&snapshot.path, &saves_path)
+    }
+
+    /// Backs up the saves when enabling `mod_info` may break them and the
+    /// user asked for automatic backups.
+    pub fn backup_before_enable(&self, mod_info: &ModInfo, mods: &[ModInfo]) -> Result<()> {
+        if mod_info.save_breaking && self.settings.auto_backup_saves {
+            self.backup_saves(&format!("Before enabling {}", mod_info.name), mods)?;
+        }
+        Ok(())
+    }
+
+    /// Enables the mods `wanted` picks and disables the rest as one
+    /// transaction, saving the mod list with them. Paks are deployed and
+    /// removed through the journal; other kinds are toggled first and
+    /// toggled back if anything fails. `mods` is only updated on success.
+    pub fn apply_enabled(&self, mods: &mut [ModInfo], wanted: impl Fn(&ModInfo) -> bool) -> Result<()> {
+        let changes: Vec<usize> = (0..mods.len())
+            .filter(|&index| mods[index].installed_path.is_some() && wanted(&mods[index]) != mods[index].enabled)
+            .collect();
+
+        let mut toggled: Vec<usize> = Vec::new();
+        let mut renames = Vec::new();
+        // Disabling first so nothing clashes with the mods being enabled
+        for enable in [false, true] {
+            for &index in changes.iter().filter(|&&index| mods[index].enabled != enable) {
+                let mod_info = &mods[index];
+                let Some(path) = mod_info.installed_path.as_deref() else {
+                    continue;
+                };
+                let result = if mod_info.kind == ModKind::Pak {
+                    path.file_name()
+                        .ok_or_else(|| anyhow::anyhow!("Invalid mod file name"))
+                        .and_then(|file_name| if enable {
+                            self.deploy_renames(file_name)
+                        } else {
+                            self.undeploy_renames(file_name)
+                        })
+                        .map(|pak_renames| renames.extend(pak_renames))
+                } else {
+                    self.set_mod_enabled(mod_info, enable)
+                        .map(|()| toggled.push(index))
+                };
+                if let Err(e) = result {
+                    remove_partials(&renames);
+                    self.undo_toggles(mods, &toggled);
+                    return Err(e);
+                }
+            }
+        }
+
+        let mut updated = mods.to_vec();
+        for &index in &changes {
+            let enable = !updated[index].enabled;
+            updated[index].set_enabled(enable);
+        }
+        if let Err(e) = self.run_journal(renames, Some(&updated)) {
+            self.undo_toggles(mods, &toggled);
+            return Err(e);
+        }
+        mods.clone_from_slice(&updated);
+        Ok(())
+    }
+
+    /// Puts mods toggled by a failed `apply_enabled` back, as far as possible.
+    fn undo_toggles(&self, mods: &[ModInfo], toggled: &[usize]) {
+        for &index in toggled.iter().rev() {
+            let mod_info = &mods[index];
+            if let Err(e) = self.set_mod_enabled(mod_info, mod_info.enabled) {
+                eprintln!("Failed to restore {}: {}", mod_info.name, e);
+            }
+        }
+    }
+
+    /// Enables or disables the mods with the given IDs together, deploying
+    /// every pak in one transaction. See `apply_enabled`.
+    pub fn set_mods_enabled(&self, mods: &mut [ModInfo], ids: &[ModId], enabled: bool) -> Result<()> {
+        self.apply_enabled(mods, |mod_info| {
+            if ids.contains(&mod_info.id) {
+                enabled
+            } else {
+                mod_info.enabled
+            }
+        })
+    }
+
+    /// Removes the mods with the given IDs: their files go to the trash and
+    /// their entries leave the mod list, in one transaction. Enabled mods
+    /// that aren't paks are disabled first and enabled again if anything
+    /// fails. `mods` is only updated on success.
+    pub fn uninstall_mods(&self, mods: &mut Vec<ModInfo>, ids: &[ModId]) -> Result<()> {
+        let selected: Vec<usize> = (0..mods.len())
+            .filter(|&index| ids.contains(&mods[index].id))
+            .collect();
+        if let Some(&index) = selected.iter().find(|&&index| mods[index].kind == ModKind::Ue4ss) {
+            return Err(anyhow::anyhow!("{} can't be uninstalled from the mod manager, only disabled", mods[index].name));
+        }
+
+        let mut toggled: Vec<usize> = Vec::new();
+        let mut renames = Vec::new();
+        for &index in &selected {
+            let mod_info = &mods[index];
+            let Some(path) = mod_info.installed_path.as_deref() else {
+                continue;
+            };
+            // Each mod gets its own folder, file names may repeat
+            let trash = self.trash_path().join("uninstalled").join(index.to_string());
+            if mod_info.kind == ModKind::Pak {
+                let Some(file_name) = path.file_name() else {
+                    self.undo_toggles(mods, &toggled);
+                    return Err(anyhow::anyhow!("Invalid mod file name"));
+                };
+                for (dir, folder) in [(&self.mods_path, "deployed"), (&self.staging_path, "stored")] {
+                    for name in pak_files(dir, file_name) {
+                        renames.push(Rename { from: dir.join(&name), to: trash.join(folder).join(&name) });
+                    }
+                }
+                continue;
+            }
+
+            if mod_info.enabled {
+                if let Err(e) = self.set_mod_enabled(mod_info, false) {
+                    self.undo_toggles(mods, &toggled);
+                    return Err(e);
+                }
+                toggled.push(index);
+            }
+            if let Some(name) = path.file_name().filter(|_| path.exists()) {
+                renames.push(Rename { from: path.to_path_buf(), to: trash.join(name) });
+            }
+        }
+
+        let remaining: Vec<ModInfo> = mods.iter()
+            .filter(|mod_info| !ids.contains(&mod_info.id))
+            .cloned()
+            .collect();
+        if let Err(e) = self.run_journal(renames, Some(&remaining)) {
+            self.undo_toggles(mods, &toggled);
+            return Err(e);
+        }
+        *mods = remaining;
+        Ok(())
+    }
+
+    /// Enables exactly the mods of a profile.
+    pub fn activate_profile(&self, name: &str, mods: &mut [ModInfo]) -> Result<()> {
+        let mut profiles = Profiles::load(&self.profiles_path())?;
+        let profile = profiles.get(name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No profile named {}", name))?;
+
+        if self.settings.auto_backup_saves && profiles.active.as_deref() != Some(name) {
+            self.backup_saves(&format!("Before activating profile {}", name), mods)?;
+        }
+
+        self.apply_enabled(mods, |mod_info| profile.wants_enabled(mod_info))?;
+
+        profiles.active = Some(name.to_string());
+        profiles.save(&self.profiles_path())
+    }
+
+    /// Adds mods to the ones a profile enables, without switching to it.
+    pub fn add_to_profile(&self, name: &str, ids: &[ModId]) -> Result<()> {
+        let mut profiles = Profiles::load(&self.profiles_path())?;
+        let profile = profiles.profiles.iter_mut()
+            .find(|profile| profile.name == name)
+            .ok_or_else(|| anyhow::anyhow!("No profile named {}", name))?;
+        for id in ids {
+            if !profile.mods.contains(id) {
+                profile.mods.push(*id);
+            }
+        }
+        profiles.save(&self.profiles_path())
+    }
+
+    /// Problems worth knowing about before starting the game: an invalid
+    /// game path, broken rules, unmet dependencies and mods whose files
+    /// don't match their switch.
+    pub fn pre_launch_issues(&self, mods: &[ModInfo]) -> Vec<LaunchIssue> {
+        let mut issues = launcher::check_game_path(&self.settings.launch_method, self.settings.game_path.as_deref());
+        if issues.iter().any(|issue| issue.blocking) {
+            return issues;
+        }
+
+        for mod_info in mods.iter().filter(|mod_info| mod_info.installed_path.is_some()) {
+            match (mod_info.enabled, self.is_enabled_on_disk(mod_info)) {
+                (true, false) => issues.push(LaunchIssue::warning(format!("{} is enabled but not deployed", mod_info.name))),
+                (false, true) => issues.push(LaunchIssue::warning(format!("{} is disabled but still deployed", mod_info.name))),
+                _ => {}
+            }
+        }
+
+        let on_disk = self.mods_on_disk(mods);
+        issues.extend(rules::validate(&on_disk).iter().map(|violation| LaunchIssue::warning(violation.to_string())));
+        issues.extend(dependencies::check_all(&on_disk).iter().map(|issue| LaunchIssue::warning(issue.to_string())));
+        issues
+    }
+
+    pub fn launch_command(&self) -> Result<LaunchCommand> {
+        launcher::build_command(
+            &self.settings.launch_method,
+            &self.settings.game_path.clone().unwrap_or_default(),
+            Platform::current(),
+        )
+    }
+
+    pub fn game_logs(&self) -> Vec<LogSource> {
+        self.user_data_path()
+            .map(|user_data| game_logs::find_logs(&user_data))
+            .unwrap_or_default()
+    }
+
+    /// Indexes the pak names and assets of the pak mods in `mods`, enabled
+    /// or not, to find them in logs.
+    pub fn log_index(&self, mods: &[ModInfo]) -> ModIndex {
+        let mut index = ModIndex::default();
+        for (mod_index, mod_info) in mods.iter().enumerate() {
+            if mod_info.kind != ModKind::Pak {
+                continue;
+            }
+            let Some(file_name) = mod_info.installed_path.as_deref().and_then(Path::file_name) else {
+                continue;
+            };
+            let pak_path = [&self.mods_path, &self.staging_path].iter()
+                .map(|dir| dir.join(file_name))
+                .find(|path| path.exists());
+            let assets = pak_path
+                .and_then(|path| pak::list_assets(&path).ok())
+                .unwrap_or_default();
+            let stem = Path::new(file_name).file_stem().unwrap_or_default().to_string_lossy();
+            index.add(mod_index, &stem, &assets);
+        }
+        index
+    }
+
+    pub fn bisect_path(&self) -> PathBuf {
+        self.settings.game_path.clone()
+            .unwrap_or_default()
+            .join("Stalker2")
+            .join("ModManager")
+            .join("bisect.json")
+    }
+
+    pub fn load_bisect(&self) -> Result<Option<BisectSession>> {
+        BisectSession::load(&self.bisect_path())
+    }
+
+    /// Starts a search with the enabled mods as suspects and enables the
+    /// first half to test.
+    pub fn start_bisect(&self, mods: &mut [ModInfo]) -> Result<BisectSession> {
+        let session = BisectSession::start(mods)?;
+        session.save(&self.bisect_path())?;
+        self.apply_enabled(mods, |mod_info| session.is_testing(mod_info))?;
+        Ok(session)
+    }
+
+    /// Records the answer for the current test and enables the next one.
+    pub fn answer_bisect(&self, session: &mut BisectSession, problem: bool, mods: &mut [ModInfo]) -> Result<()> {
+        session.answer(problem, mods);
+        session.save(&self.bisect_path())?;
+        if session.state() == BisectState::Testing {
+            self.apply_enabled(mods, |mod_info| session.is_testing(mod_info))?;
+        }
+        Ok(())
+    }
+
+    /// Ends the search, enabling the mods that were enabled before it
+    /// except for `keep_disabled`.
+    pub fn finish_bisect(&self, session: &BisectSession, keep_disabled: Option<ModId>, mods: &mut [ModInfo]) -> Result<()> {
+        self.apply_enabled(mods, |mod_info| {
+            session.was_enabled(mod_info) && Some(mod_info.id) != keep_disabled
+        })?;
+        fs::remove_file(self.bisect_path())?;
+        Ok(())
+    }
+
+    fn purge_state_path(&self) -> PathBuf {
+        self.staging_path.join("purge.json")
+    }
+
+    /// Where files a purge moves out of the game folder are kept.
+    fn purged_files_path(&self) -> PathBuf {
+        self.staging_path.join("purged")
+    }
+
+    pub fn is_purged(&self) -> bool {
+        self.purge_state_path().exists()
+    }
+
+    /// Takes every mod out of the game folder: paks and loose files are
+    /// removed, tweaks reverted and UE4SS moved to the store. What was
+    /// enabled is recorded for `redeploy`. Returns whatever still looks
+    /// modded afterwards.
+    pub fn purge(&self, mods: &mut [ModInfo]) -> Result<Vec<String>> {
+        if self.is_purged() {
+            anyhow::bail!("The mods are already purged; redeploy them first");
+        }
+        let game_path = self.settings.game_path.clone()
+            .ok_or_else(|| anyhow::anyhow!("Game path not set"))?;
+
+        // Recorded first, so an interrupted purge can still be redeployed
+        let mut state = PurgeState::capture(unix_now(), mods);
+        state.save(&self.purge_state_path())?;
+        self.apply_enabled(mods, |_| false)?;
+
+        state.moved = ue4ss::runtime_files(&self.ue4ss_path).iter()
+            .filter_map(|path| path.strip_prefix(&game_path).ok())
+            .map(Path::to_path_buf)
+            .collect();
+        state.save(&self.purge_state_path())?;
+        let renames = state.moved.iter()
+            .map(|relative| Rename {
+                from: game_path.join(relative),
+                to: self.purged_files_path().join(relative),
+            })
+            .collect();
+        self.run_journal(renames, None)?;
+
+        Ok(self.vanilla_issues(mods))
+    }
+
+    /// Puts back what the last purge took out and enables exactly the mods
+    /// that were enabled then.
+    pub fn redeploy(&self, mods: &mut [ModInfo]) -> Result<()> {
+        let state = PurgeState::load(&self.purge_state_path())?
+            .ok_or_else(|| anyhow::anyhow!("The mods are not purged"))?;
+        let game_path = self.settings.game_path.clone()
+            .ok_or_else(|| anyhow::anyhow!("Game path not set"))?;
+
+        let renames = state.moved.iter()
+            .map(|relative| Rename {
+                from: self.purged_files_path().join(relative),
+                to: game_path.join(relative),
+            })
+            .filter(|rename| rename.from.exists())
+            .collect();
+        self.run_journal(renames, None)?;
+
+        self.apply_enabled(mods, |mod_info| state.wants_enabled(mod_info))?;
+        fs::remove_file(self.purge_state_path())?;
+        let _ = fs::remove_dir_all(self.purged_files_path());
+        Ok(())
+    }
+
+    /// Anything the manager knows about that is still in the game folder:
+    /// files in `~mods` or `LogicMods`, UE4SS, and mods still enabled.
+    /// The manager's own `Stalker2/ModManager` folder is not counted.
+    pub fn vanilla_issues(&self, mods: &[ModInfo]) -> Vec<String> {
+        let mut issues = Vec::new();
+        let paks_path = self.mods_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        for dir in [self.mods_path.clone(), paks_path.join("LogicMods")] {
+            let entries = fs::read_dir(&dir).into_iter().flatten().filter_map(|entry| entry.ok());
+            for entry in entries {
+                issues.push(format!("{} is still in {}", entry.file_name().to_string_lossy(), dir.display()));
+            }
+        }
+        if !ue4ss::runtime_files(&self.ue4ss_path).is_empty() {
+            issues.push("UE4SS is still installed in Binaries/Win64".to_string());
+        }
+        for mod_info in mods {
+            if self.is_enabled_on_disk(mod_info) {
+                issues.push(format!("{} is still enabled", mod_info.name));
+            }
+        }
+        issues
+    }
+
     pub fn settings(&self) -> &Settings {
         &self.settings
     }
 
+    /// Copies a pak into the store and deploys it, returning its stored path.
     pub fn install_local_mod(&self, source_path: &Path) -> Result<PathBuf> {
-        std::fs::create_dir_all(&self.mods_path)?;
+        std::fs::create_dir_all(&self.staging_path)?;
 
         let file_name = source_path.file_name()
             .ok_or_else(|| anyhow::anyhow!("Invalid file name"))?;
@@ -191,10 +873,12 @@ impl ModManager {
         // Remove any duplicate .pak extensions
         let name = file_name.to_string_lossy();
         let clean_name = name.trim_end_matches(".pak").to_string() + ".pak";
-        let dest_path = self.mods_path.join(clean_name);
+        self.check_pak_name_free(std::ffi::OsStr::new(&clean_name))?;
+        let dest_path = self.staging_path.join(clean_name);
 
         // Copy the file
         std::fs::copy(source_path, &dest_path)?;
+        self.enable_mod(&dest_path)?;
         
         Ok(dest_path)
     }
@@ -207,88 +891,400 @@ impl ModManager {
         }
     }
 
-    pub fn save_mod_list(&self, mods: &[ModInfo]) -> Result<()> {
-        let mod_list_path = self.settings.game_path.clone()
-            .unwrap_or_else(|| PathBuf::new())
-            .join("Stalker2")
-            .join("ModManager")
-            .join("mod_list.json");
+    /// Dependency problems left by enabling or disabling the mod `id`.
+    pub fn dependency_issues(&self, mods: &[ModInfo], id: ModId, enabling: bool) -> Vec<DependencyIssue> {
+        let mods = self.mods_on_disk(mods);
+        let Some(index) = find_mod(&mods, id) else {
+            return Vec::new();
+        };
 
-        // Create parent directories if they don't exist
-        if let Some(parent) = mod_list_path.parent() {
-            fs::create_dir_all(parent)?;
+        if enabling {
+            dependencies::check_enable(&mods, index)
+        } else {
+            dependencies::check_disable(&mods, index)
         }
+    }
 
-        let json = serde_json::to_string_pretty(mods)?;
-        fs::write(mod_list_path, json)?;
-        Ok(())
+    /// Rule violations among the enabled mods in their current load order.
+    pub fn validate_rules(&self, mods: &[ModInfo]) -> Vec<RuleViolation> {
+        rules::validate(&self.mods_on_disk(mods))
     }
 
-    pub fn load_mod_list(&self) -> Result<Vec<ModInfo>> {
-        let mut mods: Vec<ModInfo> = Vec::new();
-        
-        // First load saved mod list
-        let mod_list_path = self.settings.game_path.clone()
-            .unwrap_or_else(|| PathBuf::new())
-            .join("Stalker2")
-            .join("ModManager")
-            .join("mod_list.json");
+    /// Rule violations enabling the mod `id` would cause, checked before any
+    /// file is moved.
+    pub fn rule_violations_for_enable(&self, mods: &[ModInfo], id: ModId) -> Vec<RuleViolation> {
+        let mods = self.mods_on_disk(mods);
+        match find_mod(&mods, id) {
+            Some(index) => rules::check_enable(&mods, index),
+            None => Vec::new(),
+        }
+    }
 
-        if mod_list_path.exists() {
-            let json = fs::read_to_string(&mod_list_path)?;
-            mods = serde_json::from_str(&json)?;
+    /// Rule violations and dependency problems toggling the mods `ids`
+    /// together would cause, checked before any file is moved.
+    pub fn batch_problems(&self, mods: &[ModInfo], ids: &[ModId], enabling: bool) -> (Vec<RuleViolation>, Vec<DependencyIssue>) {
+        let mut mods = self.mods_on_disk(mods);
+        for mod_info in mods.iter_mut().filter(|mod_info| ids.contains(&mod_info.id)) {
+            mod_info.enabled = enabling;
         }
 
-        // Track both paths and filenames to prevent duplicates
-        let existing_paths: Vec<PathBuf> = mods.iter()
-            .filter_map(|m| m.installed_path.clone())
+        let violations = if enabling {
+            rules::validate(&mods).into_iter()
+                .filter(|violation| ids.iter().any(|&id| violation.involves(id)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let issues = ids.iter()
+            .filter_map(|&id| find_mod(&mods, id))
+            .flat_map(|index| if enabling {
+                dependencies::check_enable(&mods, index)
+            } else {
+                dependencies::check_disable(&mods, index)
+            })
             .collect();
-        
-        let existing_filenames: Vec<String> = existing_paths.iter()
-            .filter_map(|p| p.file_name())
-            .filter_map(|f| f.to_str())
-            .map(|s| s.to_string())
+        (violations, issues)
+    }
+
+    /// Computes a sorted load order for the enabled mods without touching
+    /// any file. Paks whose index can't be read count as having no assets.
+    pub fn plan_load_order(&self, mods: &[ModInfo]) -> Result<LoadOrderPlan> {
+        let mods = self.mods_on_disk(mods);
+        let assets: Vec<Vec<String>> = mods.iter()
+            .map(|mod_info| {
+                let Some(file_name) = mod_info.installed_path.as_deref().and_then(Path::file_name) else {
+                    return Vec::new();
+                };
+                if !mod_info.enabled {
+                    return Vec::new();
+                }
+                pak::list_assets(&self.mods_path.join(file_name)).unwrap_or_else(|e| {
+                    eprintln!("Failed to read assets of {:?}: {}", file_name, e);
+                    Vec::new()
+                })
+            })
             .collect();
 
-        // Check ~mods directory
-        if self.mods_path.exists() {
-            for entry in std::fs::read_dir(&self.mods_path)? {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if path.extension().map_or(false, |ext| ext == "pak") {
-                        let filename = path.file_name()
-                            .and_then(|f| f.to_str())
-                            .map(|s| s.to_string());
-                        
-                        // Only add if neither path nor filename exists in tracked mods
-                        if !existing_paths.contains(&path) && 
-                           !filename.as_ref().map_or(false, |f| existing_filenames.contains(f)) {
-                            // Add untracked mod
-                            mods.push(ModInfo {
-                                name: path.file_stem()
-                                    .unwrap_or_default()
-                                    .to_string_lossy()
-                                    .to_string(),
-                                version: "Unknown".to_string(),
-                                author: "Unknown".to_string(),
-                                description: String::new(),
-                                nexus_mod_id: None,
-                                installed_path: Some(path),
-                                enabled: true,
-                            });
-                        }
+        load_order::plan(&mods, &assets)
+    }
+
+    /// Renames the files in `~mods` and the store to match `plan` and updates
+    /// the paths of the renamed mods in `mods`, the list the plan was
+    /// computed from. Deployed paks are deployed again under the new name,
+    /// so links point at the renamed stored file.
+    pub fn apply_load_order(&self, plan: &LoadOrderPlan, mods: &mut [ModInfo]) -> Result<()> {
+        let mut renames = Vec::new();
+        let mut deploys = Vec::new();
+        let mut updated = mods.to_vec();
+        for placement in plan.changes() {
+            // IoStore companions have to keep the pak's name
+            let names = std::iter::once(PathBuf::from(&placement.current_file))
+                .chain(COMPANION_EXTENSIONS.iter().map(|extension| Path::new(&placement.current_file).with_extension(extension)));
+            for name in names {
+                let new_name = Path::new(&placement.new_file).with_extension(name.extension().unwrap_or_default());
+                let stored = self.staging_path.join(&name);
+                let new_stored = self.staging_path.join(&new_name);
+                let deployed = self.mods_path.join(&name);
+                let stored_exists = stored.exists();
+                if stored_exists {
+                    renames.push(Rename { from: stored.clone(), to: new_stored.clone() });
+                }
+                // Dangling links are deployments too
+                if fs::symlink_metadata(&deployed).is_err() {
+                    continue;
+                }
+
+                if !stored_exists || !staging::is_deployment_of(&deployed, &stored)? {
+                    // Never stored or changed by hand: the file itself moves
+                    renames.push(Rename { from: deployed, to: self.mods_path.join(&new_name) });
+                    continue;
+                }
+                // A symbolic link can point at the new name before the file is there
+                let source = match self.settings.deploy_method {
+                    staging::DeployMethod::Symlink => new_stored,
+                    _ => stored,
+                };
+                let partial = self.mods_path.join(partial_name(&new_name));
+                let result = fs::remove_file(&partial)
+                    .or_else(|e| if e.kind() == std::io::ErrorKind::NotFound { Ok(()) } else { Err(e) })
+                    .and_then(|()| staging::deploy_file(&source, &partial, self.settings.deploy_method));
+                if let Err(e) = result {
+                    remove_partials(&deploys);
+                    return Err(anyhow::Error::from(e).context(format!("Failed to deploy {}", new_name.display())));
+                }
+                renames.push(Rename { from: deployed, to: self.trash_path().join(&name) });
+                deploys.push(Rename { from: partial, to: self.mods_path.join(&new_name) });
+            }
+
+            if let Some(mod_info) = updated.get_mut(placement.index) {
+                let dir = if self.staging_path.join(&placement.current_file).exists() {
+                    &self.staging_path
+                } else {
+                    &self.mods_path
+                };
+                mod_info.installed_path = Some(dir.join(&placement.new_file));
+            }
+        }
+        renames.extend(deploys);
+
+        self.run_journal(renames, Some(&updated))?;
+        mods.clone_from_slice(&updated);
+        Ok(())
+    }
+
+    /// `mods` with `enabled` read from disk, since the caller's copy may
+    /// predate the last toggle.
+    fn mods_on_disk(&self, mods: &[ModInfo]) -> Vec<ModInfo> {
+        mods.iter()
+            .cloned()
+            .map(|mut mod_info| {
+                if mod_info.installed_path.is_some() {
+                    mod_info.enabled = self.is_enabled_on_disk(&mod_info);
+                }
+                mod_info
+            })
+            .collect()
+    }
+
+    /// Compares `mods` with the files on disk: paks in `~mods` or the
+    /// store and UE4SS mods without an entry, entries whose files are gone
+    /// or elsewhere, and switches that don't match the files. A pak renamed
+    /// outside the manager is matched to its entry by size when no other
+    /// missing or new pak has the same size. Nothing is changed; the caller
+    /// applies the result.
+    pub fn reconcile(&self, mods: &[ModInfo]) -> Result<Reconciliation> {
+        let mut changes = Vec::new();
+        let mut sizes = Vec::new();
+        let mut missing_paks = Vec::new();
+        // UE4SS and its mods are out of the game folder while purged
+        let purged = self.is_purged();
+
+        for mod_info in mods {
+            let id = mod_info.id;
+            let Some(path) = mod_info.installed_path.as_deref() else {
+                continue;
+            };
+            let expected = match mod_info.kind {
+                ModKind::Pak => path.file_name()
+                    .filter(|name| self.mods_path.join(name).exists() || self.staging_path.join(name).exists())
+                    .map(|name| self.pak_path(name)),
+                ModKind::Ue4ss | ModKind::Lua if purged => continue,
+                ModKind::Ue4ss => ue4ss::is_installed(&self.ue4ss_path).then(|| self.ue4ss_path.clone()),
+                ModKind::Lua | ModKind::Files | ModKind::IniTweak => path.exists().then(|| path.to_path_buf()),
+            };
+            let Some(expected) = expected else {
+                match mod_info.file_size.filter(|_| mod_info.kind == ModKind::Pak) {
+                    Some(size) => missing_paks.push((id, size)),
+                    None => changes.push(Change::Missing { id }),
+                }
+                continue;
+            };
+            if expected != path {
+                changes.push(Change::Moved { id, path: expected.clone() });
+            }
+            if mod_info.kind == ModKind::Pak {
+                if let Ok(metadata) = fs::metadata(&expected) {
+                    if mod_info.file_size != Some(metadata.len()) {
+                        sizes.push((id, metadata.len()));
                     }
                 }
             }
+            let on_disk = ModInfo { installed_path: Some(expected), ..mod_info.clone() };
+            let enabled_on_disk = self.is_enabled_on_disk(&on_disk);
+            if enabled_on_disk != mod_info.enabled {
+                changes.push(Change::EnabledMismatch { id, enabled_on_disk });
+            }
         }
 
-        // Save updated list if new mods were found
-        self.save_mod_list(&mods)?;
-        
+        let tracked = |kind: ModKind, name: &std::ffi::OsStr| {
+            mods.iter().any(|mod_info| {
+                mod_info.kind == kind && mod_info.installed_path.as_deref().and_then(Path::file_name) == Some(name)
+            })
+        };
+        let mut found = Vec::new();
+        for dir in [&self.mods_path, &self.staging_path] {
+            for entry in fs::read_dir(dir)?.filter_map(|entry| entry.ok()) {
+                let name = entry.file_name();
+                let is_pak = Path::new(&name).extension().is_some_and(|ext| ext == "pak");
+                if is_pak && !tracked(ModKind::Pak, &name) && !found.iter().any(|(found, _)| *found == name) {
+                    let size = fs::metadata(entry.path()).map(|metadata| metadata.len()).ok();
+                    found.push((name, size));
+                }
+            }
+        }
+
+        // A missing pak and a new one of the same size are the same pak
+        // renamed, as long as no other missing or new pak has that size
+        let count = |size: u64| (
+            missing_paks.iter().filter(|(_, missing)| *missing == size).count(),
+            found.iter().filter(|(_, found)| *found == Some(size)).count(),
+        );
+        let mut renamed = Vec::new();
+        for (name, size) in &found {
+            let enabled = self.mods_path.join(name).exists();
+            let entry = size
+                .filter(|&size| count(size) == (1, 1))
+                .and_then(|size| missing_paks.iter().find(|(_, missing)| *missing == size))
+                .and_then(|(id, _)| mods.iter().find(|mod_info| mod_info.id == *id));
+            let Some(mod_info) = entry else {
+                let mut mod_info = untracked_pak(self.pak_path(name), enabled);
+                mod_info.file_size = *size;
+                changes.push(Change::Added(Box::new(mod_info)));
+                continue;
+            };
+            changes.push(Change::Moved { id: mod_info.id, path: self.pak_path(name) });
+            if enabled != mod_info.enabled {
+                changes.push(Change::EnabledMismatch { id: mod_info.id, enabled_on_disk: enabled });
+            }
+            renamed.push(mod_info.id);
+        }
+        for (id, _) in missing_paks.iter().filter(|(id, _)| !renamed.contains(id)) {
+            changes.push(Change::Missing { id: *id });
+        }
+
+        // UE4SS and its mods, when installed by hand
+        if ue4ss::is_installed(&self.ue4ss_path) && !mods.iter().any(|m| m.kind == ModKind::Ue4ss) {
+            changes.push(Change::Added(Box::new(self.ue4ss_mod_info())));
+        }
+        let mods_path = self.ue4ss_mods_path();
+        for name in ue4ss::list_mods(&mods_path)? {
+            if !tracked(ModKind::Lua, std::ffi::OsStr::new(&name)) {
+                changes.push(Change::Added(Box::new(ModInfo {
+                    enabled: ue4ss::is_mod_enabled(&mods_path, &name),
+                    installed_path: Some(mods_path.join(&name)),
+                    name,
+                    version: "Unknown".to_string(),
+                    author: "Unknown".to_string(),
+                    kind: ModKind::Lua,
+                    ..Default::default()
+                })));
+            }
+        }
+
+        Ok(Reconciliation { changes, sizes })
+    }
+
+    pub fn save_mod_list(&self, mods: &[ModInfo]) -> Result<()> {
+        let json = serde_json::to_string_pretty(mods)?;
+        journal::write_atomic(&self.mod_list_path(), json.as_bytes())
+    }
+
+    fn mod_list_path(&self) -> PathBuf {
+        self.settings.game_path.clone()
+            .unwrap_or_default()
+            .join("Stalker2")
+            .join("ModManager")
+            .join("mod_list.json")
+    }
+
+    fn journal_path(&self) -> PathBuf {
+        self.settings.game_path.clone()
+            .unwrap_or_default()
+            .join("Stalker2")
+            .join("ModManager")
+            .join("journal.json")
+    }
+
+    /// Where files taken out of `~mods` wait until their transaction is done.
+    fn trash_path(&self) -> PathBuf {
+        self.settings.game_path.clone()
+            .unwrap_or_default()
+            .join("Stalker2")
+            .join("ModManager")
+            .join("trash")
+    }
+
+    /// Finishes or undoes an operation interrupted by a crash. Run before
+    /// the mod list is loaded.
+    pub fn recover_journal(&self) -> Result<Option<Recovery>> {
+        let recovery = journal::recover(&self.journal_path(), &self.mod_list_path())?;
+        let _ = fs::remove_dir_all(self.trash_path());
+        Ok(recovery)
+    }
+
+    /// Moves mods kept by older versions in the game folder into the store,
+    /// and copies enabled paks that aren't stored yet (a hard link when the
+    /// store is on the same drive), so every pak lives in the store.
+    pub fn migrate_to_staging(&self) -> Result<()> {
+        let game_path = self.settings.game_path.clone().unwrap_or_default();
+        let legacy = legacy_storage_path(&game_path);
+        if legacy.is_dir() && legacy != self.staging_path {
+            self.move_store(&legacy, &self.staging_path)?;
+            let _ = fs::remove_dir(&legacy);
+        }
+
+        let method = match self.settings.deploy_method {
+            staging::DeployMethod::Hardlink => staging::DeployMethod::Hardlink,
+            _ => staging::DeployMethod::Copy,
+        };
+        let mut renames = Vec::new();
+        for entry in fs::read_dir(&self.mods_path)?.filter_map(|entry| entry.ok()) {
+            let name = PathBuf::from(entry.file_name());
+            let is_mod_file = name.extension().is_some_and(|ext| {
+                ext == "pak" || COMPANION_EXTENSIONS.iter().any(|companion| ext == *companion)
+            });
+            let stored = self.staging_path.join(&name);
+            if !is_mod_file || stored.exists() || entry.path().is_symlink() {
+                continue;
+            }
+            let partial = self.staging_path.join(partial_name(&name));
+            if let Err(e) = staging::deploy_file(&entry.path(), &partial, method) {
+                remove_partials(&renames);
+                return Err(e.into());
+            }
+            renames.push(Rename { from: partial, to: stored });
+        }
+        if renames.is_empty() {
+            return Ok(());
+        }
+
+        let mut mods = self.load_mod_list()?;
+        for mod_info in mods.iter_mut().filter(|mod_info| mod_info.kind == ModKind::Pak) {
+            let stored = mod_info.installed_path.as_deref()
+                .and_then(Path::file_name)
+                .map(|file_name| self.staging_path.join(file_name));
+            if stored.as_ref().is_some_and(|stored| renames.iter().any(|rename| &rename.to == stored)) {
+                mod_info.installed_path = stored;
+            }
+        }
+        self.run_journal(renames, Some(&mods))
+    }
+
+    /// Reads the saved mod list as it is; `reconcile` compares it with the
+    /// files on disk.
+    pub fn load_mod_list(&self) -> Result<Vec<ModInfo>> {
+        let mod_list_path = self.mod_list_path();
+        if !mod_list_path.exists() {
+            return Ok(Vec::new());
+        }
+        let (mods, migrated) = parse_mod_list(&fs::read_to_string(&mod_list_path)?)?;
+        // Lists from before mods had IDs get them now, saved so profiles
+        // and other references to them stay valid
+        if migrated {
+            self.save_mod_list(&mods)?;
+        }
         Ok(mods)
     }
 
+    /// Exports every pak; other kinds of mods can't be exported.
     pub fn export_mods(&self, zip_path: &Path) -> Result<()> {
+        let paks: Vec<ModInfo> = self.load_mod_list()?.into_iter()
+            .filter(|mod_info| mod_info.kind == ModKind::Pak)
+            .collect();
+        self.export_mod_selection(zip_path, &paks)
+    }
+
+    /// Writes `mods` and their files to a zip that `import_mods` reads.
+    /// Only paks can be exported.
+    pub fn export_mod_selection(&self, zip_path: &Path, mods: &[ModInfo]) -> Result<()> {
+        let others: Vec<&str> = mods.iter()
+            .filter(|mod_info| mod_info.kind != ModKind::Pak)
+            .map(|mod_info| mod_info.name.as_str())
+            .collect();
+        if !others.is_empty() {
+            return Err(anyhow::anyhow!("Only pak mods can be exported, not {}", others.join(", ")));
+        }
+
         let file = std::fs::File::create(zip_path)?;
         let mut zip = ZipWriter::new(file);
         let options = FileOptions::default()
@@ -296,21 +1292,20 @@ impl ModManager {
             .unix_permissions(0o755);
 
         // First, write the manifest
-        let mod_list = self.load_mod_list()?;
-        let manifest = serde_json::to_string_pretty(&mod_list)?;
+        let manifest = serde_json::to_string_pretty(mods)?;
         zip.start_file("mod-manifest.json", options)?;
         zip.write_all(manifest.as_bytes())?;
 
         // Then write each mod file
-        for mod_info in mod_list {
-            if let Some(path) = mod_info.installed_path {
+        for mod_info in mods {
+            if let Some(path) = &mod_info.installed_path {
                 if path.exists() {
                     let file_name = path.file_name()
                         .ok_or_else(|| anyhow::anyhow!("Invalid mod file name"))?
                         .to_string_lossy();
                     
                     zip.start_file(format!("mods/{}", file_name), options)?;
-                    let mut file = std::fs::File::open(&path)?;
+                    let mut file = std::fs::File::open(path)?;
                     let mut buffer = Vec::new();
                     file.read_to_end(&mut buffer)?;
                     zip.write_all(&buffer)?;
@@ -335,32 +1330,43 @@ impl ModManager {
         };
 
         // Now parse the manifest
-        let mod_list: Vec<ModInfo> = serde_json::from_str(&manifest_contents)?;
+        let (mod_list, _) = parse_mod_list(&manifest_contents)?;
 
-        // Create necessary directories
-        std::fs::create_dir_all(&self.mods_path)?;
-        std::fs::create_dir_all(&self.unloaded_mods_path)?;
+        // Extract into a staging folder first, so nothing in the game
+        // folder changes until every file is there
+        let staging = self.mod_list_path().with_file_name("import_staging");
+        let _ = fs::remove_dir_all(&staging);
+        fs::create_dir_all(&staging)?;
 
         // Track successfully imported mods
         let mut imported_mods = Vec::new();
+        let mut renames = Vec::new();
 
         // Extract mod files
         for mut mod_info in mod_list {
             if let Some(path) = mod_info.installed_path.clone() {
                 let file_name = path.file_name()
                     .ok_or_else(|| anyhow::anyhow!("Invalid mod file name"))?
-                    .to_string_lossy();
+                    .to_string_lossy()
+                    .to_string();
                 
                 let zip_path = format!("mods/{}", file_name);
                 if let Ok(mut zip_file) = archive.by_name(&zip_path) {
-                    let target_path = if mod_info.enabled {
-                        self.mods_path.join(&*file_name)
-                    } else {
-                        self.unloaded_mods_path.join(&*file_name)
-                    };
+                    let target_path = self.staging_path.join(&file_name);
 
-                    let mut target_file = std::fs::File::create(&target_path)?;
-                    std::io::copy(&mut zip_file, &mut target_file)?;
+                    let staged_path = staging.join(&file_name);
+                    let mut staged_file = std::fs::File::create(&staged_path)?;
+                    std::io::copy(&mut zip_file, &mut staged_file)?;
+                    staged_file.sync_all()?;
+
+                    // Files already there are moved aside so they can come back on failure
+                    for (existing, folder) in [(self.mods_path.join(&file_name), "deployed"), (target_path.clone(), "stored")] {
+                        if existing.exists() {
+                            let backup = staging.join("replaced").join(folder).join(&file_name);
+                            renames.push(Rename { from: existing, to: backup });
+                        }
+                    }
+                    renames.push(Rename { from: staged_path, to: target_path.clone() });
 
                     // Update mod_info with new path
                     mod_info.installed_path = Some(target_path);
@@ -369,12 +1375,43 @@ impl ModManager {
             }
         }
 
-        // Update mod list with imported mods
         let mut current_mods = self.load_mod_list()?;
-        current_mods.extend(imported_mods);
-        self.save_mod_list(&current_mods)?;
 
-        Ok(())
+        // Enabled mods are deployed in the same transaction, from the staged
+        // file; a symbolic link can point at the store before the file is there
+        let mut deploys = Vec::new();
+        for mod_info in imported_mods.iter().filter(|mod_info| mod_info.enabled) {
+            let Some(stored) = mod_info.installed_path.as_deref() else {
+                continue;
+            };
+            let Some(file_name) = stored.file_name() else {
+                continue;
+            };
+            let source = match self.settings.deploy_method {
+                staging::DeployMethod::Symlink => stored.to_path_buf(),
+                _ => staging.join(file_name),
+            };
+            let partial = self.mods_path.join(partial_name(Path::new(file_name)));
+            let result = fs::remove_file(&partial)
+                .or_else(|e| if e.kind() == std::io::ErrorKind::NotFound { Ok(()) } else { Err(e) })
+                .and_then(|()| staging::deploy_file(&source, &partial, self.settings.deploy_method));
+            if let Err(e) = result {
+                remove_partials(&deploys);
+                let _ = fs::remove_dir_all(&staging);
+                return Err(anyhow::Error::from(e).context(format!("Failed to deploy {}", mod_info.name)));
+            }
+            deploys.push(Rename { from: partial, to: self.mods_path.join(file_name) });
+        }
+        renames.extend(deploys);
+
+        // Update mod list with imported mods, in the same transaction
+        current_mods.extend(imported_mods);
+        let result = self.run_journal(renames, Some(&current_mods));
+        // A journal left behind still needs the staged files to recover
+        if !self.journal_path().exists() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+        result
     }
 
     pub fn add_to_mod_list(&self, mod_info: ModInfo) -> Result<()> {
@@ -384,7 +1421,191 @@ impl ModManager {
         Ok(())
     }
 
-    pub fn unloaded_mods_path(&self) -> &Path {
-        &self.unloaded_mods_path
+    /// Nexus mods the user tracks on the website that are not installed yet.
+    pub fn tracked_mods_to_install(&self, tracked: &[TrackedMod]) -> Result<Vec<i32>> {
+        let installed: Vec<i32> = self.load_mod_list()?
+            .iter()
+            .filter_map(|mod_info| mod_info.nexus_mod_id)
+            .collect();
+
+        Ok(tracked.iter()
+            .map(|t| t.mod_id)
+            .filter(|mod_id| !installed.contains(mod_id))
+            .collect())
+    }
+
+    pub fn staging_path(&self) -> &Path {
+        &self.staging_path
     }
-} 
\ No newline at end of file
+
+    /// Moves the store to a new folder, updating the paths in the mod list,
+    /// before the setting is changed to it.
+    pub fn move_mod_storage(&self, to: &Path) -> Result<()> {
+        self.move_store(&self.staging_path, to)
+    }
+
+    fn move_store(&self, from: &Path, to: &Path) -> Result<()> {
+        if to == from {
+            return Ok(());
+        }
+        fs::create_dir_all(to)?;
+
+        let renames: Vec<Rename> = fs::read_dir(from)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| Rename { from: entry.path(), to: to.join(entry.file_name()) })
+            .collect();
+        let mut mods = self.load_mod_list()?;
+        for mod_info in &mut mods {
+            let moved = mod_info.installed_path.as_ref()
+                .and_then(|path| path.strip_prefix(from).ok())
+                .map(|relative| to.join(relative));
+            if moved.is_some() {
+                mod_info.installed_path = moved;
+            }
+        }
+        self.run_journal(renames, Some(&mods))?;
+        self.relink(from, to)
+    }
+
+    /// Points symlinks in `~mods` into the store at `from` to `to` instead.
+    fn relink(&self, from: &Path, to: &Path) -> Result<()> {
+        for entry in fs::read_dir(&self.mods_path)?.filter_map(|entry| entry.ok()) {
+            let Ok(target) = fs::read_link(entry.path()) else {
+                continue;
+            };
+            if let Ok(relative) = target.strip_prefix(from) {
+                fs::remove_file(entry.path())?;
+                staging::deploy_file(&to.join(relative), &entry.path(), staging::DeployMethod::Symlink)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Paks are stored, deployed and toggled by file name, so a second mod
+    /// with the same name would replace the first one's files.
+    pub fn check_pak_name_free(&self, file_name: &std::ffi::OsStr) -> Result<()> {
+        if self.staging_path.join(file_name).exists() || self.mods_path.join(file_name).exists() {
+            return Err(anyhow::anyhow!(
+                "A mod with the file name {} is already installed; uninstall it first",
+                file_name.to_string_lossy()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Where a pak is kept: the store, or `~mods` for one never stored.
+    pub fn pak_path(&self, file_name: &std::ffi::OsStr) -> PathBuf {
+        let stored = self.staging_path.join(file_name);
+        if stored.exists() {
+            stored
+        } else {
+            self.mods_path.join(file_name)
+        }
+    }
+
+    pub fn ue4ss_path(&self) -> &Path {
+        &self.ue4ss_path
+    }
+
+    pub fn ue4ss_mods_path(&self) -> PathBuf {
+        self.ue4ss_path.join("Mods")
+    }
+}
+
+/// The store every installed pak lives in unless set otherwise, kept with
+/// the manager's own data so the game folder can be reset to vanilla.
+pub fn default_staging_path(game_path: &Path) -> PathBuf {
+    directories::ProjectDirs::from("", "", "Stalker2ModManager")
+        .map(|dirs| dirs.data_dir().join("mods"))
+        .unwrap_or_else(|| legacy_storage_path(game_path))
+}
+
+/// Where older versions kept disabled mods.
+fn legacy_storage_path(game_path: &Path) -> PathBuf {
+    game_path.join("Stalker2").join("ModManager").join("unloaded_mods")
+}
+
+/// Files of an IoStore mod that have to move and be renamed with its pak.
+const COMPANION_EXTENSIONS: [&str; 3] = ["utoc", "ucas", "sig"];
+
+/// A pak and those of its companion files present in `dir`.
+fn pak_files(dir: &Path, file_name: &std::ffi::OsStr) -> Vec<PathBuf> {
+    let pak = Path::new(file_name);
+    std::iter::once(pak.to_path_buf())
+        .chain(COMPANION_EXTENSIONS.iter().map(|extension| pak.with_extension(extension)))
+        .filter(|name| name == pak || dir.join(name).exists())
+        .collect()
+}
+
+/// A free name in `dir` for a hand-changed copy of `name`: `Mod.pak` is
+/// kept as `Mod.modified.pak`, or `Mod.modified-2.pak` if that is taken.
+/// Companions get the same stem as their pak.
+fn modified_name(dir: &Path, name: &Path) -> PathBuf {
+    let stem = name.file_stem().unwrap_or_default().to_string_lossy();
+    let taken = |stem: &str| std::iter::once("pak")
+        .chain(COMPANION_EXTENSIONS)
+        .any(|extension| dir.join(format!("{}.{}", stem, extension)).exists());
+
+    let mut modified = format!("{}.modified", stem);
+    let mut counter = 2;
+    while taken(&modified) {
+        modified = format!("{}.modified-{}", stem, counter);
+        counter += 1;
+    }
+    dir.join(format!("{}.{}", modified, name.extension().unwrap_or_default().to_string_lossy()))
+}
+
+/// The temporary name a file is deployed under before it is put in place.
+fn partial_name(name: &Path) -> PathBuf {
+    let mut partial = name.as_os_str().to_os_string();
+    partial.push(".partial");
+    PathBuf::from(partial)
+}
+
+fn remove_partials(renames: &[Rename]) {
+    for rename in renames {
+        if rename.from.extension().is_some_and(|ext| ext == "partial") && rename.from.exists() {
+            let _ = fs::remove_file(&rename.from);
+        }
+    }
+}
+
+/// A list entry for a pak found on disk without one.
+fn untracked_pak(path: PathBuf, enabled: bool) -> ModInfo {
+    ModInfo {
+        name: path.file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        version: "Unknown".to_string(),
+        author: "Unknown".to_string(),
+        description: String::new(),
+        nexus_mod_id: None,
+        installed_path: Some(path),
+        enabled,
+        ..Default::default()
+    }
+}
+
+fn find_mod(mods: &[ModInfo], id: ModId) -> Option<usize> {
+    mods.iter().position(|mod_info| mod_info.id == id)
+}
+
+/// Reads a saved mod list, giving entries from older versions an ID and
+/// pointing their local rule and dependency targets at IDs instead of
+/// names. The flag tells whether anything had to be migrated.
+fn parse_mod_list(json: &str) -> Result<(Vec<ModInfo>, bool)> {
+    let mut entries: Vec<serde_json::Value> = serde_json::from_str(json)?;
+    let mut migrated = false;
+    for entry in entries.iter_mut().filter(|entry| entry.get("id").is_none()) {
+        if let Some(object) = entry.as_object_mut() {
+            object.insert("id".to_string(), serde_json::to_value(ModId::default())?);
+            migrated = true;
+        }
+    }
+    migrated |= dependencies::migrate_local_targets(&mut entries);
+    let mods = entries.into_iter()
+        .map(serde_json::from_value)
+        .collect::<serde_json::Result<_>>()?;
+    Ok((mods, migrated))
+}
@@ -0,0 +1,145 @@
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use gtk::glib::{self, subclass::prelude::*, subclass::Signal};
+use gtk::prelude::*;
+use crate::mod_info::{ModId, ModInfo};
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct ModObject {
+        pub mod_info: RefCell<ModInfo>,
+        /// Bytes on disk, worked out on first use.
+        pub size: Cell<Option<u64>>,
+        /// Position among the enabled paks, counted from 1.
+        pub load_order: Cell<Option<u32>>,
+        /// Whether the mod is part of a rule violation.
+        pub conflicting: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ModObject {
+        const NAME: &'static str = "Stalker2ModObject";
+        type Type = super::ModObject;
+    }
+
+    impl ObjectImpl for ModObject {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("changed").build()])
+        }
+    }
+}
+
+glib::wrapper! {
+    /// A mod in the list model. Cells, sorting and filtering read the
+    /// record from here, and every change goes through `set_mod_info`.
+    pub struct ModObject(ObjectSubclass<imp::ModObject>);
+}
+
+impl ModObject {
+    pub fn new(mod_info: ModInfo) -> Self {
+        let object: Self = glib::Object::new();
+        object.imp().mod_info.replace(mod_info);
+        object
+    }
+
+    pub fn mod_info(&self) -> ModInfo {
+        self.imp().mod_info.borrow().clone()
+    }
+
+    pub fn id(&self) -> ModId {
+        self.imp().mod_info.borrow().id
+    }
+
+    /// Replaces the record and lets the cells showing it know.
+    pub fn set_mod_info(&self, mod_info: ModInfo) {
+        let old = self.imp().mod_info.replace(mod_info);
+        if old.installed_path != self.imp().mod_info.borrow().installed_path {
+            self.imp().size.set(None);
+        }
+        self.emit_changed();
+    }
+
+    /// Changes the record in place, see `set_mod_info`.
+    pub fn update(&self, change: impl FnOnce(&mut ModInfo)) {
+        let mut mod_info = self.mod_info();
+        change(&mut mod_info);
+        self.set_mod_info(mod_info);
+    }
+
+    pub fn size(&self) -> u64 {
+        if let Some(size) = self.imp().size.get() {
+            return size;
+        }
+        let size = self.imp().mod_info.borrow().installed_path.as_deref().map_or(0, disk_size);
+        self.imp().size.set(Some(size));
+        size
+    }
+
+    /// When the mod was installed, or for entries older than that record
+    /// when its files were last written.
+    pub fn installed_at(&self) -> Option<u64> {
+        let mod_info = self.imp().mod_info.borrow();
+        mod_info.installed_at.or_else(|| {
+            let modified = fs::metadata(mod_info.installed_path.as_ref()?).ok()?.modified().ok()?;
+            modified.duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_secs())
+        })
+    }
+
+    pub fn load_order(&self) -> Option<u32> {
+        self.imp().load_order.get()
+    }
+
+    pub fn is_conflicting(&self) -> bool {
+        self.imp().conflicting.get()
+    }
+
+    /// Updates the state worked out from the whole list, returning whether
+    /// it changed.
+    pub fn set_list_state(&self, load_order: Option<u32>, conflicting: bool) -> bool {
+        let changed = self.imp().load_order.replace(load_order) != load_order
+            || self.imp().conflicting.replace(conflicting) != conflicting;
+        if changed {
+            self.emit_changed();
+        }
+        changed
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_local("changed", false, move |values| {
+            if let Some(object) = values.first().and_then(|value| value.get::<Self>().ok()) {
+                f(&object);
+            }
+            None
+        })
+    }
+
+    fn emit_changed(&self) {
+        self.emit_by_name::<()>("changed", &[]);
+    }
+}
+
+/// Size of a file or a folder with everything in it. Paks count their
+/// `.utoc` and `.ucas` companions.
+fn disk_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::metadata(path) else {
+        return 0;
+    };
+    if metadata.is_dir() {
+        return fs::read_dir(path).into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| disk_size(&entry.path()))
+            .sum();
+    }
+    let companions: u64 = ["utoc", "ucas"].iter()
+        .filter(|_| path.extension().is_some_and(|ext| ext == "pak"))
+        .filter_map(|ext| fs::metadata(path.with_extension(ext)).ok())
+        .map(|metadata| metadata.len())
+        .sum();
+    metadata.len() + companions
+}
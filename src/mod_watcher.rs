@@ -0,0 +1,40 @@
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Extensions of the files whose changes matter to the mod list.
+const WATCHED_EXTENSIONS: [&str; 4] = ["pak", "utoc", "ucas", "sig"];
+
+/// Watches `~mods` and the store for mod files appearing, disappearing or
+/// being renamed by other programs, and sends a message for each change.
+/// Bursts of changes are left to the receiver to debounce. Watching stops
+/// when the returned watcher is dropped.
+pub fn watch(paths: &[PathBuf], sender: async_channel::Sender<()>) -> Result<RecommendedWatcher> {
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        match event {
+            Ok(event) if is_relevant(&event) => {
+                // A full channel already has a refresh pending
+                let _ = sender.try_send(());
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Failed to watch the mod folders: {}", e),
+        }
+    })?;
+
+    for path in paths {
+        watcher.watch(path, RecursiveMode::NonRecursive)?;
+    }
+    Ok(watcher)
+}
+
+fn is_relevant(event: &Event) -> bool {
+    let changes_files = matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(notify::event::ModifyKind::Name(_)) | EventKind::Any
+    );
+    changes_files && event.paths.iter().any(|path| is_mod_file(path))
+}
+
+fn is_mod_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| WATCHED_EXTENSIONS.iter().any(|watched| ext == *watched))
+}
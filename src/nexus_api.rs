@@ -1,27 +1,41 @@
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
-use serde::Deserialize;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, RETRY_AFTER};
+use serde::{Deserialize, Serialize};
 use anyhow::Result;
+use std::time::Duration;
 use url;
+use crate::http::{self, HttpConfig};
 
-const NEXUS_API_BASE: &str = "https://api.nexusmods.com/v1";
-const GAME_DOMAIN: &str = "stalker2heartofchornobyl";
+pub const NEXUS_API_BASE: &str = "https://api.nexusmods.com/v1";
+pub const GAME_DOMAIN: &str = "stalker2heartofchornobyl";
+pub const NEXUS_GRAPHQL_URL: &str = "https://api.nexusmods.com/v2/graphql";
+pub const NEXUS_WEBSITE: &str = "https://www.nexusmods.com";
 
 #[derive(Debug, Deserialize)]
 pub struct NexusModInfo {
+    // Unpublished mods in the feeds come without name and author
+    #[serde(default)]
     pub name: String,
     pub version: Option<String>,
+    #[serde(default)]
     pub author: String,
     #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
     pub description: String,
+    #[serde(default)]
+    pub picture_url: Option<String>,
+    #[serde(default)]
+    pub endorsement_count: i32,
     #[serde(rename = "mod_id")]
     pub id: i32,
     pub category_id: Option<i32>,
     pub status: String,
     pub available: bool,
+    #[serde(default)]
     pub user: ModUser,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct ModUser {
     pub name: String,
     pub member_id: i32,
@@ -36,6 +50,12 @@ pub struct ModFile {
     pub category_id: Option<i32>,
     pub file_name: String,
     pub mod_version: Option<String>,
+    #[serde(default)]
+    pub category_name: Option<String>,
+    #[serde(default)]
+    pub size_kb: Option<i64>,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 impl ModFile {
@@ -101,69 +121,459 @@ pub struct ModFilesResponse {
     pub files: Vec<ModFile>,
 }
 
-pub struct NexusClient {
-    client: reqwest::Client,
+#[derive(Debug, Deserialize)]
+pub struct GameInfo {
+    pub name: String,
+    #[serde(default)]
+    pub categories: Vec<ModCategory>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModCategory {
+    pub category_id: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EndorsementStatus {
+    Endorsed,
+    Abstained,
+    Undecided,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserEndorsement {
+    pub mod_id: i32,
+    pub domain_name: String,
+    pub status: EndorsementStatus,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrackedMod {
+    pub mod_id: i32,
+    pub domain_name: String,
+}
+
+/// A mod listed under "Requirements" on a mod page.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModRequirement {
+    pub mod_id: Option<String>,
+    pub mod_name: String,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub external_requirement: bool,
+}
+
+impl ModRequirement {
+    /// Nexus mod ID, if the requirement is a mod on the same site.
+    pub fn nexus_mod_id(&self) -> Option<i32> {
+        if self.external_requirement {
+            return None;
+        }
+        self.mod_id.as_deref()?.parse().ok()
+    }
+}
+
+/// Mod listings exposed by the v1 API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModFeed {
+    LatestAdded,
+    LatestUpdated,
+    Trending,
+}
+
+impl ModFeed {
+    pub const ALL: [ModFeed; 3] = [ModFeed::LatestAdded, ModFeed::LatestUpdated, ModFeed::Trending];
+
+    fn endpoint(self) -> &'static str {
+        match self {
+            ModFeed::LatestAdded => "latest_added.json",
+            ModFeed::LatestUpdated => "latest_updated.json",
+            ModFeed::Trending => "trending.json",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ModFeed::LatestAdded => "Latest Added",
+            ModFeed::LatestUpdated => "Latest Updated",
+            ModFeed::Trending => "Trending",
+        }
+    }
+}
+
+pub struct NexusClientBuilder {
     api_key: String,
+    base_url: String,
+    game_domain: String,
+    graphql_url: String,
+    http: HttpConfig,
+    preferred_cdn: Option<String>,
 }
 
-impl NexusClient {
-    pub fn new(api_key: &str) -> Result<Self> {
+impl NexusClientBuilder {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            base_url: NEXUS_API_BASE.to_string(),
+            game_domain: GAME_DOMAIN.to_string(),
+            graphql_url: NEXUS_GRAPHQL_URL.to_string(),
+            http: HttpConfig::default(),
+            preferred_cdn: None,
+        }
+    }
+
+    /// Overrides the API root, e.g. a staging endpoint or a local mock server.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn game_domain(mut self, game_domain: &str) -> Self {
+        self.game_domain = game_domain.to_string();
+        self
+    }
+
+    /// Overrides the v2 GraphQL endpoint, used for data v1 does not expose.
+    pub fn graphql_url(mut self, graphql_url: &str) -> Self {
+        self.graphql_url = graphql_url.to_string();
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.http.user_agent = user_agent.to_string();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.http.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.http.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Routes all requests through the given proxy URL (http, https or socks5).
+    pub fn proxy(mut self, proxy: &str) -> Self {
+        self.http.proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// CDN mirror to download from, matched against a download link's
+    /// `short_name` or `name`. Falls back to the first link Nexus returns.
+    pub fn preferred_cdn(mut self, cdn: Option<&str>) -> Self {
+        self.preferred_cdn = cdn.map(|c| c.to_string());
+        self
+    }
+
+    pub fn build(self) -> Result<NexusClient> {
         let mut headers = HeaderMap::new();
         headers.insert(
             AUTHORIZATION, 
-            HeaderValue::from_str(&format!("Bearer {}", api_key))?
+            HeaderValue::from_str(&format!("Bearer {}", self.api_key))?
         );
-        headers.insert("apikey", HeaderValue::from_str(api_key)?);
-        
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()?;
-            
-        Ok(Self { 
-            client,
-            api_key: api_key.to_string(),
+        headers.insert("apikey", HeaderValue::from_str(&self.api_key)?);
+
+        Ok(NexusClient {
+            client: http::api_client(&self.http, headers)?,
+            download_client: http::download_client(&self.http)?,
+            api_key: self.api_key,
+            base_url: self.base_url,
+            game_domain: self.game_domain,
+            graphql_url: self.graphql_url,
+            preferred_cdn: self.preferred_cdn,
         })
     }
+}
+
+#[derive(Clone)]
+pub struct NexusClient {
+    client: reqwest::Client,
+    download_client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+    game_domain: String,
+    graphql_url: String,
+    preferred_cdn: Option<String>,
+}
+
+impl NexusClient {
+    pub fn new(api_key: &str) -> Result<Self> {
+        Self::builder(api_key).build()
+    }
+
+    pub fn builder(api_key: &str) -> NexusClientBuilder {
+        NexusClientBuilder::new(api_key)
+    }
+
+    pub fn game_domain(&self) -> &str {
+        &self.game_domain
+    }
+
+    pub fn mod_page_url(&self, mod_id: i32) -> String {
+        mod_page_url(&self.game_domain, mod_id)
+    }
+
+    fn mod_url(&self, mod_id: i32) -> String {
+        format!("{}/games/{}/mods/{}", self.base_url, self.game_domain, mod_id)
+    }
 
     pub async fn get_mod_info(&self, mod_id: i32) -> Result<NexusModInfo> {
-        let url = format!("{}/games/{}/mods/{}", NEXUS_API_BASE, GAME_DOMAIN, mod_id);
+        let url = self.mod_url(mod_id);
         let response = self.client.get(&url).send().await?;
+        check_rate_limit(&response)?;
         
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("Failed to get mod info: {}", response.status()));
         }
         
-        // Print the response body for debugging
-        let text = response.text().await?;
-        println!("API Response for mod info: {}", text);
-        
-        // Parse the JSON text
-        let mod_info = serde_json::from_str(&text)?;
-        Ok(mod_info)
+        Ok(response.json().await?)
+    }
+
+    /// The current version of each mod, skipping mods that are removed,
+    /// hidden or have none. Any other failure, like an invalid API key or
+    /// the rate limit, stops the check.
+    pub async fn latest_versions(&self, mod_ids: &[i32]) -> Result<Vec<(i32, String)>> {
+        let mut versions = Vec::new();
+        for &mod_id in mod_ids {
+            let response = self.client.get(self.mod_url(mod_id)).send().await?;
+            check_rate_limit(&response)?;
+
+            let status = response.status();
+            if matches!(status, reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::GONE) {
+                continue;
+            }
+            if !status.is_success() {
+                return Err(anyhow::anyhow!("Failed to check mod {} for updates: {}", mod_id, status));
+            }
+
+            let mod_info: NexusModInfo = response.json().await?;
+            if let Some(version) = mod_info.version.filter(|_| mod_info.available) {
+                versions.push((mod_id, version));
+            }
+        }
+        Ok(versions)
+    }
+
+    pub async fn get_game_info(&self) -> Result<GameInfo> {
+        let url = format!("{}/games/{}.json", self.base_url, self.game_domain);
+        let response = self.client.get(&url).send().await?;
+        check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to get game info: {}", response.status()));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Returns the published mods of a feed; hidden and removed entries are skipped.
+    pub async fn get_mod_feed(&self, feed: ModFeed) -> Result<Vec<NexusModInfo>> {
+        let url = format!("{}/games/{}/mods/{}", self.base_url, self.game_domain, feed.endpoint());
+        let response = self.client.get(&url).send().await?;
+        check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to get {}: {}", feed.label(), response.status()));
+        }
+
+        let mods: Vec<NexusModInfo> = response.json().await?;
+        Ok(mods.into_iter().filter(|m| m.available).collect())
+    }
+
+    /// The v1 API has no per-category listing, so this merges all feeds
+    /// and keeps the mods of the requested category.
+    pub async fn get_mods_in_category(&self, category_id: i32) -> Result<Vec<NexusModInfo>> {
+        let mut mods: Vec<NexusModInfo> = Vec::new();
+        for feed in ModFeed::ALL {
+            for mod_info in self.get_mod_feed(feed).await? {
+                if mod_info.category_id == Some(category_id)
+                    && !mods.iter().any(|m| m.id == mod_info.id)
+                {
+                    mods.push(mod_info);
+                }
+            }
+        }
+        Ok(mods)
+    }
+
+    /// Changelog entries per version, newest version first.
+    pub async fn get_changelogs(&self, mod_id: i32) -> Result<Vec<(String, Vec<String>)>> {
+        let url = format!("{}/changelogs.json", self.mod_url(mod_id));
+        let response = self.client.get(&url).send().await?;
+        check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to get changelogs: {}", response.status()));
+        }
+
+        // Mods without a changelog return an empty array instead of an object
+        let value: serde_json::Value = response.json().await?;
+        let mut changelogs: Vec<(String, Vec<String>)> = match value {
+            serde_json::Value::Object(map) => map.into_iter()
+                .map(|(version, entries)| {
+                    let entries = serde_json::from_value(entries).unwrap_or_default();
+                    (version, entries)
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        changelogs.sort_by(|a, b| compare_versions(&b.0, &a.0));
+        Ok(changelogs)
+    }
+
+    /// Requirements declared on the mod page. Only available through the v2 GraphQL API.
+    pub async fn get_mod_requirements(&self, mod_id: i32) -> Result<Vec<ModRequirement>> {
+        let query = r#"
+            query ModRequirements($ids: [CompositeDomainWithIdInput!]!) {
+                legacyModsByDomain(ids: $ids) {
+                    nodes {
+                        modRequirements {
+                            nexusRequirements {
+                                nodes { modId modName url notes externalRequirement }
+                            }
+                        }
+                    }
+                }
+            }
+        "#;
+        let body = serde_json::json!({
+            "query": query,
+            "variables": {
+                "ids": [{ "gameDomain": self.game_domain, "modId": mod_id.to_string() }]
+            }
+        });
+
+        let response = self.client.post(&self.graphql_url).json(&body).send().await?;
+        check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to get mod requirements: {}", response.status()));
+        }
+
+        let value: serde_json::Value = response.json().await?;
+        if let Some(errors) = value.get("errors") {
+            return Err(anyhow::anyhow!("Failed to get mod requirements: {}", errors));
+        }
+
+        let nodes = value
+            .pointer("/data/legacyModsByDomain/nodes/0/modRequirements/nexusRequirements/nodes")
+            .cloned()
+            .unwrap_or(serde_json::Value::Array(Vec::new()));
+        Ok(serde_json::from_value(nodes)?)
+    }
+
+    pub async fn endorse_mod(&self, mod_id: i32, version: &str) -> Result<()> {
+        self.post_endorsement(mod_id, version, "endorse").await
+    }
+
+    pub async fn abstain_mod(&self, mod_id: i32, version: &str) -> Result<()> {
+        self.post_endorsement(mod_id, version, "abstain").await
+    }
+
+    async fn post_endorsement(&self, mod_id: i32, version: &str, action: &str) -> Result<()> {
+        let url = format!("{}/{}.json", self.mod_url(mod_id), action);
+        let response = self.client.post(&url)
+            .form(&[("version", version)])
+            .send()
+            .await?;
+        check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to {} mod: {}", action, response.status()));
+        }
+        Ok(())
+    }
+
+    /// Endorsement state of every mod the user has voted on, for this game only.
+    pub async fn get_endorsements(&self) -> Result<Vec<UserEndorsement>> {
+        let url = format!("{}/user/endorsements.json", self.base_url);
+        let response = self.client.get(&url).send().await?;
+        check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to get endorsements: {}", response.status()));
+        }
+
+        let endorsements: Vec<UserEndorsement> = response.json().await?;
+        Ok(endorsements.into_iter().filter(|e| e.domain_name == self.game_domain).collect())
+    }
+
+    /// Mods the user tracks on the website, for this game only.
+    pub async fn get_tracked_mods(&self) -> Result<Vec<TrackedMod>> {
+        let url = format!("{}/user/tracked_mods.json", self.base_url);
+        let response = self.client.get(&url).send().await?;
+        check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to get tracked mods: {}", response.status()));
+        }
+
+        let tracked: Vec<TrackedMod> = response.json().await?;
+        Ok(tracked.into_iter().filter(|t| t.domain_name == self.game_domain).collect())
+    }
+
+    pub async fn track_mod(&self, mod_id: i32) -> Result<()> {
+        let url = format!("{}/user/tracked_mods.json", self.base_url);
+        let response = self.client.post(&url)
+            .query(&[("domain_name", &self.game_domain)])
+            .form(&[("mod_id", mod_id.to_string())])
+            .send()
+            .await?;
+        check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to track mod: {}", response.status()));
+        }
+        Ok(())
+    }
+
+    pub async fn untrack_mod(&self, mod_id: i32) -> Result<()> {
+        let url = format!("{}/user/tracked_mods.json", self.base_url);
+        let response = self.client.delete(&url)
+            .query(&[("domain_name", &self.game_domain)])
+            .form(&[("mod_id", mod_id.to_string())])
+            .send()
+            .await?;
+        check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to untrack mod: {}", response.status()));
+        }
+        Ok(())
+    }
+
+    /// Fetches an image such as a mod thumbnail from the Nexus static CDN.
+    pub async fn fetch_image(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self.download_client.get(url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to fetch image: {}", response.status()));
+        }
+
+        Ok(response.bytes().await?.to_vec())
     }
 
     pub async fn get_mod_files(&self, mod_id: i32) -> Result<Vec<ModFile>> {
-        let url = format!("{}/games/{}/mods/{}/files", NEXUS_API_BASE, GAME_DOMAIN, mod_id);
+        let url = format!("{}/files", self.mod_url(mod_id));
         let response = self.client.get(&url).send().await?;
+        check_rate_limit(&response)?;
         
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("Failed to get mod files: {}", response.status()));
         }
         
-        // Print the response body for debugging
-        let text = response.text().await?;
-        println!("API Response for mod files: {}", text);
-        
-        // Parse the JSON text
-        let files_response: ModFilesResponse = serde_json::from_str(&text)?;
+        let files_response: ModFilesResponse = response.json().await?;
         Ok(files_response.files)
     }
 
-    pub async fn download_mod(&self, mod_id: i32, file_id: i32, nxm_info: Option<(String, i64)>) -> Result<Vec<u8>> {
-        let url = format!(
-            "{}/games/{}/mods/{}/files/{}/download_link.json",
-            NEXUS_API_BASE, GAME_DOMAIN, mod_id, file_id
-        );
+    pub async fn get_download_links(&self, mod_id: i32, file_id: i32, nxm_info: Option<(String, i64)>) -> Result<Vec<DownloadLink>> {
+        let url = format!("{}/files/{}/download_link.json", self.mod_url(mod_id), file_id);
         
         let mut query = Vec::new();
         if let Some((key, expires)) = nxm_info {
@@ -177,6 +587,7 @@ impl NexusClient {
             .header("accept", "application/json")
             .send()
             .await?;
+        check_rate_limit(&response)?;
         
         if response.status() == reqwest::StatusCode::FORBIDDEN {
             return Err(anyhow::anyhow!(
@@ -189,19 +600,18 @@ impl NexusClient {
             return Err(anyhow::anyhow!("Failed to get download link: {}", response.status()));
         }
         
-        // Print response for debugging
-        let text = response.text().await?;
-        println!("Download link response: {}", text);
-        
-        let download_links: Vec<DownloadLink> = serde_json::from_str(&text)?;
-        let download_url = download_links.first()
+        Ok(response.json().await?)
+    }
+
+    pub async fn download_mod(&self, mod_id: i32, file_id: i32, nxm_info: Option<(String, i64)>) -> Result<Vec<u8>> {
+        let download_links = self.get_download_links(mod_id, file_id, nxm_info).await?;
+        let download_url = select_download_link(&download_links, self.preferred_cdn.as_deref())
             .ok_or_else(|| anyhow::anyhow!("No download links available"))?;
         
-        // Download the actual file
-        let mod_response = self.client.get(&download_url.uri)
-        .header("apikey", &self.api_key)
-        .send()
-        .await?;
+        // Download the actual file. CDN links are pre-signed, so no API key is sent.
+        let mod_response = self.download_client.get(&download_url.uri)
+            .send()
+            .await?;
         
         if !mod_response.status().is_success() {
             return Err(anyhow::anyhow!("Failed to download mod: {}", mod_response.status()));
@@ -210,4 +620,74 @@ impl NexusClient {
         let mod_data = mod_response.bytes().await?;
         Ok(mod_data.to_vec())
     }
-} 
\ No newline at end of file
+}
+
+/// Link to a mod page on the website.
+pub fn mod_page_url(game_domain: &str, mod_id: i32) -> String {
+    format!("{}/{}/mods/{}", NEXUS_WEBSITE, game_domain, mod_id)
+}
+
+/// Compares mod version strings such as "1.10" and "1.9b" component by
+/// component. Each component is split into runs of digits and letters,
+/// so "10" > "9b" > "9a" > "9"; digit runs compare as numbers.
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parts = |v: &str| -> Vec<String> {
+        v.trim_start_matches(['v', 'V'])
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|p| !p.is_empty())
+            .flat_map(|p| {
+                let mut runs: Vec<String> = Vec::new();
+                for c in p.chars() {
+                    match runs.last_mut() {
+                        Some(run) if run.ends_with(|last: char| last.is_ascii_digit() == c.is_ascii_digit()) => run.push(c),
+                        _ => runs.push(c.to_string()),
+                    }
+                }
+                runs
+            })
+            .map(|p| p.to_ascii_lowercase())
+            .collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+
+    for (x, y) in a.iter().zip(b.iter()) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != std::cmp::Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// Picks the preferred CDN mirror if Nexus offered it, otherwise the first link.
+pub fn select_download_link<'a>(links: &'a [DownloadLink], preferred: Option<&str>) -> Option<&'a DownloadLink> {
+    preferred
+        .and_then(|preferred| {
+            links.iter().find(|link| {
+                link.short_name.eq_ignore_ascii_case(preferred)
+                    || link.name.eq_ignore_ascii_case(preferred)
+            })
+        })
+        .or_else(|| links.first())
+}
+
+fn check_rate_limit(response: &reqwest::Response) -> Result<()> {
+    if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Ok(());
+    }
+
+    let retry_after = response.headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| format!(" Retry after {} seconds.", value))
+        .unwrap_or_default();
+
+    Err(anyhow::anyhow!(
+        "Nexus API rate limit exceeded.{} \
+        Daily and hourly limits reset automatically.",
+        retry_after
+    ))
+}
@@ -0,0 +1,113 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use anyhow::{bail, Context, Result};
+
+const PAK_MAGIC: u32 = 0x5A6F12E1;
+/// Footer of v8+ paks: encryption key GUID, encrypted flag, magic, version,
+/// index offset, size and hash, then five compression method names.
+const FOOTER_SIZE: u64 = 16 + 1 + 4 + 4 + 8 + 8 + 20 + 5 * 32;
+const PATH_HASH_INDEX_VERSION: i32 = 10;
+/// Paths longer than this mean we are reading garbage.
+const MAX_STRING_LEN: u32 = 4096;
+
+/// Lists the asset paths stored in an Unreal pak, lowercased and without
+/// the `../../../` mount prefix so paths from different mods compare equal.
+///
+/// Only reads the v10/v11 full directory index used by UE5 games; older
+/// formats and encrypted indexes are reported as errors.
+pub fn list_assets(path: &Path) -> Result<Vec<String>> {
+    let mut file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let len = file.metadata()?.len();
+    if len < FOOTER_SIZE {
+        bail!("{} is too small to be a pak file", path.display());
+    }
+
+    file.seek(SeekFrom::Start(len - FOOTER_SIZE))?;
+    file.seek(SeekFrom::Current(16))?;
+    let encrypted = read_u8(&mut file)? != 0;
+    if read_u32(&mut file)? != PAK_MAGIC {
+        bail!("{} is not a supported pak file", path.display());
+    }
+    let version = read_i32(&mut file)?;
+    if version < PATH_HASH_INDEX_VERSION {
+        bail!("Pak version {} is not supported", version);
+    }
+    if encrypted {
+        bail!("{} has an encrypted index", path.display());
+    }
+    let index_offset = read_u64(&mut file)?;
+
+    file.seek(SeekFrom::Start(index_offset))?;
+    let mount_point = read_string(&mut file)?;
+    let _entry_count = read_i32(&mut file)?;
+    let _path_hash_seed = read_u64(&mut file)?;
+    if read_i32(&mut file)? != 0 {
+        // Path hash index offset, size and hash
+        file.seek(SeekFrom::Current(8 + 8 + 20))?;
+    }
+    if read_i32(&mut file)? == 0 {
+        bail!("{} has no directory index", path.display());
+    }
+    let directory_index_offset = read_u64(&mut file)?;
+
+    file.seek(SeekFrom::Start(directory_index_offset))?;
+    let mount_point = mount_point.trim_start_matches("../").trim_start_matches('/');
+    let mut assets = Vec::new();
+    for _ in 0..read_u32(&mut file)? {
+        let directory = read_string(&mut file)?;
+        for _ in 0..read_u32(&mut file)? {
+            let name = read_string(&mut file)?;
+            let _entry_location = read_i32(&mut file)?;
+            let directory = directory.trim_start_matches('/');
+            assets.push(format!("{}{}{}", mount_point, directory, name).to_lowercase());
+        }
+    }
+
+    Ok(assets)
+}
+
+fn read_u8(reader: &mut impl Read) -> Result<u8> {
+    let mut buf = [0; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_i32(reader: &mut impl Read) -> Result<i32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(i32::from_le_bytes(buf))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64> {
+    let mut buf = [0; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Reads an `FString`: a length including the terminator, negative for UTF-16.
+fn read_string(reader: &mut impl Read) -> Result<String> {
+    let len = read_i32(reader)?;
+    if len.unsigned_abs() > MAX_STRING_LEN {
+        bail!("Corrupt pak index");
+    }
+    let text = if len >= 0 {
+        let mut buf = vec![0; len as usize];
+        reader.read_exact(&mut buf)?;
+        String::from_utf8_lossy(&buf).into_owned()
+    } else {
+        let mut buf = vec![0; len.unsigned_abs() as usize * 2];
+        reader.read_exact(&mut buf)?;
+        let units: Vec<u16> = buf.chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+    Ok(text.trim_end_matches('\0').to_string())
+}
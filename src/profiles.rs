@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::Path;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use crate::mod_info::{ModId, ModInfo};
+
+/// A named set of enabled mods that can be switched to in one go.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// The enabled mods.
+    #[serde(default)]
+    pub mods: Vec<ModId>,
+    /// File names of the enabled mods, from profiles saved before mods had
+    /// IDs. Still honoured until the profile is saved again.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enabled: Vec<String>,
+}
+
+impl Profile {
+    pub fn capture(name: &str, mods: &[ModInfo]) -> Self {
+        Self {
+            name: name.to_string(),
+            mods: mods.iter()
+                .filter(|mod_info| mod_info.enabled)
+                .map(|mod_info| mod_info.id)
+                .collect(),
+            enabled: Vec::new(),
+        }
+    }
+
+    pub fn wants_enabled(&self, mod_info: &ModInfo) -> bool {
+        self.mods.contains(&mod_info.id)
+            || mod_info.file_name().is_some_and(|name| self.enabled.contains(&name))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    pub active: Option<String>,
+    pub profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    /// Reads the profiles, treating a missing file as none.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// Adds a profile or replaces the one with the same name.
+    pub fn insert(&mut self, profile: Profile) -> Result<()> {
+        if profile.name.trim().is_empty() {
+            bail!("A profile needs a name");
+        }
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.profiles.retain(|profile| profile.name != name);
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+    }
+}
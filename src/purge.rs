@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use crate::mod_info::{ModId, ModInfo};
+
+/// What a purge took out of the game folder, kept in the store so the mods
+/// can be put back exactly as they were.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PurgeState {
+    /// Unix time of the purge.
+    pub created: u64,
+    /// The mods that were enabled, as in a profile.
+    pub enabled: Vec<ModId>,
+    /// Files moved out of the game folder as they are, relative to it,
+    /// e.g. UE4SS.
+    pub moved: Vec<PathBuf>,
+}
+
+impl PurgeState {
+    pub fn capture(created: u64, mods: &[ModInfo]) -> Self {
+        Self {
+            created,
+            enabled: mods.iter()
+                .filter(|mod_info| mod_info.enabled)
+                .map(|mod_info| mod_info.id)
+                .collect(),
+            moved: Vec::new(),
+        }
+    }
+
+    pub fn wants_enabled(&self, mod_info: &ModInfo) -> bool {
+        self.enabled.contains(&mod_info.id)
+    }
+
+    /// Reads the state of the last purge, `None` when the mods are deployed.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
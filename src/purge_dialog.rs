@@ -0,0 +1,104 @@
+use gtk::prelude::*;
+use gtk::{MessageDialog, MessageType, ButtonsType, ResponseType, DialogFlags, Window, Button};
+use gtk::glib;
+use crate::mod_info::ModInfo;
+use crate::mod_list::{list_mods, replace_mods, ModList};
+use crate::mod_manager::ModManager;
+use crate::settings::Settings;
+
+pub const PURGE_LABEL: &str = "Purge Mods";
+pub const REDEPLOY_LABEL: &str = "Redeploy Mods";
+
+/// Takes every mod out of the game folder, or puts them back after a purge,
+/// asking first.
+pub fn purge_or_redeploy(parent: &impl IsA<Window>, mod_list: &ModList, button: &Button) {
+    let mod_manager = match ModManager::new(Settings::load()) {
+        Ok(mod_manager) => mod_manager,
+        Err(e) => {
+            show_message(parent, MessageType::Error, &format!("Failed to load the mods: {}", e), None);
+            return;
+        }
+    };
+    let purged = mod_manager.is_purged();
+
+    let (text, secondary, action) = if purged {
+        (
+            "Redeploy your mods?",
+            "The mods enabled before the purge are enabled again, and UE4SS is put back.",
+            "Redeploy",
+        )
+    } else {
+        (
+            "Make the game vanilla?",
+            "Paks and loose files are removed from the game folder, INI tweaks reverted and UE4SS moved out. \
+             Your mods stay installed, and Redeploy puts them back as they are now.",
+            "Purge",
+        )
+    };
+    let dialog = MessageDialog::new(
+        Some(parent),
+        DialogFlags::MODAL,
+        MessageType::Question,
+        ButtonsType::None,
+        text,
+    );
+    dialog.set_secondary_text(Some(secondary));
+    dialog.add_button("Cancel", ResponseType::Cancel);
+    dialog.add_button(action, ResponseType::Accept);
+    if !purged {
+        if let Some(widget) = dialog.widget_for_response(ResponseType::Accept) {
+            widget.add_css_class("destructive-action");
+        }
+    }
+
+    let parent = parent.upcast_ref::<Window>().clone();
+    dialog.connect_response(glib::clone!(@weak parent, @weak mod_list, @weak button => move |dialog, response| {
+        dialog.close();
+        if response != ResponseType::Accept {
+            return;
+        }
+
+        let mut mods: Vec<ModInfo> = list_mods(&mod_list);
+        let result = if purged {
+            mod_manager.redeploy(&mut mods).map(|()| None)
+        } else {
+            mod_manager.purge(&mut mods).map(Some)
+        };
+        let _ = mod_manager.save_mod_list(&mods);
+        replace_mods(&mod_list, &mods);
+        update_button(&button, &mod_manager);
+
+        match result {
+            Ok(None) => show_message(&parent, MessageType::Info, "Your mods are deployed again.", None),
+            Ok(Some(issues)) if issues.is_empty() => {
+                show_message(&parent, MessageType::Info, "The game folder is vanilla.", None)
+            }
+            Ok(Some(issues)) => show_message(
+                &parent,
+                MessageType::Warning,
+                "The mods were purged, but the game folder isn't vanilla yet",
+                Some(&format!("• {}", issues.join("\n• "))),
+            ),
+            Err(e) => show_message(&parent, MessageType::Error, &format!("Failed to {} the mods: {}", action.to_lowercase(), e), None),
+        }
+    }));
+    dialog.present();
+}
+
+/// Shows whether the button purges or redeploys.
+pub fn update_button(button: &Button, mod_manager: &ModManager) {
+    button.set_label(if mod_manager.is_purged() { REDEPLOY_LABEL } else { PURGE_LABEL });
+}
+
+fn show_message(parent: &impl IsA<Window>, message_type: MessageType, text: &str, secondary: Option<&str>) {
+    let dialog = MessageDialog::new(
+        Some(parent),
+        DialogFlags::MODAL,
+        message_type,
+        ButtonsType::Close,
+        text,
+    );
+    dialog.set_secondary_text(secondary);
+    dialog.connect_response(|dialog, _| dialog.close());
+    dialog.present();
+}
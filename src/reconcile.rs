@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+use crate::mod_info::{ModId, ModInfo};
+
+/// A difference between the mod list and the files on disk.
+#[derive(Clone)]
+pub enum Change {
+    /// A mod on disk without a list entry.
+    Added(Box<ModInfo>),
+    /// An entry whose files are gone.
+    Missing { id: ModId },
+    /// An entry whose files are somewhere else than recorded.
+    Moved { id: ModId, path: PathBuf },
+    /// An entry whose switch doesn't match the files, e.g. a pak put into or
+    /// taken out of `~mods` by hand.
+    EnabledMismatch { id: ModId, enabled_on_disk: bool },
+}
+
+impl Change {
+    /// Whether the files on disk settle it. Missing files may only be out
+    /// of reach, like on a drive that isn't mounted, so removing their
+    /// entries is left to the user.
+    pub fn is_unambiguous(&self) -> bool {
+        !matches!(self, Change::Missing { .. })
+    }
+
+    pub fn describe(&self, mods: &[ModInfo]) -> String {
+        let name = |id: &ModId| {
+            mods.iter()
+                .find(|mod_info| mod_info.id == *id)
+                .map(|mod_info| mod_info.name.as_str())
+                .unwrap_or_default()
+        };
+        match self {
+            Change::Added(mod_info) => format!("{} was found and is added", mod_info.name),
+            Change::Missing { id } => format!("{} is missing its files", name(id)),
+            Change::Moved { id, path } => format!("{} is now at {}", name(id), path.display()),
+            Change::EnabledMismatch { id, enabled_on_disk: true } => format!("{} is enabled on disk", name(id)),
+            Change::EnabledMismatch { id, enabled_on_disk: false } => format!("{} is disabled on disk", name(id)),
+        }
+    }
+}
+
+/// Everything that differs between a mod list and the disk.
+#[derive(Clone, Default)]
+pub struct Reconciliation {
+    pub changes: Vec<Change>,
+    /// Pak sizes to record, see `ModInfo::file_size`.
+    pub sizes: Vec<(ModId, u64)>,
+}
+
+impl Reconciliation {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.sizes.is_empty()
+    }
+
+    pub fn is_unambiguous(&self) -> bool {
+        self.changes.iter().all(Change::is_unambiguous)
+    }
+
+    /// `mods` with the changes applied. Missing entries are only removed
+    /// when `remove_missing` is set.
+    pub fn apply(&self, mods: &[ModInfo], remove_missing: bool) -> Vec<ModInfo> {
+        let mut updated = mods.to_vec();
+        for change in &self.changes {
+            match change {
+                Change::Added(mod_info) => updated.push(mod_info.as_ref().clone()),
+                Change::Missing { id } if remove_missing => updated.retain(|mod_info| mod_info.id != *id),
+                Change::Missing { .. } => {}
+                Change::Moved { id, path } => {
+                    if let Some(mod_info) = updated.iter_mut().find(|mod_info| mod_info.id == *id) {
+                        mod_info.installed_path = Some(path.clone());
+                    }
+                }
+                Change::EnabledMismatch { id, enabled_on_disk } => {
+                    if let Some(mod_info) = updated.iter_mut().find(|mod_info| mod_info.id == *id) {
+                        mod_info.set_enabled(*enabled_on_disk);
+                    }
+                }
+            }
+        }
+        for (id, size) in &self.sizes {
+            if let Some(mod_info) = updated.iter_mut().find(|mod_info| mod_info.id == *id) {
+                mod_info.file_size = Some(*size);
+            }
+        }
+        updated
+    }
+}
@@ -0,0 +1,88 @@
+use gtk::prelude::*;
+use gtk::{MessageDialog, MessageType, ButtonsType, ResponseType, DialogFlags, Window};
+use std::cell::RefCell;
+use crate::mod_info::{ModId, ModInfo};
+use crate::mod_list::{list_mods, replace_mods, ModList};
+use crate::mod_manager::ModManager;
+use crate::reconcile::Change;
+use crate::settings::Settings;
+
+thread_local! {
+    /// Missing mods the user chose to keep, not asked about again until restart.
+    static KEPT_MISSING: RefCell<Vec<ModId>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Brings the list in line with the files on disk. Changes the files settle
+/// are applied and saved right away; for mods whose files are missing the
+/// user is asked whether to drop them from the list.
+pub fn reconcile_list(mod_list: &ModList, mod_manager: &ModManager) {
+    let mods: Vec<ModInfo> = list_mods(mod_list);
+    let reconciliation = match mod_manager.reconcile(&mods) {
+        Ok(reconciliation) => reconciliation,
+        Err(e) => {
+            eprintln!("Failed to check the mod list against the disk: {}", e);
+            return;
+        }
+    };
+    if reconciliation.is_empty() {
+        return;
+    }
+
+    if !reconciliation.sizes.is_empty() || reconciliation.changes.iter().any(Change::is_unambiguous) {
+        let updated = reconciliation.apply(&mods, false);
+        if let Err(e) = mod_manager.save_mod_list(&updated) {
+            eprintln!("Failed to save the mod list: {}", e);
+        }
+        replace_mods(mod_list, &updated);
+    }
+    if reconciliation.is_unambiguous() {
+        return;
+    }
+
+    let missing: Vec<ModId> = reconciliation.changes.iter()
+        .filter_map(|change| match change {
+            Change::Missing { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    let already_asked = KEPT_MISSING.with(|kept| missing.iter().all(|id| kept.borrow().contains(id)));
+    if already_asked {
+        return;
+    }
+
+    let descriptions: Vec<String> = reconciliation.changes.iter()
+        .map(|change| change.describe(&mods))
+        .collect();
+    let window = mod_list.root().and_downcast::<Window>();
+    let dialog = MessageDialog::new(
+        window.as_ref(),
+        DialogFlags::MODAL,
+        MessageType::Question,
+        ButtonsType::None,
+        "Some mods are missing their files",
+    );
+    dialog.set_secondary_text(Some(&format!(
+        "• {}\n\nRemove the missing mods from the list, or keep them in case their files come back, \
+         e.g. from a drive that isn't connected?",
+        descriptions.join("\n• ")
+    )));
+    dialog.add_button("Keep", ResponseType::Cancel);
+    dialog.add_button("Remove from List", ResponseType::Accept);
+
+    let mod_list = mod_list.clone();
+    dialog.connect_response(move |dialog, response| {
+        dialog.close();
+        if response != ResponseType::Accept {
+            KEPT_MISSING.with(|kept| kept.borrow_mut().extend(missing.iter().copied()));
+            return;
+        }
+
+        let mut mods: Vec<ModInfo> = list_mods(&mod_list);
+        mods.retain(|mod_info| !missing.contains(&mod_info.id));
+        if let Err(e) = ModManager::new(Settings::load()).and_then(|mod_manager| mod_manager.save_mod_list(&mods)) {
+            eprintln!("Failed to save the mod list: {}", e);
+        }
+        replace_mods(&mod_list, &mods);
+    });
+    dialog.present();
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::dependencies::DependencyTarget;
+use crate::mod_info::{ModId, ModInfo, ModKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    IncompatibleWith,
+    LoadAfter,
+}
+
+impl RuleKind {
+    pub const ALL: [RuleKind; 2] = [RuleKind::IncompatibleWith, RuleKind::LoadAfter];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RuleKind::IncompatibleWith => "Incompatible with",
+            RuleKind::LoadAfter => "Loads after",
+        }
+    }
+}
+
+/// A user-declared relation between a mod and another one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModRule {
+    pub kind: RuleKind,
+    pub target: DependencyTarget,
+    /// Display name of the other mod.
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum RuleViolation {
+    Incompatible { mod_id: ModId, mod_name: String, other_id: ModId, other: String },
+    LoadOrder { mod_id: ModId, mod_name: String, after_id: ModId, after: String },
+}
+
+impl RuleViolation {
+    /// Incompatible mods are never enabled together, load order problems
+    /// only warrant a warning.
+    pub fn is_blocking(&self) -> bool {
+        matches!(self, RuleViolation::Incompatible { .. })
+    }
+
+    pub fn involves(&self, id: ModId) -> bool {
+        match self {
+            RuleViolation::Incompatible { mod_id, other_id, .. } => *mod_id == id || *other_id == id,
+            RuleViolation::LoadOrder { mod_id, after_id, .. } => *mod_id == id || *after_id == id,
+        }
+    }
+}
+
+impl fmt::Display for RuleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleViolation::Incompatible { mod_name, other, .. } => {
+                write!(f, "{} is incompatible with {}", mod_name, other)
+            }
+            RuleViolation::LoadOrder { mod_name, after, .. } => {
+                write!(f, "{} must load after {}", mod_name, after)
+            }
+        }
+    }
+}
+
+/// Indices of the enabled paks in the order the game mounts them. Paks in
+/// `~mods` are mounted by file name, later ones overriding earlier ones;
+/// the comparison ignores case like the Windows file system does.
+pub fn load_order(mods: &[ModInfo]) -> Vec<usize> {
+    let mut order: Vec<usize> = enabled(mods)
+        .into_iter()
+        .filter(|&index| mods[index].kind == ModKind::Pak)
+        .collect();
+    order.sort_by_key(|&index| sort_key(&mods[index]));
+    order
+}
+
+fn enabled(mods: &[ModInfo]) -> Vec<usize> {
+    (0..mods.len())
+        .filter(|&index| mods[index].enabled && mods[index].installed_path.is_some())
+        .collect()
+}
+
+fn sort_key(mod_info: &ModInfo) -> String {
+    mod_info.installed_path.as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Rule violations among the enabled mods. Load-after rules only apply
+/// between paks, the only mods with a load order.
+pub fn validate(mods: &[ModInfo]) -> Vec<RuleViolation> {
+    let order = load_order(mods);
+    let position = |index: usize| order.iter().position(|&i| i == index);
+    let enabled = enabled(mods);
+    let mut violations = Vec::new();
+
+    for &index in &enabled {
+        let mod_info = &mods[index];
+        for rule in &mod_info.rules {
+            for &other in &enabled {
+                if other == index || !rule.target.matches(&mods[other]) {
+                    continue;
+                }
+                match rule.kind {
+                    RuleKind::IncompatibleWith => {
+                        // Often declared on both sides, report the pair once
+                        let reported = violations.iter().any(|v| matches!(
+                            v,
+                            RuleViolation::Incompatible { mod_id, other_id, .. }
+                                if *mod_id == mods[other].id && *other_id == mod_info.id
+                        ));
+                        if !reported {
+                            violations.push(RuleViolation::Incompatible {
+                                mod_id: mod_info.id,
+                                mod_name: mod_info.name.clone(),
+                                other_id: mods[other].id,
+                                other: mods[other].name.clone(),
+                            });
+                        }
+                    }
+                    RuleKind::LoadAfter => {
+                        if let (Some(mod_position), Some(other_position)) = (position(index), position(other)) {
+                            if mod_position < other_position {
+                                violations.push(RuleViolation::LoadOrder {
+                                    mod_id: mod_info.id,
+                                    mod_name: mod_info.name.clone(),
+                                    after_id: mods[other].id,
+                                    after: mods[other].name.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+/// Violations involving `mods[index]` once it is enabled.
+pub fn check_enable(mods: &[ModInfo], index: usize) -> Vec<RuleViolation> {
+    let mut mods = mods.to_vec();
+    let Some(mod_info) = mods.get_mut(index) else {
+        return Vec::new();
+    };
+    mod_info.enabled = true;
+    let id = mod_info.id;
+
+    validate(&mods)
+        .into_iter()
+        .filter(|violation| violation.involves(id))
+        .collect()
+}
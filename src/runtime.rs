@@ -0,0 +1,23 @@
+use std::future::Future;
+use std::sync::OnceLock;
+use tokio::runtime::Runtime;
+
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| Runtime::new().expect("Failed to create Tokio runtime"))
+}
+
+/// Runs `future` on the shared Tokio runtime and returns a channel that yields
+/// its output, so GTK code can await network calls from
+/// `glib::spawn_future_local` without blocking the main loop.
+pub fn spawn<F>(future: F) -> async_channel::Receiver<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let (sender, receiver) = async_channel::bounded(1);
+    runtime().spawn(async move {
+        let _ = sender.send(future.await).await;
+    });
+    receiver
+}
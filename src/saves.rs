@@ -0,0 +1,162 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use zip::{ZipArchive, ZipWriter, write::FileOptions};
+
+/// Describes a snapshot inside its archive.
+const SNAPSHOT_FILE: &str = "snapshot.json";
+/// Folder inside the archive holding the save files.
+const SAVES_PREFIX: &str = "SaveGames/";
+
+/// A mod as it was when a snapshot was taken.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotMod {
+    pub name: String,
+    pub version: String,
+    pub enabled: bool,
+}
+
+/// A backup of the save folder along with the mod setup it was played with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The archive this snapshot was read from.
+    #[serde(skip)]
+    pub path: PathBuf,
+    /// Unix time the snapshot was taken.
+    pub created: u64,
+    /// Why it was taken, e.g. "Before enabling X".
+    pub reason: String,
+    pub profile: Option<String>,
+    pub mods: Vec<SnapshotMod>,
+}
+
+/// Zips every file of `saves_dir` into a new archive in `backups_dir`.
+pub fn create(saves_dir: &Path, backups_dir: &Path, mut snapshot: Snapshot) -> Result<Snapshot> {
+    if !saves_dir.is_dir() {
+        bail!("The save folder was not found: {}", saves_dir.display());
+    }
+    fs::create_dir_all(backups_dir)?;
+
+    let mut path = backups_dir.join(format!("saves-{}.zip", snapshot.created));
+    let mut counter = 1;
+    while path.exists() {
+        path = backups_dir.join(format!("saves-{}-{}.zip", snapshot.created, counter));
+        counter += 1;
+    }
+
+    let mut zip = ZipWriter::new(fs::File::create(&path)?);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file(SNAPSHOT_FILE, options)?;
+    zip.write_all(serde_json::to_string_pretty(&snapshot)?.as_bytes())?;
+
+    for file in files_in(saves_dir)? {
+        let relative = file.strip_prefix(saves_dir)?;
+        let name = relative.iter()
+            .map(|part| part.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        zip.start_file(format!("{}{}", SAVES_PREFIX, name), options)?;
+        std::io::copy(&mut fs::File::open(&file)?, &mut zip)?;
+    }
+    zip.finish()?;
+
+    snapshot.path = path;
+    Ok(snapshot)
+}
+
+/// Snapshots in `backups_dir`, newest first. Unreadable archives are skipped.
+pub fn list(backups_dir: &Path) -> Vec<Snapshot> {
+    let Ok(entries) = fs::read_dir(backups_dir) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<Snapshot> = entries.filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "zip"))
+        .filter_map(|path| read(&path).ok())
+        .collect();
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created));
+    snapshots
+}
+
+pub fn read(path: &Path) -> Result<Snapshot> {
+    let mut archive = ZipArchive::new(fs::File::open(path)?)?;
+    let mut json = String::new();
+    archive.by_name(SNAPSHOT_FILE)?.read_to_string(&mut json)?;
+    let mut snapshot: Snapshot = serde_json::from_str(&json)?;
+    snapshot.path = path.to_path_buf();
+    Ok(snapshot)
+}
+
+/// Replaces the contents of `saves_dir` with the saves in a snapshot. The
+/// saves are extracted next to the folder first, so a damaged archive leaves
+/// the current saves untouched.
+pub fn restore(path: &Path, saves_dir: &Path) -> Result<()> {
+    let staging = saves_dir.with_extension("restore");
+    let old = saves_dir.with_extension("old");
+    let _ = fs::remove_dir_all(&staging);
+
+    if let Err(e) = extract_saves(path, &staging) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    let _ = fs::remove_dir_all(&old);
+    if saves_dir.exists() {
+        if let Err(e) = fs::rename(saves_dir, &old) {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e.into());
+        }
+    }
+    if let Err(e) = fs::rename(&staging, saves_dir) {
+        let _ = fs::rename(&old, saves_dir);
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e.into());
+    }
+    let _ = fs::remove_dir_all(&old);
+    Ok(())
+}
+
+/// Extracts the saves of a snapshot into `staging`. Archives without a
+/// snapshot description are refused rather than restored as an empty folder.
+fn extract_saves(path: &Path, staging: &Path) -> Result<()> {
+    let mut archive = ZipArchive::new(fs::File::open(path)?)?;
+    if archive.by_name(SNAPSHOT_FILE).is_err() {
+        bail!("{} is not a save snapshot", path.display());
+    }
+    fs::create_dir_all(staging)?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(relative) = entry.enclosed_name()
+            .and_then(|name| name.strip_prefix(SAVES_PREFIX).ok())
+            .map(Path::to_path_buf)
+        else {
+            continue;
+        };
+        if entry.is_dir() || relative.as_os_str().is_empty() {
+            continue;
+        }
+        let out_path = staging.join(relative);
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut fs::File::create(&out_path)?)?;
+    }
+    Ok(())
+}
+
+fn files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(files_in(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,294 @@
+use gtk::prelude::*;
+use gtk::{Window, ScrolledWindow, Box, Label, Entry, Button, DropDown, Orientation};
+use gtk::glib::{self, clone};
+use crate::mod_info::ModInfo;
+use crate::mod_list::{list_mods, replace_mods, ModList};
+use crate::mod_manager::ModManager;
+use crate::profiles::{Profile, Profiles};
+use crate::saves::{self, Snapshot};
+use crate::settings::Settings;
+
+/// Switches between mod profiles and backs up or restores the save folder.
+pub fn show_saves_window(parent: &impl IsA<Window>, mod_list: &ModList) {
+    let window = Window::builder()
+        .title("Profiles & Saves")
+        .transient_for(parent)
+        .default_width(640)
+        .default_height(520)
+        .build();
+
+    let main_box = Box::new(Orientation::Vertical, 12);
+    main_box.set_margin_start(12);
+    main_box.set_margin_end(12);
+    main_box.set_margin_top(12);
+    main_box.set_margin_bottom(12);
+
+    // Profiles
+    main_box.append(&heading("Profiles"));
+    let active_label = Label::new(None);
+    active_label.set_xalign(0.0);
+    active_label.add_css_class("dim-label");
+    main_box.append(&active_label);
+
+    let profile_box = Box::new(Orientation::Horizontal, 6);
+    let profile_dropdown = DropDown::from_strings(&[]);
+    profile_dropdown.set_hexpand(true);
+    let activate_button = Button::with_label("Activate");
+    activate_button.add_css_class("suggested-action");
+    let delete_profile_button = Button::from_icon_name("user-trash-symbolic");
+    delete_profile_button.set_tooltip_text(Some("Delete profile"));
+    profile_box.append(&profile_dropdown);
+    profile_box.append(&activate_button);
+    profile_box.append(&delete_profile_button);
+    main_box.append(&profile_box);
+
+    let new_profile_box = Box::new(Orientation::Horizontal, 6);
+    let profile_entry = Entry::new();
+    profile_entry.set_placeholder_text(Some("Profile name"));
+    profile_entry.set_hexpand(true);
+    let save_profile_button = Button::with_label("Save Current Mods");
+    new_profile_box.append(&profile_entry);
+    new_profile_box.append(&save_profile_button);
+    main_box.append(&new_profile_box);
+
+    // Save backups
+    main_box.append(&heading("Save Backups"));
+    let mod_manager = ModManager::new(Settings::load()).ok();
+    let saves_path = mod_manager.as_ref().and_then(ModManager::saves_path);
+    let location = Label::new(Some(&match &saves_path {
+        Some(path) => format!("Save folder: {}", path.display()),
+        None => "The game's save folder was not found. Set the user data folder in Settings.".to_string(),
+    }));
+    location.set_xalign(0.0);
+    location.set_wrap(true);
+    location.add_css_class("dim-label");
+    main_box.append(&location);
+
+    let backup_button = Button::with_label("Back Up Now");
+    backup_button.set_halign(gtk::Align::Start);
+    backup_button.set_sensitive(saves_path.is_some());
+    main_box.append(&backup_button);
+
+    let snapshots_box = Box::new(Orientation::Vertical, 6);
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+    scrolled.set_vexpand(true);
+    scrolled.set_child(Some(&snapshots_box));
+    main_box.append(&scrolled);
+
+    let status_label = Label::new(None);
+    status_label.set_xalign(0.0);
+    status_label.set_wrap(true);
+    main_box.append(&status_label);
+
+    window.set_child(Some(&main_box));
+
+    render_profiles(&profile_dropdown, &active_label);
+    render_snapshots(&snapshots_box, &status_label, mod_list);
+
+    save_profile_button.connect_clicked(clone!(@weak mod_list, @weak profile_entry, @weak profile_dropdown, @weak active_label, @weak status_label => move |_| {
+        let name = profile_entry.text().trim().to_string();
+        let mods = current_mods(&mod_list);
+        let result = ModManager::new(Settings::load()).and_then(|mod_manager| {
+            let path = mod_manager.profiles_path();
+            let mut profiles = Profiles::load(&path)?;
+            profiles.insert(Profile::capture(&name, &mods))?;
+            // The mods on disk already match the new profile
+            profiles.active = Some(name.clone());
+            profiles.save(&path)
+        });
+        match result {
+            Ok(()) => {
+                profile_entry.set_text("");
+                status_label.set_text(&format!("Saved profile {}.", name));
+            },
+            Err(e) => status_label.set_text(&format!("Failed to save profile: {}", e)),
+        }
+        render_profiles(&profile_dropdown, &active_label);
+    }));
+
+    activate_button.connect_clicked(clone!(@weak mod_list, @weak profile_dropdown, @weak active_label, @weak snapshots_box, @weak status_label => move |_| {
+        let Some(name) = selected_profile(&profile_dropdown) else {
+            return;
+        };
+        let mut mods = current_mods(&mod_list);
+        let result = ModManager::new(Settings::load()).and_then(|mod_manager| {
+            let result = mod_manager.activate_profile(&name, &mut mods);
+            // Mods toggled before a failure still need their new state
+            let _ = mod_manager.save_mod_list(&mods);
+            result
+        });
+        replace_mods(&mod_list, &mods);
+        match result {
+            Ok(()) => status_label.set_text(&format!("Activated profile {}.", name)),
+            Err(e) => status_label.set_text(&format!("Failed to activate profile: {}", e)),
+        }
+        render_profiles(&profile_dropdown, &active_label);
+        render_snapshots(&snapshots_box, &status_label, &mod_list);
+    }));
+
+    delete_profile_button.connect_clicked(clone!(@weak profile_dropdown, @weak active_label, @weak status_label => move |_| {
+        let Some(name) = selected_profile(&profile_dropdown) else {
+            return;
+        };
+        let result = ModManager::new(Settings::load()).and_then(|mod_manager| {
+            let path = mod_manager.profiles_path();
+            let mut profiles = Profiles::load(&path)?;
+            profiles.remove(&name);
+            profiles.save(&path)
+        });
+        if let Err(e) = result {
+            status_label.set_text(&format!("Failed to delete profile: {}", e));
+        }
+        render_profiles(&profile_dropdown, &active_label);
+    }));
+
+    backup_button.connect_clicked(clone!(@weak mod_list, @weak snapshots_box, @weak status_label => move |_| {
+        let mods = current_mods(&mod_list);
+        let result = ModManager::new(Settings::load())
+            .and_then(|mod_manager| mod_manager.backup_saves("Manual backup", &mods));
+        match result {
+            Ok(_) => status_label.set_text("Saves backed up."),
+            Err(e) => status_label.set_text(&format!("Failed to back up saves: {}", e)),
+        }
+        render_snapshots(&snapshots_box, &status_label, &mod_list);
+    }));
+
+    window.present();
+}
+
+fn render_profiles(dropdown: &DropDown, active_label: &Label) {
+    let profiles = ModManager::new(Settings::load())
+        .and_then(|mod_manager| Profiles::load(&mod_manager.profiles_path()))
+        .unwrap_or_default();
+    let names: Vec<&str> = profiles.profiles.iter().map(|profile| profile.name.as_str()).collect();
+    dropdown.set_model(Some(&gtk::StringList::new(&names)));
+    if let Some(index) = profiles.active.as_ref().and_then(|active| names.iter().position(|name| name == active)) {
+        dropdown.set_selected(index as u32);
+    }
+    active_label.set_text(&match &profiles.active {
+        Some(active) => format!("Active profile: {}", active),
+        None if names.is_empty() => "No profiles yet. Save the current mods as one below.".to_string(),
+        None => "No profile active.".to_string(),
+    });
+}
+
+fn render_snapshots(container: &Box, status_label: &Label, mod_list: &ModList) {
+    while let Some(child) = container.first_child() {
+        container.remove(&child);
+    }
+
+    let snapshots = ModManager::new(Settings::load())
+        .and_then(|mod_manager| mod_manager.save_backups_path())
+        .map(|path| saves::list(&path))
+        .unwrap_or_default();
+    if snapshots.is_empty() {
+        let empty = Label::new(Some("No backups yet."));
+        empty.add_css_class("dim-label");
+        empty.set_xalign(0.0);
+        container.append(&empty);
+        return;
+    }
+
+    for snapshot in snapshots {
+        let line = Box::new(Orientation::Horizontal, 6);
+        let text = Box::new(Orientation::Vertical, 2);
+        text.set_hexpand(true);
+
+        let title = Label::new(Some(&format!("{} · {}", format_time(snapshot.created), snapshot.reason)));
+        title.set_xalign(0.0);
+        title.set_wrap(true);
+        text.append(&title);
+
+        let enabled: Vec<&str> = snapshot.mods.iter()
+            .filter(|mod_info| mod_info.enabled)
+            .map(|mod_info| mod_info.name.as_str())
+            .collect();
+        let summary = match &snapshot.profile {
+            Some(profile) => format!("Profile {} · {} mods enabled", profile, enabled.len()),
+            None => format!("{} mods enabled", enabled.len()),
+        };
+        let summary_label = Label::new(Some(&summary));
+        summary_label.add_css_class("dim-label");
+        summary_label.set_xalign(0.0);
+        summary_label.set_tooltip_text(Some(&enabled.join("\n")));
+        text.append(&summary_label);
+
+        let restore_button = Button::with_label("Restore");
+        restore_button.set_valign(gtk::Align::Center);
+        let delete_button = Button::from_icon_name("user-trash-symbolic");
+        delete_button.set_valign(gtk::Align::Center);
+        delete_button.set_tooltip_text(Some("Delete backup"));
+
+        line.append(&text);
+        line.append(&restore_button);
+        line.append(&delete_button);
+        container.append(&line);
+
+        restore_button.connect_clicked(clone!(@weak container, @weak status_label, @weak mod_list, @strong snapshot => move |_| {
+            confirm_restore(&container, &status_label, &mod_list, &snapshot);
+        }));
+
+        delete_button.connect_clicked(clone!(@weak container, @weak status_label, @weak mod_list, @strong snapshot => move |_| {
+            if let Err(e) = std::fs::remove_file(&snapshot.path) {
+                status_label.set_text(&format!("Failed to delete backup: {}", e));
+            }
+            render_snapshots(&container, &status_label, &mod_list);
+        }));
+    }
+}
+
+/// Restoring overwrites the current saves, so it is confirmed first.
+fn confirm_restore(container: &Box, status_label: &Label, mod_list: &ModList, snapshot: &Snapshot) {
+    let Some(window) = container.root().and_downcast::<Window>() else {
+        return;
+    };
+    let dialog = gtk::MessageDialog::new(
+        Some(&window),
+        gtk::DialogFlags::MODAL,
+        gtk::MessageType::Question,
+        gtk::ButtonsType::OkCancel,
+        format!(
+            "Replace the current saves with the backup from {}? The current saves are backed up first.",
+            format_time(snapshot.created)
+        ),
+    );
+    dialog.connect_response(clone!(@weak container, @weak status_label, @weak mod_list, @strong snapshot => move |dialog, response| {
+        if response == gtk::ResponseType::Ok {
+            let mods = current_mods(&mod_list);
+            let result = ModManager::new(Settings::load())
+                .and_then(|mod_manager| mod_manager.restore_saves(&snapshot, &mods));
+            match result {
+                Ok(()) => status_label.set_text("Saves restored."),
+                Err(e) => status_label.set_text(&format!("Failed to restore saves: {}", e)),
+            }
+            render_snapshots(&container, &status_label, &mod_list);
+        }
+        dialog.close();
+    }));
+    dialog.present();
+}
+
+fn selected_profile(dropdown: &DropDown) -> Option<String> {
+    dropdown.selected_item()
+        .and_downcast::<gtk::StringObject>()
+        .map(|item| item.string().to_string())
+}
+
+fn current_mods(mod_list: &ModList) -> Vec<ModInfo> {
+    list_mods(mod_list)
+}
+
+fn format_time(unix: u64) -> String {
+    glib::DateTime::from_unix_local(unix as i64)
+        .and_then(|time| time.format("%Y-%m-%d %H:%M"))
+        .map(|text| text.to_string())
+        .unwrap_or_else(|_| unix.to_string())
+}
+
+fn heading(text: &str) -> Label {
+    let label = Label::new(Some(text));
+    label.add_css_class("title-3");
+    label.set_xalign(0.0);
+    label
+}
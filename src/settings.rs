@@ -1,10 +1,30 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use crate::launcher::LaunchMethod;
+use crate::staging::DeployMethod;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Settings {
     pub game_path: Option<PathBuf>,
     pub nexus_api_key: Option<String>,
+    pub preferred_cdn: Option<String>,
+    /// The game's `AppData/Local/Stalker2` folder, when it isn't found
+    /// automatically (e.g. a Wine prefix in a custom location).
+    #[serde(default)]
+    pub user_data_path: Option<PathBuf>,
+    /// Snapshot the saves before switching profiles or enabling a mod
+    /// marked as save-breaking.
+    #[serde(default)]
+    pub auto_backup_saves: bool,
+    #[serde(default)]
+    pub launch_method: LaunchMethod,
+    /// The store every installed pak lives in, instead of the manager's
+    /// data folder. May be on another drive.
+    #[serde(default)]
+    pub mod_storage_path: Option<PathBuf>,
+    /// How enabled paks are put into `~mods` from the store.
+    #[serde(default)]
+    pub deploy_method: DeployMethod,
 }
 
 impl Settings {
@@ -37,6 +57,12 @@ impl Default for Settings {
         Self {
             game_path: None,
             nexus_api_key: None,
+            preferred_cdn: None,
+            user_data_path: None,
+            auto_backup_saves: false,
+            launch_method: LaunchMethod::default(),
+            mod_storage_path: None,
+            deploy_method: DeployMethod::default(),
         }
     }
 } 
\ No newline at end of file
@@ -2,9 +2,27 @@ use gtk::prelude::*;
 use gtk::{Dialog, Box, Label, Entry, Switch, ResponseType, Orientation, Button, FileChooserDialog, FileChooserAction, FileFilter, Window};
 use crate::settings::Settings;
 use crate::docs_window::show_docs_window;
-use crate::mod_manager::ModManager;
+use crate::mod_manager::{self, ModManager};
+use crate::user_data;
+use crate::launcher::LaunchMethod;
+use crate::staging::DeployMethod;
 use gtk::glib;
 
+// First entry means "use whatever Nexus lists first"
+const CDN_MIRRORS: &[&str] = &[
+    "Automatic",
+    "Nexus CDN",
+    "Amsterdam",
+    "Prague",
+    "Chicago",
+    "Los Angeles",
+    "Paris",
+    "Frankfurt",
+    "London",
+    "Miami",
+    "Singapore",
+];
+
 pub fn show_settings_dialog(parent: &impl IsA<Window>) {
     let dialog = Dialog::builder()
         .title("Settings")
@@ -30,6 +48,63 @@ pub fn show_settings_dialog(parent: &impl IsA<Window>) {
     path_box.append(&path_display);
     content.append(&path_box);
 
+    // User data folder (saves, config, logs); empty means detect it
+    let user_data_box = Box::new(Orientation::Horizontal, 12);
+    let user_data_label = Label::new(Some("User Data:"));
+    let user_data_entry = Entry::new();
+    user_data_entry.set_placeholder_text(Some("Detected automatically"));
+    user_data_entry.set_hexpand(true);
+    let user_data_button = Button::from_icon_name("folder-open-symbolic");
+    user_data_button.set_tooltip_text(Some("Select the Stalker2 folder in AppData/Local"));
+    let detect_button = Button::with_label("Detect");
+    user_data_box.append(&user_data_label);
+    user_data_box.append(&user_data_entry);
+    user_data_box.append(&user_data_button);
+    user_data_box.append(&detect_button);
+    content.append(&user_data_box);
+
+    // The store every installed pak lives in; empty means the manager's data folder
+    let storage_box = Box::new(Orientation::Horizontal, 12);
+    let storage_label = Label::new(Some("Mod Storage:"));
+    let storage_entry = Entry::new();
+    storage_entry.set_placeholder_text(Some("The mod manager's data folder"));
+    storage_entry.set_hexpand(true);
+    let storage_button = Button::from_icon_name("folder-open-symbolic");
+    storage_button.set_tooltip_text(Some("Select a folder to keep installed mods in, on any drive"));
+    storage_box.append(&storage_label);
+    storage_box.append(&storage_entry);
+    storage_box.append(&storage_button);
+    content.append(&storage_box);
+
+    // How enabled paks get from the store into ~mods
+    let deploy_box = Box::new(Orientation::Horizontal, 12);
+    let deploy_label = Label::new(Some("Deploy With:"));
+    let deploy_dropdown = gtk::DropDown::from_strings(&DeployMethod::LABELS);
+    deploy_dropdown.set_tooltip_text(Some("Applies to mods enabled from now on"));
+    deploy_box.append(&deploy_label);
+    deploy_box.append(&deploy_dropdown);
+    content.append(&deploy_box);
+
+    // How the Play button starts the game
+    let launch_box = Box::new(Orientation::Horizontal, 12);
+    let launch_label = Label::new(Some("Launch With:"));
+    let launch_dropdown = gtk::DropDown::from_strings(&LaunchMethod::LABELS);
+    let launch_entry = Entry::new();
+    launch_entry.set_hexpand(true);
+    launch_box.append(&launch_label);
+    launch_box.append(&launch_dropdown);
+    launch_box.append(&launch_entry);
+    content.append(&launch_box);
+
+    // Automatic save backups
+    let backup_box = Box::new(Orientation::Horizontal, 12);
+    let backup_label = Label::new(Some("Back Up Saves Automatically:"));
+    let backup_switch = Switch::new();
+    backup_switch.set_tooltip_text(Some("Before switching profiles or enabling a save-breaking mod"));
+    backup_box.append(&backup_label);
+    backup_box.append(&backup_switch);
+    content.append(&backup_box);
+
     // Nexus API key
     let api_box = Box::new(Orientation::Horizontal, 12);
     let api_label = Label::new(Some("Nexus API Key:"));
@@ -39,6 +114,14 @@ pub fn show_settings_dialog(parent: &impl IsA<Window>) {
     api_box.append(&api_entry);
     content.append(&api_box);
 
+    // Preferred CDN mirror
+    let cdn_box = Box::new(Orientation::Horizontal, 12);
+    let cdn_label = Label::new(Some("Download Mirror:"));
+    let cdn_dropdown = gtk::DropDown::from_strings(CDN_MIRRORS);
+    cdn_box.append(&cdn_label);
+    cdn_box.append(&cdn_dropdown);
+    content.append(&cdn_box);
+
     // docs button
     let docs_button = Button::with_label("Documentation");
     content.append(&docs_button);
@@ -110,11 +193,7 @@ pub fn show_settings_dialog(parent: &impl IsA<Window>) {
                         match mod_manager.import_mods(&path) {
                             Ok(_) => {
                                 if let Some(parent) = dialog.transient_for() {
-                                    unsafe {
-                                        if let Some(sender) = parent.data::<glib::Sender<()>>("refresh_sender") {
-                                            sender.as_ref().send(()).expect("Failed to send refresh signal");
-                                        }
-                                    }
+                                    request_refresh(&parent);
                                 }
                             },
                             Err(e) => eprintln!("Failed to import mods: {}", e),
@@ -133,9 +212,28 @@ pub fn show_settings_dialog(parent: &impl IsA<Window>) {
     if let Some(path) = settings.game_path.as_ref() {
         path_display.set_text(&path.to_string_lossy());
     }
+    if let Some(path) = settings.user_data_path.as_ref() {
+        user_data_entry.set_text(&path.to_string_lossy());
+    }
+    if let Some(path) = settings.mod_storage_path.as_ref() {
+        storage_entry.set_text(&path.to_string_lossy());
+    }
+    deploy_dropdown.set_selected(settings.deploy_method.index() as u32);
+    backup_switch.set_active(settings.auto_backup_saves);
+    launch_dropdown.set_selected(settings.launch_method.index() as u32);
+    launch_entry.set_text(settings.launch_method.value());
+    update_launch_entry(&launch_entry, launch_dropdown.selected());
+    launch_dropdown.connect_selected_notify(glib::clone!(@weak launch_entry => move |dropdown| {
+        update_launch_entry(&launch_entry, dropdown.selected());
+    }));
     if let Some(key) = settings.nexus_api_key.as_ref() {
         api_entry.set_text(key);
     }
+    if let Some(cdn) = settings.preferred_cdn.as_ref() {
+        if let Some(index) = CDN_MIRRORS.iter().position(|m| m.eq_ignore_ascii_case(cdn)) {
+            cdn_dropdown.set_selected(index as u32);
+        }
+    }
 
     // Setup file chooser dialog
     path_button.connect_clicked(glib::clone!(@weak dialog, @weak path_display => move |_| {
@@ -158,6 +256,61 @@ pub fn show_settings_dialog(parent: &impl IsA<Window>) {
         file_chooser.show();
     }));
 
+    user_data_button.connect_clicked(glib::clone!(@weak dialog, @weak user_data_entry => move |_| {
+        let file_chooser = FileChooserDialog::new(
+            Some("Select User Data Folder"),
+            Some(&dialog),
+            FileChooserAction::SelectFolder,
+            &[("Cancel", ResponseType::Cancel), ("Select", ResponseType::Accept)]
+        );
+
+        file_chooser.connect_response(glib::clone!(@weak user_data_entry => move |file_chooser, response| {
+            if response == ResponseType::Accept {
+                if let Some(path) = file_chooser.file().and_then(|file| file.path()) {
+                    user_data_entry.set_text(&path.to_string_lossy());
+                }
+            }
+            file_chooser.close();
+        }));
+
+        file_chooser.show();
+    }));
+
+    storage_button.connect_clicked(glib::clone!(@weak dialog, @weak storage_entry => move |_| {
+        let file_chooser = FileChooserDialog::new(
+            Some("Select Mod Storage Folder"),
+            Some(&dialog),
+            FileChooserAction::SelectFolder,
+            &[("Cancel", ResponseType::Cancel), ("Select", ResponseType::Accept)]
+        );
+
+        file_chooser.connect_response(glib::clone!(@weak storage_entry => move |file_chooser, response| {
+            if response == ResponseType::Accept {
+                if let Some(path) = file_chooser.file().and_then(|file| file.path()) {
+                    storage_entry.set_text(&path.to_string_lossy());
+                }
+            }
+            file_chooser.close();
+        }));
+
+        file_chooser.show();
+    }));
+
+    // Looks in the prefix of the game path currently entered
+    detect_button.connect_clicked(glib::clone!(@weak path_display, @weak user_data_entry => move |_| {
+        let game_path = std::path::PathBuf::from(path_display.text().as_str());
+        match user_data::detect(&game_path) {
+            Some(path) => {
+                user_data_entry.set_text(&path.to_string_lossy());
+                user_data_entry.set_tooltip_text(None);
+            },
+            None => {
+                user_data_entry.set_text("");
+                user_data_entry.set_tooltip_text(Some("Not found. Start the game once or select the folder."));
+            },
+        }
+    }));
+
     dialog.add_button("Cancel", ResponseType::Cancel);
     dialog.add_button("Save", ResponseType::Accept);
 
@@ -166,11 +319,74 @@ pub fn show_settings_dialog(parent: &impl IsA<Window>) {
             let mut settings = Settings::load();
             let path_str = path_display.text();
             settings.game_path = Some(std::path::PathBuf::from(path_str.as_str()));
+            let user_data = user_data_entry.text();
+            settings.user_data_path = (!user_data.trim().is_empty())
+                .then(|| std::path::PathBuf::from(user_data.trim()));
+            let storage = storage_entry.text();
+            let storage = (!storage.trim().is_empty())
+                .then(|| std::path::PathBuf::from(storage.trim()));
+            if storage != settings.mod_storage_path {
+                // The stored mods go along to the new folder first
+                let target = storage.clone().unwrap_or_else(|| {
+                    mod_manager::default_staging_path(settings.game_path.as_deref().unwrap_or(std::path::Path::new("")))
+                });
+                let moved = ModManager::new(Settings::load())
+                    .and_then(|mod_manager| mod_manager.move_mod_storage(&target));
+                match moved {
+                    Ok(()) => {
+                        settings.mod_storage_path = storage;
+                        if let Some(parent) = dialog.transient_for() {
+                            request_refresh(&parent);
+                        }
+                    },
+                    Err(e) => show_error(dialog, &format!("Failed to move the stored mods, the storage folder is unchanged: {}", e)),
+                }
+            }
+            settings.deploy_method = DeployMethod::from_index(deploy_dropdown.selected() as usize);
+            settings.auto_backup_saves = backup_switch.is_active();
+            settings.launch_method = LaunchMethod::from_index(launch_dropdown.selected() as usize, &launch_entry.text());
             settings.nexus_api_key = Some(api_entry.text().to_string());
+            settings.preferred_cdn = match cdn_dropdown.selected() {
+                0 | gtk::INVALID_LIST_POSITION => None,
+                index => CDN_MIRRORS.get(index as usize).map(|m| m.to_string()),
+            };
             settings.save().unwrap();
         }
         dialog.close();
     });
 
     dialog.present();
-} 
\ No newline at end of file
+}
+
+/// The entry holds the Heroic app name, Lutris slug or Wine command.
+fn update_launch_entry(entry: &Entry, selected: u32) {
+    let placeholder = match selected {
+        1 => Some("Heroic app name"),
+        2 => Some("Lutris game slug, e.g. stalker-2"),
+        3 if !cfg!(windows) => Some("Wine command, e.g. wine or umu-run"),
+        _ => None,
+    };
+    entry.set_placeholder_text(placeholder);
+    entry.set_visible(placeholder.is_some());
+}
+
+/// Asks the main window to reload the mod list.
+fn request_refresh(parent: &Window) {
+    unsafe {
+        if let Some(sender) = parent.data::<async_channel::Sender<()>>("refresh_sender") {
+            let _ = sender.as_ref().try_send(());
+        }
+    }
+}
+
+fn show_error(parent: &Dialog, message: &str) {
+    let dialog = gtk::MessageDialog::new(
+        parent.transient_for().as_ref(),
+        gtk::DialogFlags::MODAL,
+        gtk::MessageType::Error,
+        gtk::ButtonsType::Close,
+        message,
+    );
+    dialog.connect_response(|dialog, _| dialog.close());
+    dialog.present();
+}
@@ -0,0 +1,97 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::fs_ops;
+
+/// How enabled paks are put into `~mods` from the staging store, where
+/// every installed mod stays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployMethod {
+    /// Takes no space, but needs the store on the game's drive; a copy is
+    /// made otherwise.
+    #[default]
+    Hardlink,
+    /// Works across drives. Needs Developer Mode or admin rights on Windows.
+    Symlink,
+    Copy,
+}
+
+impl DeployMethod {
+    pub const LABELS: [&'static str; 3] = ["Hard links", "Symbolic links", "Copies"];
+
+    pub fn index(&self) -> usize {
+        match self {
+            DeployMethod::Hardlink => 0,
+            DeployMethod::Symlink => 1,
+            DeployMethod::Copy => 2,
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        match index {
+            1 => DeployMethod::Symlink,
+            2 => DeployMethod::Copy,
+            _ => DeployMethod::Hardlink,
+        }
+    }
+}
+
+/// Creates `target` from the stored `source`, returning the method actually
+/// used.
+pub fn deploy_file(source: &Path, target: &Path, method: DeployMethod) -> io::Result<DeployMethod> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match method {
+        DeployMethod::Hardlink => match fs::hard_link(source, target) {
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => copy_file(source, target),
+            result => result.map(|()| DeployMethod::Hardlink),
+        },
+        DeployMethod::Symlink => symlink_file(source, target).map(|()| DeployMethod::Symlink),
+        DeployMethod::Copy => copy_file(source, target),
+    }
+}
+
+/// Whether `deployed` is `source` put in place by `deploy_file`: a link to
+/// it or an identical copy. Anything else has been changed by hand.
+pub fn is_deployment_of(deployed: &Path, source: &Path) -> io::Result<bool> {
+    if fs::symlink_metadata(deployed)?.file_type().is_symlink() {
+        return Ok(fs::read_link(deployed)? == source);
+    }
+    if same_file(deployed, source)? {
+        return Ok(true);
+    }
+    fs_ops::same_contents(deployed, source)
+}
+
+fn copy_file(source: &Path, target: &Path) -> io::Result<DeployMethod> {
+    fs::copy(source, target)?;
+    fs::File::open(target)?.sync_all()?;
+    Ok(DeployMethod::Copy)
+}
+
+#[cfg(unix)]
+fn symlink_file(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(windows)]
+fn symlink_file(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(source, target)
+}
+
+/// Hard links share the inode. Windows has no stable way to ask, so the
+/// contents are compared there instead.
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+    let (a, b) = (fs::metadata(a)?, fs::metadata(b)?);
+    Ok(a.dev() == b.dev() && a.ino() == b.ino())
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &Path, _b: &Path) -> io::Result<bool> {
+    Ok(false)
+}
@@ -0,0 +1,273 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Result};
+
+/// Name of the runtime's row in the mod list.
+pub const NAME: &str = "UE4SS";
+/// The proxy DLL the game loads, which in turn loads UE4SS.
+const PROXY_DLL: &str = "dwmapi.dll";
+const DISABLED_SUFFIX: &str = ".disabled";
+const MODS_TXT: &str = "mods.txt";
+const ENABLED_TXT: &str = "enabled.txt";
+/// Mods shipped with UE4SS itself, managed by UE4SS rather than by us.
+const BUILTIN_MODS: &[&str] = &[
+    "shared",
+    "ActorDumperMod",
+    "BPML_GenericFunctions",
+    "BPModLoaderMod",
+    "CheatManagerEnablerMod",
+    "ConsoleCommandsMod",
+    "ConsoleEnablerMod",
+    "jsbLuaProfilerMod",
+    "Keybinds",
+    "LineTraceMod",
+    "SplitScreenMod",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    /// A UE4SS release.
+    Runtime,
+    /// One or more mods for `ue4ss/Mods`.
+    Mods,
+    /// Anything else, usually paks.
+    Other,
+}
+
+/// `ue4ss_path` is `Binaries/Win64/ue4ss`, the folder holding `UE4SS.dll`.
+pub fn is_installed(ue4ss_path: &Path) -> bool {
+    ue4ss_path.join("UE4SS.dll").exists()
+}
+
+pub fn is_runtime_enabled(ue4ss_path: &Path) -> bool {
+    win64_path(ue4ss_path).join(PROXY_DLL).exists()
+}
+
+/// Turns UE4SS on or off by renaming the proxy DLL, keeping everything else
+/// in place.
+pub fn set_runtime_enabled(ue4ss_path: &Path, enabled: bool) -> Result<()> {
+    let win64 = win64_path(ue4ss_path);
+    let active = win64.join(PROXY_DLL);
+    let disabled = win64.join(format!("{}{}", PROXY_DLL, DISABLED_SUFFIX));
+
+    if enabled && !active.exists() {
+        if !disabled.exists() {
+            bail!("UE4SS is not installed");
+        }
+        fs::rename(&disabled, &active)?;
+    } else if !enabled && active.exists() {
+        fs::rename(&active, &disabled)?;
+    }
+    Ok(())
+}
+
+/// Everything a UE4SS install put in `Binaries/Win64`: the proxy DLL,
+/// enabled or not, and the `ue4ss` folder with the Lua mods.
+pub fn runtime_files(ue4ss_path: &Path) -> Vec<PathBuf> {
+    let win64 = win64_path(ue4ss_path);
+    [
+        win64.join(PROXY_DLL),
+        win64.join(format!("{}{}", PROXY_DLL, DISABLED_SUFFIX)),
+        ue4ss_path.to_path_buf(),
+    ]
+    .into_iter()
+    .filter(|path| path.exists())
+    .collect()
+}
+
+/// A mod is enabled by an `enabled.txt` in its folder or a `Name : 1` line
+/// in `mods.txt`.
+pub fn is_mod_enabled(mods_path: &Path, name: &str) -> bool {
+    if mods_path.join(name).join(ENABLED_TXT).exists() {
+        return true;
+    }
+    fs::read_to_string(mods_path.join(MODS_TXT))
+        .map(|content| content.lines().any(|line| parse_entry(line) == Some((name, true))))
+        .unwrap_or(false)
+}
+
+pub fn set_mod_enabled(mods_path: &Path, name: &str, enabled: bool) -> Result<()> {
+    let mod_dir = mods_path.join(name);
+    if !mod_dir.is_dir() {
+        bail!("Mod folder not found: {}", mod_dir.display());
+    }
+
+    let mods_txt = mods_path.join(MODS_TXT);
+    let content = match fs::read_to_string(&mods_txt) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    fs::write(&mods_txt, update_mods_txt(&content, name, enabled))?;
+
+    // enabled.txt wins over mods.txt, so it has to go when disabling
+    let marker = mod_dir.join(ENABLED_TXT);
+    if enabled {
+        fs::write(&marker, "")?;
+    } else if marker.exists() {
+        fs::remove_file(&marker)?;
+    }
+    Ok(())
+}
+
+/// Sets the `Name : 0/1` entry of a mod, adding it before `Keybinds` which
+/// conventionally stays last. Comments and line endings are kept.
+pub fn update_mods_txt(content: &str, name: &str, enabled: bool) -> String {
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let entry = format!("{} : {}", name, if enabled { 1 } else { 0 });
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+    match lines.iter_mut().find(|line| parse_entry(line).is_some_and(|(n, _)| n == name)) {
+        Some(line) => *line = entry,
+        None => {
+            let at = lines.iter()
+                .position(|line| parse_entry(line).is_some_and(|(n, _)| n == "Keybinds"))
+                .unwrap_or(lines.len());
+            lines.insert(at, entry);
+        }
+    }
+
+    let mut out = lines.join(newline);
+    out.push_str(newline);
+    out
+}
+
+fn parse_entry(line: &str) -> Option<(&str, bool)> {
+    let line = line.trim();
+    if line.starts_with(';') {
+        return None;
+    }
+    let (name, state) = line.split_once(':')?;
+    Some((name.trim(), state.trim() == "1"))
+}
+
+/// Folders in `ue4ss/Mods` that look like mods, without the built-in ones.
+pub fn list_mods(mods_path: &Path) -> Result<Vec<String>> {
+    if !mods_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut mods = Vec::new();
+    for entry in fs::read_dir(mods_path)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let is_mod = path.join("Scripts").is_dir() || path.join("dlls").is_dir();
+        if is_mod && !BUILTIN_MODS.iter().any(|builtin| builtin.eq_ignore_ascii_case(&name)) {
+            mods.push(name);
+        }
+    }
+    mods.sort();
+    Ok(mods)
+}
+
+pub fn classify_archive(archive_path: &Path) -> Result<ArchiveKind> {
+    let names = archive_names(archive_path)?;
+    if names.iter().any(|name| file_name_is(name, "UE4SS.dll")) {
+        Ok(ArchiveKind::Runtime)
+    } else if !mod_roots(&names).is_empty() {
+        Ok(ArchiveKind::Mods)
+    } else {
+        Ok(ArchiveKind::Other)
+    }
+}
+
+/// Extracts a UE4SS release into `Binaries/Win64`. An existing `mods.txt`
+/// is kept so updating UE4SS doesn't reset which mods are enabled.
+pub fn install_runtime(archive_path: &Path, ue4ss_path: &Path) -> Result<()> {
+    let names = archive_names(archive_path)?;
+    // The proxy DLL sits at the root of the release, possibly in a top folder
+    let Some(prefix) = names.iter()
+        .find(|name| file_name_is(name, PROXY_DLL))
+        .map(|name| name.parent().map(Path::to_path_buf).unwrap_or_default())
+    else {
+        bail!("Archive doesn't contain {}", PROXY_DLL);
+    };
+
+    let win64 = win64_path(ue4ss_path);
+    extract(archive_path, &prefix, &win64, |relative| {
+        relative.file_name().is_some_and(|n| n == MODS_TXT) && win64.join(relative).exists()
+    })
+}
+
+/// Extracts every mod in the archive into `ue4ss/Mods` and returns their
+/// folder names. Archives may hold the mod folder at any depth.
+pub fn install_mods(archive_path: &Path, mods_path: &Path) -> Result<Vec<String>> {
+    let names = archive_names(archive_path)?;
+    let roots = mod_roots(&names);
+    if roots.is_empty() {
+        bail!("Archive doesn't contain any UE4SS mods");
+    }
+
+    let mut installed = Vec::new();
+    for root in roots {
+        // A mod at the archive root is named after the archive
+        let name = root.file_name()
+            .or_else(|| archive_path.file_stem())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        extract(archive_path, &root, &mods_path.join(&name), |_| false)?;
+        installed.push(name);
+    }
+    Ok(installed)
+}
+
+fn win64_path(ue4ss_path: &Path) -> PathBuf {
+    ue4ss_path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+fn archive_names(archive_path: &Path) -> Result<Vec<PathBuf>> {
+    let archive = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+    Ok(archive.file_names().map(PathBuf::from).collect())
+}
+
+fn file_name_is(path: &Path, name: &str) -> bool {
+    path.file_name().is_some_and(|n| n.to_string_lossy().eq_ignore_ascii_case(name))
+}
+
+/// Folders containing `Scripts/main.lua` or `dlls/main.dll`.
+fn mod_roots(names: &[PathBuf]) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = names.iter()
+        .filter(|name| file_name_is(name, "main.lua") || file_name_is(name, "main.dll"))
+        .filter_map(|name| {
+            let folder = name.parent()?;
+            let is_entry_point = file_name_is(folder, "Scripts") || file_name_is(folder, "dlls");
+            is_entry_point.then(|| folder.parent().map(Path::to_path_buf).unwrap_or_default())
+        })
+        .collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+/// Extracts the entries under `prefix` into `target`, skipping the ones
+/// `skip` is true for.
+fn extract(archive_path: &Path, prefix: &Path, target: &Path, skip: impl Fn(&Path) -> bool) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(relative) = entry.enclosed_name()
+            .and_then(|name| name.strip_prefix(prefix).ok())
+            .map(Path::to_path_buf)
+        else {
+            continue;
+        };
+        if relative.as_os_str().is_empty() || skip(&relative) {
+            continue;
+        }
+
+        let out_path = target.join(&relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path)?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out_file = fs::File::create(&out_path)?;
+        io::copy(&mut entry, &mut out_file)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Steam app ID of S.T.A.L.K.E.R. 2, which names its Proton prefix.
+pub const STEAM_APP_ID: &str = "1643320";
+/// Folder the game creates under the Windows user's local app data.
+const GAME_FOLDER: &str = "Stalker2";
+
+/// Finds the game's user data folder (`AppData/Local/Stalker2`, holding
+/// saves, config and logs): in local app data on Windows, inside the Steam
+/// Proton, Lutris/Wine or Heroic prefix on Linux.
+pub fn detect(game_path: &Path) -> Option<PathBuf> {
+    candidates(game_path).into_iter().find(|path| path.is_dir())
+}
+
+/// `Saved/Config/Windows`, or `WinGDK` for the Game Pass version.
+pub fn config_dir(user_data: &Path) -> Option<PathBuf> {
+    let config = user_data.join("Saved").join("Config");
+    ["Windows", "WinGDK"].iter()
+        .map(|platform| config.join(platform))
+        .find(|path| path.exists())
+}
+
+pub fn saves_dir(user_data: &Path) -> PathBuf {
+    user_data.join("Saved").join("SaveGames")
+}
+
+pub fn logs_dir(user_data: &Path) -> PathBuf {
+    user_data.join("Saved").join("Logs")
+}
+
+/// One folder per crash report, holding a copy of the log.
+pub fn crashes_dir(user_data: &Path) -> PathBuf {
+    user_data.join("Saved").join("Crashes")
+}
+
+fn candidates(game_path: &Path) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+
+    if cfg!(windows) {
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            candidates.push(PathBuf::from(local).join(GAME_FOLDER));
+        }
+        return candidates;
+    }
+
+    // Steam keeps the prefix in the library the game is installed in:
+    // <library>/steamapps/common/<game> -> <library>/steamapps/compatdata
+    if let Some(steamapps) = game_path.ancestors().find(|p| p.file_name().is_some_and(|n| n == "steamapps")) {
+        candidates.push(proton_user_data(&steamapps.join("compatdata").join(STEAM_APP_ID).join("pfx")));
+    }
+
+    // Lutris and plain Wine installs put the game inside the prefix itself
+    if let Some(drive_c) = game_path.ancestors().find(|p| p.file_name().is_some_and(|n| n == "drive_c")) {
+        candidates.extend(prefix_user_data(drive_c.parent().unwrap_or(drive_c)));
+    }
+
+    if let Some(base) = directories::BaseDirs::new() {
+        let home = base.home_dir();
+
+        // The default Steam library, for games moved to another library
+        for steam in [
+            home.join(".steam").join("steam"),
+            home.join(".local").join("share").join("Steam"),
+            home.join(".var").join("app").join("com.valvesoftware.Steam").join("data").join("Steam"),
+        ] {
+            candidates.push(proton_user_data(
+                &steam.join("steamapps").join("compatdata").join(STEAM_APP_ID).join("pfx"),
+            ));
+        }
+
+        // Heroic records each game's prefix in its per-game config
+        for prefix in heroic_prefixes(&base.config_dir().join("heroic").join("GamesConfig")) {
+            candidates.extend(prefix_user_data(&prefix));
+        }
+    }
+
+    candidates
+}
+
+fn proton_user_data(pfx: &Path) -> PathBuf {
+    pfx.join("drive_c").join("users").join("steamuser").join("AppData").join("Local").join(GAME_FOLDER)
+}
+
+/// The game folder for every user of a Wine prefix. Proton prefixes keep
+/// `drive_c` in a `pfx` subfolder.
+pub fn prefix_user_data(prefix: &Path) -> Vec<PathBuf> {
+    let prefix = if prefix.join("pfx").is_dir() { prefix.join("pfx") } else { prefix.to_path_buf() };
+    let Ok(users) = fs::read_dir(prefix.join("drive_c").join("users")) else {
+        return Vec::new();
+    };
+    users.filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join("AppData").join("Local").join(GAME_FOLDER))
+        .collect()
+}
+
+/// `winePrefix` values from Heroic's per-game config files.
+pub fn heroic_prefixes(games_config: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(games_config) else {
+        return Vec::new();
+    };
+    entries.filter_map(|entry| entry.ok())
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        .filter_map(|config| {
+            // { "<app name>": { "winePrefix": "...", ... } }
+            config.as_object()?
+                .values()
+                .find_map(|game| game.get("winePrefix")?.as_str().map(PathBuf::from))
+        })
+        .collect()
+}
@@ -0,0 +1,32 @@
+use stalker2_mod_manager::bbcode::{decode_entities, image_urls, to_markdown};
+
+#[test]
+fn formatting_and_links_become_markdown() {
+    let markdown = to_markdown("[b]Bold[/b] and [i]italic[/i], see [url=\"https://example.com\"]here[/url]");
+    assert_eq!(markdown, "**Bold** and *italic*, see [here](https://example.com)");
+}
+
+#[test]
+fn presentation_tags_are_dropped_and_unknown_tags_kept() {
+    let markdown = to_markdown("[size=4][color=red]Warning[/color][/size] [mystery]x[/mystery]");
+    assert_eq!(markdown, "Warning [mystery]x[/mystery]");
+}
+
+#[test]
+fn lists_images_and_line_breaks() {
+    let markdown = to_markdown("Features:<br />[list][*]One[*]Two[/list][IMG] https://img/a.png [/IMG]");
+    assert!(markdown.starts_with("Features:  \n"));
+    assert!(markdown.contains("\n- One\n- Two\n"));
+    assert!(markdown.contains("\n\n![image](https://img/a.png)\n\n"));
+}
+
+#[test]
+fn image_urls_are_collected_in_order() {
+    let urls = image_urls("[img]https://img/a.png[/img] text [IMG] https://img/b.jpg [/IMG]");
+    assert_eq!(urls, vec!["https://img/a.png", "https://img/b.jpg"]);
+}
+
+#[test]
+fn entities_are_decoded_once() {
+    assert_eq!(decode_entities("Don&#39;t &quot;fix&quot; &lt;this&gt; &amp;amp;"), "Don't \"fix\" <this> &amp;");
+}
@@ -0,0 +1,91 @@
+use stalker2_mod_manager::bisect::{BisectSession, BisectState};
+use stalker2_mod_manager::dependencies::{Dependency, DependencyTarget};
+use stalker2_mod_manager::mod_info::ModInfo;
+
+fn enabled_mods(names: &[&str]) -> Vec<ModInfo> {
+    names.iter()
+        .map(|name| ModInfo { name: name.to_string(), enabled: true, ..ModInfo::default() })
+        .collect()
+}
+
+fn requires(mods: &mut [ModInfo], dependent: usize, required: usize) {
+    let dependency = Dependency {
+        target: DependencyTarget::for_mod(&mods[required]),
+        name: mods[required].name.clone(),
+    };
+    mods[dependent].dependencies.push(dependency);
+}
+
+#[test]
+fn dependencies_come_before_the_mods_needing_them_and_are_tested_with_them() {
+    let mut mods = enabled_mods(&["a", "b", "c", "d"]);
+    requires(&mut mods, 0, 3);
+    let session = BisectSession::start(&mods).unwrap();
+
+    let ids: Vec<_> = [3, 0, 1, 2].iter().map(|&index| mods[index].id).collect();
+    assert_eq!(session.suspects, ids);
+    assert_eq!(session.testing, ids[..2]);
+}
+
+#[test]
+fn a_cleared_dependency_is_still_enabled_for_the_mods_needing_it() {
+    let mut mods = enabled_mods(&["a", "b", "c", "d"]);
+    requires(&mut mods, 2, 0);
+    let mut session = BisectSession::start(&mods).unwrap();
+    assert_eq!(session.testing, [mods[0].id, mods[1].id]);
+
+    session.answer(false, &mods);
+    assert_eq!(session.suspects, [mods[2].id, mods[3].id]);
+    assert_eq!(session.testing, [mods[2].id, mods[0].id]);
+}
+
+#[test]
+fn the_culprit_is_found_once_it_causes_the_problem_on_its_own() {
+    let mods = enabled_mods(&["a", "b", "c", "d"]);
+    let mut session = BisectSession::start(&mods).unwrap();
+    assert_eq!(session.steps_left(), 3);
+
+    session.answer(false, &mods);
+    assert_eq!(session.testing, [mods[2].id]);
+    session.answer(false, &mods);
+    // d was never enabled without c, so it gets a test of its own
+    assert_eq!(session.state(), BisectState::Testing);
+    assert_eq!(session.testing, [mods[3].id]);
+    assert_eq!(session.steps_left(), 1);
+
+    session.answer(true, &mods);
+    assert_eq!(session.state(), BisectState::Found(mods[3].id));
+    assert_eq!(session.steps_left(), 0);
+    assert_eq!(session.steps.len(), 3);
+}
+
+#[test]
+fn a_last_suspect_without_the_problem_is_not_the_culprit() {
+    let mods = enabled_mods(&["a", "b"]);
+    let mut session = BisectSession::start(&mods).unwrap();
+
+    session.answer(true, &mods);
+    assert_eq!(session.state(), BisectState::Found(mods[0].id));
+
+    let mut session = BisectSession::start(&mods).unwrap();
+    session.answer(false, &mods);
+    session.answer(false, &mods);
+    assert_eq!(session.state(), BisectState::NotFound);
+    session.answer(true, &mods);
+    assert_eq!(session.steps.len(), 2);
+}
+
+#[test]
+fn sessions_survive_a_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bisect.json");
+    let mods = enabled_mods(&["a", "b", "c"]);
+    assert!(BisectSession::load(&path).unwrap().is_none());
+
+    let session = BisectSession::start(&mods).unwrap();
+    session.save(&path).unwrap();
+    let loaded = BisectSession::load(&path).unwrap().unwrap();
+    assert_eq!(loaded.suspects, session.suspects);
+    assert_eq!(loaded.testing, session.testing);
+    assert!(!path.with_extension("tmp").exists());
+}
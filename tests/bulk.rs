@@ -0,0 +1,62 @@
+use stalker2_mod_manager::mod_info::{ModInfo, ModKind};
+use stalker2_mod_manager::mod_manager::ModManager;
+use stalker2_mod_manager::settings::Settings;
+use std::fs;
+
+#[test]
+fn bulk_toggles_and_uninstalls_touch_only_the_selected_mods() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = dir.path().join("store");
+    let mod_manager = ModManager::new(Settings {
+        game_path: Some(dir.path().join("game")),
+        mod_storage_path: Some(store.clone()),
+        ..Settings::default()
+    }).unwrap();
+    for name in ["a.pak", "b.pak", "c.pak"] {
+        fs::write(store.join(name), name).unwrap();
+    }
+    fs::write(store.join("a.utoc"), "toc").unwrap();
+    let mut mods = mod_manager.reconcile(&[]).unwrap().apply(&[], false);
+    mods.sort_by(|a, b| a.name.cmp(&b.name));
+    let (a, b) = (mods[0].id, mods[1].id);
+
+    mod_manager.set_mods_enabled(&mut mods, &[a, b], true).unwrap();
+    assert!(mod_manager.mods_path().join("a.pak").exists());
+    assert!(mod_manager.mods_path().join("a.utoc").exists());
+    assert!(mod_manager.mods_path().join("b.pak").exists());
+    assert!(!mod_manager.mods_path().join("c.pak").exists());
+    assert_eq!(mods.iter().filter(|m| m.enabled).count(), 2);
+
+    mod_manager.uninstall_mods(&mut mods, &[a]).unwrap();
+    assert_eq!(mods.len(), 2);
+    assert!(!mods.iter().any(|m| m.id == a));
+    for file in ["a.pak", "a.utoc"] {
+        assert!(!mod_manager.mods_path().join(file).exists());
+        assert!(!store.join(file).exists());
+    }
+    assert!(mod_manager.mods_path().join("b.pak").exists());
+    assert_eq!(mod_manager.load_mod_list().unwrap().len(), 2);
+}
+
+#[test]
+fn exports_refuse_mods_other_than_paks() {
+    let dir = tempfile::tempdir().unwrap();
+    let mod_manager = ModManager::new(Settings {
+        game_path: Some(dir.path().join("game")),
+        mod_storage_path: Some(dir.path().join("store")),
+        ..Settings::default()
+    }).unwrap();
+    let folder = dir.path().join("Scripts");
+    fs::create_dir_all(&folder).unwrap();
+    let lua = ModInfo {
+        name: "Scripts".to_string(),
+        kind: ModKind::Lua,
+        installed_path: Some(folder),
+        ..ModInfo::default()
+    };
+
+    let zip = dir.path().join("export.zip");
+    let err = mod_manager.export_mod_selection(&zip, &[lua]).unwrap_err();
+    assert!(err.to_string().contains("Scripts"));
+    assert!(!zip.exists());
+}
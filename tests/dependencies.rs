@@ -0,0 +1,94 @@
+use stalker2_mod_manager::dependencies::{self, Dependency, DependencyFix, DependencyIssue, DependencyTarget};
+use stalker2_mod_manager::mod_info::{ModInfo, ModKind};
+use stalker2_mod_manager::mod_manager::ModManager;
+use stalker2_mod_manager::settings::Settings;
+use std::fs;
+
+fn requiring(name: &str, targets: &[DependencyTarget]) -> ModInfo {
+    ModInfo {
+        name: name.to_string(),
+        dependencies: targets.iter()
+            .map(|target| Dependency { target: target.clone(), name: format!("{:?}", target) })
+            .collect(),
+        ..ModInfo::default()
+    }
+}
+
+#[test]
+fn enabling_reports_disabled_and_missing_requirements() {
+    let library = ModInfo { name: "Library".to_string(), nexus_mod_id: Some(7), ..ModInfo::default() };
+    let local = ModInfo { name: "Local".to_string(), enabled: true, ..ModInfo::default() };
+    let mods = vec![
+        requiring("Mod", &[
+            DependencyTarget::Nexus { mod_id: 7 },
+            DependencyTarget::Nexus { mod_id: 8 },
+            DependencyTarget::for_mod(&local),
+        ]),
+        library.clone(),
+        local,
+    ];
+
+    let issues = dependencies::check_enable(&mods, 0);
+    assert_eq!(issues.len(), 2);
+    assert!(matches!(&issues[0], DependencyIssue::Disabled { id, .. } if *id == library.id));
+    assert_eq!(issues[0].fix(), Some(DependencyFix::Enable(library.id)));
+    assert!(matches!(&issues[1], DependencyIssue::Missing { .. }));
+    assert_eq!(issues[1].fix(), Some(DependencyFix::Install(8)));
+    assert_eq!(issues[0].to_string(), "Mod requires Library which is disabled");
+}
+
+#[test]
+fn lua_mods_need_ue4ss() {
+    let lua = ModInfo { name: "Script".to_string(), kind: ModKind::Lua, ..ModInfo::default() };
+    let issues = dependencies::check_enable(std::slice::from_ref(&lua), 0);
+    assert!(matches!(&issues[..], [DependencyIssue::Missing { dependency, .. }] if dependency.target == DependencyTarget::Ue4ss));
+    assert_eq!(issues[0].fix(), None);
+
+    let ue4ss = ModInfo { name: "UE4SS".to_string(), kind: ModKind::Ue4ss, enabled: true, ..ModInfo::default() };
+    assert!(dependencies::check_enable(&[lua, ue4ss], 0).is_empty());
+}
+
+#[test]
+fn disabling_reports_only_enabled_dependents() {
+    let base = ModInfo { name: "Base".to_string(), enabled: true, ..ModInfo::default() };
+    let mut enabled = requiring("Enabled", &[DependencyTarget::for_mod(&base)]);
+    enabled.enabled = true;
+    let disabled = requiring("Disabled", &[DependencyTarget::for_mod(&base)]);
+    let mods = vec![base, enabled.clone(), disabled];
+
+    let issues = dependencies::check_disable(&mods, 0);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].fix(), Some(DependencyFix::Disable(enabled.id)));
+    assert_eq!(issues[0].to_string(), "Enabled requires Base which is disabled");
+}
+
+#[test]
+fn batches_are_checked_as_a_whole_before_anything_moves() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = dir.path().join("store");
+    let mod_manager = ModManager::new(Settings {
+        game_path: Some(dir.path().join("game")),
+        mod_storage_path: Some(store.clone()),
+        ..Settings::default()
+    }).unwrap();
+    for name in ["base.pak", "patch.pak"] {
+        fs::write(store.join(name), name).unwrap();
+    }
+    let mut mods = mod_manager.reconcile(&[]).unwrap().apply(&[], false);
+    mods.sort_by(|a, b| a.name.cmp(&b.name));
+    let (base, patch) = (mods[0].id, mods[1].id);
+    let target = DependencyTarget::for_mod(&mods[0]);
+    mods[1].dependencies.push(Dependency { target, name: "base".to_string() });
+
+    let (_, issues) = mod_manager.batch_problems(&mods, &[patch], true);
+    assert_eq!(issues.len(), 1);
+    let (_, issues) = mod_manager.batch_problems(&mods, &[base, patch], true);
+    assert!(issues.is_empty());
+    assert!(!mod_manager.mods_path().join("patch.pak").exists());
+
+    mod_manager.set_mods_enabled(&mut mods, &[base, patch], true).unwrap();
+    let (_, issues) = mod_manager.batch_problems(&mods, &[base], false);
+    assert_eq!(issues.len(), 1);
+    let (_, issues) = mod_manager.batch_problems(&mods, &[base, patch], false);
+    assert!(issues.is_empty());
+}
@@ -0,0 +1,140 @@
+use stalker2_mod_manager::file_deploy::{self, DeployRoot, DeployRoots, FileMapping};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+fn roots(dir: &Path) -> DeployRoots {
+    DeployRoots {
+        game_root: dir.join("game"),
+        win64: dir.join("game").join("Stalker2").join("Binaries").join("Win64"),
+        user_config: Some(dir.join("config")),
+    }
+}
+
+fn write_zip(path: &Path, files: &[(&str, &str)]) {
+    let mut zip = zip::ZipWriter::new(fs::File::create(path).unwrap());
+    for (name, contents) in files {
+        zip.start_file(*name, zip::write::FileOptions::default()).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+/// Stores an archive of `files` as the mod `name`, mapped by `guess_mappings`.
+fn store(dir: &Path, name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let archive = dir.join(format!("{}.zip", name));
+    write_zip(&archive, files);
+    let store = dir.join("files").join(name);
+    fs::create_dir_all(&store).unwrap();
+    let names: Vec<PathBuf> = files.iter().map(|(name, _)| PathBuf::from(name)).collect();
+    file_deploy::store_archive(&archive, &store, file_deploy::guess_mappings(&names)).unwrap();
+    store
+}
+
+#[test]
+fn mappings_follow_the_folders_and_kinds_of_files() {
+    let names: Vec<PathBuf> = [
+        "Mod/Stalker2/Content/Movies/intro.bk2",
+        "Engine.ini",
+        "loader/dinput8.dll",
+        "loader/loader.ini",
+        "loader/readme.txt",
+        "notes.txt",
+        "settings.json",
+        "Mod.pak",
+        "install.md",
+    ].iter().map(PathBuf::from).collect();
+
+    let mappings = file_deploy::guess_mappings(&names);
+    let mapping = |source: &str, root: DeployRoot, target: &str| FileMapping {
+        source: PathBuf::from(source),
+        root,
+        target: PathBuf::from(target),
+    };
+    assert_eq!(mappings, [
+        mapping("Mod/Stalker2/Content/Movies/intro.bk2", DeployRoot::GameRoot, "Stalker2/Content/Movies/intro.bk2"),
+        mapping("Engine.ini", DeployRoot::UserConfig, "Engine.ini"),
+        mapping("loader/dinput8.dll", DeployRoot::Win64, "dinput8.dll"),
+        mapping("loader/loader.ini", DeployRoot::Win64, "loader.ini"),
+    ]);
+}
+
+#[test]
+fn win64_files_keep_their_folders() {
+    let names: Vec<PathBuf> = [
+        "plugins/foo.asi",
+        "plugins/foo.ini",
+        "Mods/Trainer/dlls/main.dll",
+        "Bundle/Binaries/Win64/dxgi.dll",
+        "Bundle/Binaries/Win64/ReShade/shaders.txt",
+    ].iter().map(PathBuf::from).collect();
+
+    let targets: Vec<PathBuf> = file_deploy::guess_mappings(&names).into_iter()
+        .inspect(|mapping| assert_eq!(mapping.root, DeployRoot::Win64))
+        .map(|mapping| mapping.target)
+        .collect();
+    assert_eq!(targets, [
+        PathBuf::from("plugins/foo.asi"),
+        PathBuf::from("plugins/foo.ini"),
+        PathBuf::from("Mods/Trainer/dlls/main.dll"),
+        PathBuf::from("dxgi.dll"),
+    ]);
+
+    // Below the shallowest folder with a binary, past any wrapper folder
+    let names: Vec<PathBuf> = ["Pack/dinput8.dll", "Pack/extras/helper.dll"].iter().map(PathBuf::from).collect();
+    let targets: Vec<PathBuf> = file_deploy::guess_mappings(&names).into_iter().map(|mapping| mapping.target).collect();
+    assert_eq!(targets, [PathBuf::from("dinput8.dll"), PathBuf::from("extras/helper.dll")]);
+}
+
+#[test]
+fn undeploying_puts_the_originals_back() {
+    let dir = tempfile::tempdir().unwrap();
+    let roots = roots(dir.path());
+    fs::create_dir_all(&roots.win64).unwrap();
+    fs::write(roots.win64.join("dinput8.dll"), "original").unwrap();
+    let store = store(dir.path(), "loader", &[("dinput8.dll", "loader"), ("loader.ini", "settings")]);
+
+    file_deploy::deploy(&store, &roots).unwrap();
+    assert!(file_deploy::is_deployed(&store));
+    assert_eq!(fs::read_to_string(roots.win64.join("dinput8.dll")).unwrap(), "loader");
+    assert_eq!(fs::read_to_string(roots.win64.join("loader.ini")).unwrap(), "settings");
+
+    file_deploy::undeploy(&store, &roots).unwrap();
+    assert!(!file_deploy::is_deployed(&store));
+    assert_eq!(fs::read_to_string(roots.win64.join("dinput8.dll")).unwrap(), "original");
+    assert!(!roots.win64.join("loader.ini").exists());
+}
+
+#[test]
+fn a_file_deployed_by_another_mod_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let roots = roots(dir.path());
+    let first = store(dir.path(), "first", &[("dinput8.dll", "first")]);
+    let second = store(dir.path(), "second", &[("DINPUT8.dll", "second")]);
+
+    file_deploy::deploy(&first, &roots).unwrap();
+    let err = file_deploy::deploy(&second, &roots).unwrap_err();
+    assert!(err.to_string().contains("first"));
+    assert!(!file_deploy::is_deployed(&second));
+    assert_eq!(fs::read_to_string(roots.win64.join("dinput8.dll")).unwrap(), "first");
+
+    file_deploy::undeploy(&first, &roots).unwrap();
+    file_deploy::deploy(&second, &roots).unwrap();
+    assert_eq!(fs::read_to_string(roots.win64.join("DINPUT8.dll")).unwrap(), "second");
+}
+
+#[test]
+fn manifests_cannot_point_outside_their_roots() {
+    let dir = tempfile::tempdir().unwrap();
+    let roots = roots(dir.path());
+    let store = store(dir.path(), "loader", &[("dinput8.dll", "loader")]);
+    let manifest = store.join("deployment.json");
+    let json = fs::read_to_string(&manifest).unwrap();
+    for target in ["../../escaped.dll", "/tmp/escaped.dll"] {
+        fs::write(&manifest, json.replace(r#""target": "dinput8.dll""#, &format!(r#""target": "{}""#, target))).unwrap();
+        let err = file_deploy::deploy(&store, &roots).unwrap_err();
+        assert!(err.to_string().contains("Unsafe path"), "{}", err);
+    }
+    assert!(!dir.path().join("escaped.dll").exists());
+    assert!(!file_deploy::is_deployed(&store));
+}
@@ -0,0 +1,56 @@
+use stalker2_mod_manager::fs_ops::{copy_then_remove, move_path};
+use std::fs;
+
+#[test]
+fn copy_then_remove_moves_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let from = dir.path().join("mod.pak");
+    let to = dir.path().join("unloaded").join("mod.pak");
+    fs::create_dir_all(to.parent().unwrap()).unwrap();
+    fs::write(&from, vec![7u8; 200_000]).unwrap();
+
+    copy_then_remove(&from, &to).unwrap();
+    assert!(!from.exists());
+    assert_eq!(fs::read(&to).unwrap(), vec![7u8; 200_000]);
+    assert!(!dir.path().join("unloaded").join("mod.pak.partial").exists());
+}
+
+#[test]
+fn copy_then_remove_moves_a_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    let from = dir.path().join("LuaMod");
+    fs::create_dir_all(from.join("Scripts")).unwrap();
+    fs::write(from.join("Scripts").join("main.lua"), "print('hi')").unwrap();
+    fs::write(from.join("enabled.txt"), "").unwrap();
+    let to = dir.path().join("Moved");
+
+    copy_then_remove(&from, &to).unwrap();
+    assert!(!from.exists());
+    assert_eq!(fs::read_to_string(to.join("Scripts").join("main.lua")).unwrap(), "print('hi')");
+    assert!(to.join("enabled.txt").exists());
+}
+
+#[test]
+fn failed_copy_leaves_the_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let from = dir.path().join("missing.pak");
+    let to = dir.path().join("mod.pak");
+    assert!(copy_then_remove(&from, &to).is_err());
+    assert!(!to.exists());
+
+    fs::write(&from, "pak").unwrap();
+    let blocked = dir.path().join("no_such_folder").join("mod.pak");
+    assert!(copy_then_remove(&from, &blocked).is_err());
+    assert!(from.exists());
+}
+
+#[test]
+fn move_path_renames_on_one_filesystem() {
+    let dir = tempfile::tempdir().unwrap();
+    let from = dir.path().join("a.pak");
+    let to = dir.path().join("b.pak");
+    fs::write(&from, "pak").unwrap();
+    move_path(&from, &to).unwrap();
+    assert!(!from.exists());
+    assert_eq!(fs::read_to_string(&to).unwrap(), "pak");
+}
@@ -0,0 +1,47 @@
+use stalker2_mod_manager::game_logs::{LogLine, Mention, ModIndex, Verbosity};
+
+#[test]
+fn unreal_lines_are_split_into_parts() {
+    let line = LogLine::parse("[2024.11.20-14.03.12:345][  0]LogPakFile: Warning: Pak file is corrupt: a: b");
+    assert_eq!(line.timestamp.as_deref(), Some("2024.11.20-14.03.12:345"));
+    assert_eq!(line.category.as_deref(), Some("LogPakFile"));
+    assert_eq!(line.verbosity, Verbosity::Warning);
+    assert_eq!(line.message, "Pak file is corrupt: a: b");
+    assert!(line.verbosity.is_problem());
+}
+
+#[test]
+fn lines_without_verbosity_are_plain_logs() {
+    let line = LogLine::parse("[2024.11.20-14.03.12:345][  0]LogInit: Build: ++Stalker2+Release");
+    assert_eq!(line.category.as_deref(), Some("LogInit"));
+    assert_eq!(line.verbosity, Verbosity::Log);
+    assert_eq!(line.message, "Build: ++Stalker2+Release");
+    assert!(!line.verbosity.is_problem());
+
+    let crash = LogLine::parse("Assertion failed: IsValid() [File:Stalker2/Source/Foo.cpp]");
+    assert_eq!(crash.category, None);
+    assert_eq!(crash.verbosity, Verbosity::Fatal);
+}
+
+#[test]
+fn assets_are_found_by_their_object_path() {
+    let mut index = ModIndex::default();
+    index.add(3, "BetterGuns_P", &["stalker2/content/gamelite/weapons/ak74.uasset".to_string()]);
+
+    let mentions = index.mentions("LogStreaming: Error: Failed to load /Game/GameLite/Weapons/AK74.AK74 (0x1)");
+    assert_eq!(mentions, vec![Mention { mod_index: 3, matched: "/game/gamelite/weapons/ak74".to_string() }]);
+
+    // A pak name and one of its assets in the same line count once
+    let mentions = index.mentions("Mounted bettergunS_p.pak, loading /Game/GameLite/Weapons/AK74.AK74");
+    assert_eq!(mentions, vec![Mention { mod_index: 3, matched: "betterguns_p".to_string() }]);
+}
+
+#[test]
+fn short_pak_names_are_ignored() {
+    let mut index = ModIndex::default();
+    index.add(0, "a_P", &[]);
+    index.add(1, "Zone", &[]);
+
+    let mentions = index.mentions("LogPakFile: Display: Mounted a_P.pak and zone.pak");
+    assert_eq!(mentions, vec![Mention { mod_index: 1, matched: "zone".to_string() }]);
+}
@@ -0,0 +1,84 @@
+use stalker2_mod_manager::ini::IniFile;
+use stalker2_mod_manager::ini_tweaks::{self, ConfigFile, IniTweak, TweakManifest};
+
+const ENGINE_INI: &str = "; Written by the game\r\n\
+[SystemSettings]\r\n\
+r.MotionBlurQuality=4\r\n\
+# keep this\r\n\
+\r\n\
+[Core.Log]\r\n\
+LogTemp=Log\r\n\
+\r\n\
+[SystemSettings]\r\n\
+r.FilmGrain = 1\r\n";
+
+#[test]
+fn unchanged_files_are_written_back_as_read() {
+    for content in [ENGINE_INI, "", "[A]\nkey=value", "\n\n[A]\n\n; only a comment\n"] {
+        assert_eq!(IniFile::parse(content).to_string(), content);
+    }
+}
+
+#[test]
+fn the_last_section_and_entry_win() {
+    let ini = IniFile::parse(ENGINE_INI);
+    assert_eq!(ini.get("systemsettings", "R.FILMGRAIN"), Some("1"));
+    assert_eq!(ini.get("SystemSettings", "r.MotionBlurQuality"), Some("4"));
+    assert_eq!(ini.get("Core.Log", "missing"), None);
+}
+
+#[test]
+fn set_and_remove_keep_comments_and_other_entries() {
+    let mut ini = IniFile::parse(ENGINE_INI);
+    ini.set("SystemSettings", "r.FilmGrain", "0");
+    ini.set("SystemSettings", "r.DepthOfFieldQuality", "0");
+    ini.set("/Script/Engine.Engine", "bSmoothFrameRate", "False");
+    assert_eq!(ini.remove("SystemSettings", "r.MotionBlurQuality"), Some("4".to_string()));
+
+    assert_eq!(ini.to_string(), "; Written by the game\r\n\
+[SystemSettings]\r\n\
+# keep this\r\n\
+\r\n\
+[Core.Log]\r\n\
+LogTemp=Log\r\n\
+\r\n\
+[SystemSettings]\r\n\
+r.FilmGrain=0\r\n\
+r.DepthOfFieldQuality=0\r\n\
+\r\n\
+[/Script/Engine.Engine]\r\n\
+bSmoothFrameRate=False\r\n");
+    assert_eq!(ini.remove("SystemSettings", "r.MotionBlurQuality"), None);
+}
+
+#[test]
+fn tweaks_writing_an_entry_another_tweak_owns_are_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("Config");
+    let tweaks = dir.path().join("tweaks");
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(config.join("Engine.ini"), "[SystemSettings]\nr.FilmGrain=1\n").unwrap();
+
+    let tweak = |value: &str| IniTweak {
+        file: ConfigFile::Engine,
+        section: "SystemSettings".to_string(),
+        key: "r.FilmGrain".to_string(),
+        value: value.to_string(),
+    };
+    let first = tweaks.join("first.json");
+    let second = tweaks.join("second.json");
+    ini_tweaks::save(&first, &TweakManifest { tweaks: vec![tweak("0")], applied: Vec::new() }).unwrap();
+    let mut other = tweak("2");
+    other.key = "R.FILMGRAIN".to_string();
+    ini_tweaks::save(&second, &TweakManifest { tweaks: vec![other], applied: Vec::new() }).unwrap();
+
+    ini_tweaks::apply(&first, &config).unwrap();
+    let err = ini_tweaks::apply(&second, &config).unwrap_err().to_string();
+    assert!(err.contains("first already sets"), "{}", err);
+    assert!(!ini_tweaks::is_applied(&second));
+
+    ini_tweaks::revert(&first, &config).unwrap();
+    ini_tweaks::apply(&second, &config).unwrap();
+    ini_tweaks::revert(&second, &config).unwrap();
+    assert_eq!(IniFile::load(&config.join("Engine.ini")).unwrap().get("SystemSettings", "r.FilmGrain"), Some("1"));
+}
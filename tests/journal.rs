@@ -0,0 +1,141 @@
+use stalker2_mod_manager::journal::{self, Journal, Recovery, Rename};
+use stalker2_mod_manager::mod_manager::ModManager;
+use stalker2_mod_manager::settings::Settings;
+use stalker2_mod_manager::mod_info::ModInfo;
+use std::fs;
+use std::path::Path;
+
+fn write_journal(path: &Path, journal: &Journal) {
+    fs::write(path, serde_json::to_string(journal).unwrap()).unwrap();
+}
+
+fn mod_list() -> Vec<ModInfo> {
+    vec![ModInfo { name: "Kept".to_string(), ..ModInfo::default() }]
+}
+
+#[test]
+fn a_failed_rename_undoes_the_earlier_ones_and_keeps_existing_targets() {
+    let dir = tempfile::tempdir().unwrap();
+    let (a, b) = (dir.path().join("a.pak"), dir.path().join("moved").join("a.pak"));
+    let stored = dir.path().join("stored.pak");
+    fs::write(&a, "a").unwrap();
+    fs::write(&stored, "edited by hand").unwrap();
+    let list = dir.path().join("mod_list.json");
+
+    let renames = vec![
+        Rename { from: a.clone(), to: b.clone() },
+        Rename { from: dir.path().join("missing.pak"), to: stored.clone() },
+    ];
+    assert!(journal::run(&dir.path().join("journal.json"), renames, &list, Some(&mod_list())).is_err());
+    assert_eq!(fs::read_to_string(&a).unwrap(), "a");
+    assert!(!b.exists());
+    assert_eq!(fs::read_to_string(&stored).unwrap(), "edited by hand");
+    assert!(!list.exists());
+    assert!(!dir.path().join("journal.json").exists());
+}
+
+#[test]
+fn recovery_rolls_back_an_unfinished_transaction() {
+    let dir = tempfile::tempdir().unwrap();
+    let journal_path = dir.path().join("journal.json");
+    let list = dir.path().join("mod_list.json");
+    // The first rename went through, the second was copying across drives
+    // and the third never started
+    let moved = dir.path().join("store").join("a.pak");
+    let copying = (dir.path().join("b.pak"), dir.path().join("store").join("b.pak"));
+    let waiting = (dir.path().join("c.pak"), dir.path().join("store").join("c.pak"));
+    fs::create_dir_all(moved.parent().unwrap()).unwrap();
+    fs::write(&moved, "a").unwrap();
+    fs::write(&copying.0, "b").unwrap();
+    fs::write(&copying.1, "b").unwrap();
+    fs::write(&waiting.0, "c").unwrap();
+    write_journal(&journal_path, &Journal {
+        renames: vec![
+            Rename { from: dir.path().join("a.pak"), to: moved.clone() },
+            Rename { from: copying.0.clone(), to: copying.1.clone() },
+            Rename { from: waiting.0.clone(), to: waiting.1.clone() },
+        ],
+        done: 1,
+        target_was_free: true,
+        mod_list: Some(mod_list()),
+    });
+
+    assert_eq!(journal::recover(&journal_path, &list).unwrap(), Some(Recovery::RolledBack));
+    for name in ["a.pak", "b.pak", "c.pak"] {
+        assert!(dir.path().join(name).exists(), "{}", name);
+        assert!(!dir.path().join("store").join(name).exists(), "{}", name);
+    }
+    assert!(!list.exists());
+    assert!(!journal_path.exists());
+    assert_eq!(journal::recover(&journal_path, &list).unwrap(), None);
+}
+
+#[test]
+fn recovery_keeps_a_target_that_was_there_before() {
+    let dir = tempfile::tempdir().unwrap();
+    let journal_path = dir.path().join("journal.json");
+    let (from, to) = (dir.path().join("deployed.pak"), dir.path().join("stored.pak"));
+    fs::write(&from, "new").unwrap();
+    fs::write(&to, "old").unwrap();
+    write_journal(&journal_path, &Journal {
+        renames: vec![Rename { from: from.clone(), to: to.clone() }],
+        done: 0,
+        target_was_free: false,
+        mod_list: None,
+    });
+
+    journal::recover(&journal_path, &dir.path().join("mod_list.json")).unwrap();
+    assert_eq!(fs::read_to_string(&from).unwrap(), "new");
+    assert_eq!(fs::read_to_string(&to).unwrap(), "old");
+}
+
+#[test]
+fn recovery_rolls_forward_once_every_rename_is_done() {
+    let dir = tempfile::tempdir().unwrap();
+    let journal_path = dir.path().join("journal.json");
+    let list = dir.path().join("mod_list.json");
+    let to = dir.path().join("store").join("a.pak");
+    fs::create_dir_all(to.parent().unwrap()).unwrap();
+    fs::write(&to, "a").unwrap();
+    write_journal(&journal_path, &Journal {
+        renames: vec![Rename { from: dir.path().join("a.pak"), to: to.clone() }],
+        done: 1,
+        target_was_free: false,
+        mod_list: Some(mod_list()),
+    });
+
+    assert_eq!(journal::recover(&journal_path, &list).unwrap(), Some(Recovery::RolledForward));
+    assert!(to.exists());
+    assert!(!dir.path().join("a.pak").exists());
+    let saved: Vec<ModInfo> = serde_json::from_str(&fs::read_to_string(&list).unwrap()).unwrap();
+    assert_eq!(saved.len(), 1);
+    assert_eq!(saved[0].name, "Kept");
+    assert!(!journal_path.exists());
+}
+
+#[test]
+fn imports_deploy_enabled_mods_in_the_same_transaction() {
+    let dir = tempfile::tempdir().unwrap();
+    let manager = |name: &str| ModManager::new(Settings {
+        game_path: Some(dir.path().join(name).join("game")),
+        mod_storage_path: Some(dir.path().join(name).join("store")),
+        ..Settings::default()
+    }).unwrap();
+    let source = manager("source");
+    fs::write(dir.path().join("source").join("store").join("a.pak"), "a").unwrap();
+    fs::write(dir.path().join("source").join("store").join("b.pak"), "b").unwrap();
+    let mut mods = source.reconcile(&[]).unwrap().apply(&[], false);
+    let a = mods.iter().find(|m| m.name.starts_with('a')).unwrap().id;
+    source.set_mods_enabled(&mut mods, &[a], true).unwrap();
+    let zip = dir.path().join("export.zip");
+    source.export_mods(&zip).unwrap();
+
+    let target = manager("target");
+    target.import_mods(&zip).unwrap();
+    assert_eq!(fs::read_to_string(target.mods_path().join("a.pak")).unwrap(), "a");
+    assert!(!target.mods_path().join("b.pak").exists());
+    assert!(target.staging_path().join("b.pak").exists());
+    let imported = target.load_mod_list().unwrap();
+    assert_eq!(imported.iter().filter(|m| m.enabled).count(), 1);
+    assert!(!target.mods_path().join("a.pak.partial").exists());
+}
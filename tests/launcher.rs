@@ -0,0 +1,73 @@
+use stalker2_mod_manager::launcher::{build_command, check_game_path, LaunchMethod, Platform};
+use std::path::Path;
+
+#[test]
+fn steam_opens_the_run_link() {
+    let command = build_command(&LaunchMethod::Steam, Path::new("/games/S2"), Platform::Unix).unwrap();
+    assert_eq!(command.program, "xdg-open");
+    assert_eq!(command.args, ["steam://rungameid/1643320"]);
+
+    let command = build_command(&LaunchMethod::Steam, Path::new("C:/S2"), Platform::Windows).unwrap();
+    assert_eq!(command.program, "cmd");
+    assert_eq!(command.args, ["/C", "start", "", "steam://rungameid/1643320"]);
+}
+
+#[test]
+fn heroic_and_lutris_need_an_id() {
+    let heroic = LaunchMethod::Heroic { app_name: "stalker2".to_string() };
+    let command = build_command(&heroic, Path::new("/games/S2"), Platform::Unix).unwrap();
+    assert_eq!(command.args, ["heroic://launch/stalker2"]);
+
+    let lutris = LaunchMethod::Lutris { slug: "stalker-2".to_string() };
+    let command = build_command(&lutris, Path::new("/games/S2"), Platform::Unix).unwrap();
+    assert_eq!(command.program, "lutris");
+    assert_eq!(command.args, ["lutris:rungame/stalker-2"]);
+
+    let empty = LaunchMethod::Lutris { slug: String::new() };
+    assert!(build_command(&empty, Path::new("/games/S2"), Platform::Unix).is_err());
+}
+
+#[test]
+fn executable_runs_through_wine_off_windows() {
+    let method = LaunchMethod::Executable { wine: "env WINEPREFIX=/pfx wine".to_string() };
+    let command = build_command(&method, Path::new("/games/S2"), Platform::Unix).unwrap();
+    assert_eq!(command.program, "env");
+    assert_eq!(command.args, ["WINEPREFIX=/pfx", "wine", "/games/S2/Stalker2.exe"]);
+    assert_eq!(command.current_dir.as_deref(), Some(Path::new("/games/S2")));
+
+    let command = build_command(&method, Path::new("/games/S2"), Platform::Windows).unwrap();
+    assert_eq!(command.program, "/games/S2/Stalker2.exe");
+    assert!(command.args.is_empty());
+
+    let no_wine = LaunchMethod::Executable { wine: " ".to_string() };
+    assert!(build_command(&no_wine, Path::new("/games/S2"), Platform::Unix).is_err());
+}
+
+#[test]
+fn method_round_trips_through_settings_fields() {
+    for method in [
+        LaunchMethod::Steam,
+        LaunchMethod::Heroic { app_name: "abc".to_string() },
+        LaunchMethod::Lutris { slug: "stalker-2".to_string() },
+        LaunchMethod::Executable { wine: "wine".to_string() },
+    ] {
+        assert_eq!(LaunchMethod::from_index(method.index(), method.value()), method);
+        let json = serde_json::to_string(&method).unwrap();
+        assert_eq!(serde_json::from_str::<LaunchMethod>(&json).unwrap(), method);
+    }
+}
+
+#[test]
+fn game_path_must_be_an_install() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(check_game_path(&LaunchMethod::Steam, None)[0].blocking);
+    assert!(check_game_path(&LaunchMethod::Steam, Some(dir.path()))[0].blocking);
+
+    std::fs::create_dir(dir.path().join("Stalker2")).unwrap();
+    assert!(check_game_path(&LaunchMethod::Steam, Some(dir.path())).is_empty());
+
+    let executable = LaunchMethod::Executable { wine: "wine".to_string() };
+    assert!(check_game_path(&executable, Some(dir.path()))[0].blocking);
+    std::fs::write(dir.path().join("Stalker2.exe"), b"").unwrap();
+    assert!(check_game_path(&executable, Some(dir.path())).is_empty());
+}
@@ -0,0 +1,182 @@
+use stalker2_mod_manager::dependencies::DependencyTarget;
+use stalker2_mod_manager::load_order::{self, strip_order_prefix};
+use stalker2_mod_manager::mod_info::ModInfo;
+use stalker2_mod_manager::mod_manager::ModManager;
+use stalker2_mod_manager::pak;
+use stalker2_mod_manager::rules::{ModRule, RuleKind};
+use stalker2_mod_manager::settings::Settings;
+use stalker2_mod_manager::staging::DeployMethod;
+use std::fs;
+use std::path::PathBuf;
+
+fn pak_mod(file: &str) -> ModInfo {
+    ModInfo {
+        name: file.trim_end_matches(".pak").to_string(),
+        installed_path: Some(PathBuf::from("mods").join(file)),
+        enabled: true,
+        ..ModInfo::default()
+    }
+}
+
+fn load_after(mod_info: &mut ModInfo, other: &ModInfo) {
+    mod_info.rules.push(ModRule {
+        kind: RuleKind::LoadAfter,
+        target: DependencyTarget::for_mod(other),
+        name: other.name.clone(),
+    });
+}
+
+fn assets(paths: &[&str]) -> Vec<String> {
+    paths.iter().map(|path| path.to_string()).collect()
+}
+
+#[test]
+fn rules_come_first_then_asset_overlaps_then_the_current_order() {
+    let mut mods = vec![pak_mod("a.pak"), pak_mod("b.pak"), pak_mod("c.pak"), pak_mod("d.pak")];
+    // a loads after d; b changes a subset of what c changes, so b loads later
+    let d = mods[3].clone();
+    load_after(&mut mods[0], &d);
+    let assets = vec![
+        Vec::new(),
+        assets(&["x.uasset"]),
+        assets(&["x.uasset", "y.uasset"]),
+        Vec::new(),
+    ];
+
+    let plan = load_order::plan(&mods, &assets).unwrap();
+    let files: Vec<&str> = plan.placements.iter().map(|p| p.new_file.as_str()).collect();
+    assert_eq!(files, ["001_c.pak", "002_b.pak", "003_d.pak", "004_a.pak"]);
+    assert!(plan.placements[1].reasons[0].contains("both change 1 assets"));
+    assert!(plan.placements[3].reasons[0].contains("load-after rule"));
+    assert!(plan.has_changes());
+}
+
+#[test]
+fn sorted_mods_keep_their_names_when_sorted_again() {
+    let mods = vec![pak_mod("001_b.pak"), pak_mod("002_a.pak")];
+    let plan = load_order::plan(&mods, &[]).unwrap();
+    assert!(!plan.has_changes());
+}
+
+#[test]
+fn cyclic_rules_are_reported_with_the_cycle() {
+    let mut mods = vec![pak_mod("a.pak"), pak_mod("b.pak"), pak_mod("c.pak")];
+    let (a, b, c) = (mods[0].clone(), mods[1].clone(), mods[2].clone());
+    load_after(&mut mods[0], &c);
+    load_after(&mut mods[1], &a);
+    load_after(&mut mods[2], &b);
+
+    let err = load_order::plan(&mods, &[]).unwrap_err().to_string();
+    assert!(err.contains("cycle"));
+    assert!(err.contains("a → b → c → a"), "{}", err);
+}
+
+#[test]
+fn only_a_three_digit_prefix_is_stripped() {
+    assert_eq!(strip_order_prefix("012_mod.pak"), "mod.pak");
+    assert_eq!(strip_order_prefix("12_mod.pak"), "12_mod.pak");
+    assert_eq!(strip_order_prefix("0123_mod.pak"), "0123_mod.pak");
+    assert_eq!(strip_order_prefix("012mod.pak"), "012mod.pak");
+    assert_eq!(strip_order_prefix("012_"), "012_");
+}
+
+fn write_string(buf: &mut Vec<u8>, text: &str) {
+    buf.extend(((text.len() + 1) as i32).to_le_bytes());
+    buf.extend(text.as_bytes());
+    buf.push(0);
+}
+
+/// A pak with no file data: a v11 index with a full directory index,
+/// followed by the footer.
+fn write_pak(path: &std::path::Path, mount_point: &str, files: &[(&str, &[&str])]) {
+    let mut directory_index = Vec::new();
+    directory_index.extend((files.len() as u32).to_le_bytes());
+    for (directory, names) in files {
+        write_string(&mut directory_index, directory);
+        directory_index.extend((names.len() as u32).to_le_bytes());
+        for name in *names {
+            write_string(&mut directory_index, name);
+            directory_index.extend(0i32.to_le_bytes());
+        }
+    }
+
+    let mut index = Vec::new();
+    write_string(&mut index, mount_point);
+    index.extend(0i32.to_le_bytes());
+    index.extend(0u64.to_le_bytes());
+    // No path hash index, then a full directory index right after
+    index.extend(0i32.to_le_bytes());
+    index.extend(1i32.to_le_bytes());
+    let directory_index_offset = (index.len() + 8 + 8 + 20) as u64;
+    index.extend(directory_index_offset.to_le_bytes());
+    index.extend((directory_index.len() as u64).to_le_bytes());
+    index.extend([0; 20]);
+    index.extend(directory_index);
+
+    let mut pak = index.clone();
+    pak.extend([0; 16]);
+    pak.push(0);
+    pak.extend(0x5A6F12E1u32.to_le_bytes());
+    pak.extend(11i32.to_le_bytes());
+    pak.extend(0u64.to_le_bytes());
+    pak.extend((index.len() as u64).to_le_bytes());
+    pak.extend([0; 20 + 5 * 32]);
+    std::fs::write(path, pak).unwrap();
+}
+
+#[test]
+fn pak_assets_are_read_from_the_directory_index() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mod.pak");
+    write_pak(&path, "../../../", &[
+        ("/Stalker2/Content/Items/", &["Knife.uasset", "Knife.uexp"]),
+        ("/Stalker2/Config/", &["Game.cfg"]),
+    ]);
+
+    assert_eq!(pak::list_assets(&path).unwrap(), [
+        "stalker2/content/items/knife.uasset",
+        "stalker2/content/items/knife.uexp",
+        "stalker2/config/game.cfg",
+    ]);
+
+    std::fs::write(&path, [0; 300]).unwrap();
+    assert!(pak::list_assets(&path).is_err());
+}
+
+#[test]
+fn sorting_linked_paks_keeps_their_data() {
+    let dir = tempfile::tempdir().unwrap();
+    let mod_manager = ModManager::new(Settings {
+        game_path: Some(dir.path().join("game")),
+        mod_storage_path: Some(dir.path().join("store")),
+        deploy_method: DeployMethod::Symlink,
+        ..Settings::default()
+    }).unwrap();
+    let store = dir.path().join("store");
+    fs::write(store.join("a.pak"), "a-data").unwrap();
+    fs::write(store.join("a.utoc"), "a-toc").unwrap();
+    fs::write(store.join("b.pak"), "b-data").unwrap();
+
+    let mut mods: Vec<ModInfo> = ["a.pak", "b.pak"].iter()
+        .map(|file| ModInfo { installed_path: Some(store.join(file)), enabled: false, ..pak_mod(file) })
+        .collect();
+    let b = mods[1].clone();
+    load_after(&mut mods[0], &b);
+    let ids: Vec<_> = mods.iter().map(|mod_info| mod_info.id).collect();
+    mod_manager.set_mods_enabled(&mut mods, &ids, true).unwrap();
+
+    let plan = mod_manager.plan_load_order(&mods).unwrap();
+    mod_manager.apply_load_order(&plan, &mut mods).unwrap();
+    let links = mod_manager.mods_path();
+    assert_eq!(fs::read_to_string(links.join("002_a.pak")).unwrap(), "a-data");
+    assert_eq!(fs::read_to_string(links.join("002_a.utoc")).unwrap(), "a-toc");
+    assert_eq!(fs::read_link(links.join("001_b.pak")).unwrap(), store.join("001_b.pak"));
+    assert_eq!(mods[0].installed_path.as_deref(), Some(store.join("002_a.pak").as_path()));
+
+    mod_manager.set_mods_enabled(&mut mods, &ids, false).unwrap();
+    assert_eq!(fs::read_dir(links).unwrap().count(), 0);
+    assert_eq!(fs::read_to_string(store.join("002_a.pak")).unwrap(), "a-data");
+    assert_eq!(fs::read_to_string(store.join("002_a.utoc")).unwrap(), "a-toc");
+    assert_eq!(fs::read_to_string(store.join("001_b.pak")).unwrap(), "b-data");
+    assert!(mod_manager.reconcile(&mods).unwrap().changes.is_empty());
+}
@@ -0,0 +1,102 @@
+use stalker2_mod_manager::dependencies::DependencyTarget;
+use stalker2_mod_manager::mod_info::{ModInfo, ModKind};
+use stalker2_mod_manager::rules::{self, ModRule, RuleKind};
+use stalker2_mod_manager::mod_manager::ModManager;
+use stalker2_mod_manager::profiles::Profile;
+use stalker2_mod_manager::settings::Settings;
+use std::fs;
+
+#[test]
+fn lists_saved_without_ids_keep_the_ids_they_get() {
+    let dir = tempfile::tempdir().unwrap();
+    let mod_manager = ModManager::new(Settings {
+        game_path: Some(dir.path().join("game")),
+        mod_storage_path: Some(dir.path().join("store")),
+        ..Settings::default()
+    }).unwrap();
+    let list = dir.path().join("game").join("Stalker2").join("ModManager").join("mod_list.json");
+    let entry = |path: &str| format!(
+        r#"{{"name":"Same","version":"1","author":"","description":"","nexus_mod_id":null,"installed_path":"{}","enabled":true}}"#,
+        path
+    );
+    fs::create_dir_all(list.parent().unwrap()).unwrap();
+    fs::write(&list, format!("[{},{}]", entry("a/mod.pak"), entry("b/mod.pak"))).unwrap();
+
+    let mods = mod_manager.load_mod_list().unwrap();
+    assert_ne!(mods[0].id, mods[1].id);
+    let reloaded = mod_manager.load_mod_list().unwrap();
+    assert_eq!(reloaded[0].id, mods[0].id);
+    assert_eq!(reloaded[1].id, mods[1].id);
+
+    // Profiles from before still find their mods by file name
+    let legacy: Profile = serde_json::from_str(r#"{"name":"Old","enabled":["mod.pak"]}"#).unwrap();
+    assert!(legacy.wants_enabled(&mods[0]));
+    let profile = Profile::capture("New", &mods[..1]);
+    assert!(profile.wants_enabled(&mods[0]));
+    assert!(!profile.wants_enabled(&mods[1]));
+}
+
+#[test]
+fn a_second_pak_with_the_same_file_name_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let mod_manager = ModManager::new(Settings {
+        game_path: Some(dir.path().join("game")),
+        mod_storage_path: Some(dir.path().join("store")),
+        ..Settings::default()
+    }).unwrap();
+    for (folder, contents) in [("first", "one"), ("second", "two")] {
+        fs::create_dir_all(dir.path().join(folder)).unwrap();
+        fs::write(dir.path().join(folder).join("Same.pak"), contents).unwrap();
+    }
+
+    let installed = mod_manager.install_local_mod(&dir.path().join("first").join("Same.pak")).unwrap();
+    assert!(mod_manager.install_local_mod(&dir.path().join("second").join("Same.pak")).is_err());
+    assert_eq!(fs::read_to_string(&installed).unwrap(), "one");
+    assert_eq!(fs::read_to_string(mod_manager.mods_path().join("Same.pak")).unwrap(), "one");
+}
+
+#[test]
+fn rules_and_local_dependencies_point_at_one_of_two_same_named_mods() {
+    let installed = |path: &str| Some(std::path::PathBuf::from(path));
+    let mut mods = vec![
+        ModInfo { name: "Same".to_string(), installed_path: installed("a.pak"), enabled: true, ..ModInfo::default() },
+        ModInfo { name: "Same".to_string(), installed_path: installed("b.pak"), enabled: true, ..ModInfo::default() },
+        ModInfo { name: "Other".to_string(), installed_path: installed("c.pak"), enabled: true, ..ModInfo::default() },
+    ];
+    let target = DependencyTarget::for_mod(&mods[1]);
+    assert!(!target.matches(&mods[0]));
+    assert!(target.matches(&mods[1]));
+    mods[2].rules.push(ModRule { kind: RuleKind::IncompatibleWith, target, name: "Same".to_string() });
+
+    let violations = rules::validate(&mods);
+    assert_eq!(violations.len(), 1);
+    assert!(!violations[0].involves(mods[0].id));
+    assert!(violations[0].involves(mods[1].id));
+    assert!(violations[0].involves(mods[2].id));
+}
+
+#[test]
+fn local_targets_saved_by_name_are_migrated_to_ids() {
+    let dir = tempfile::tempdir().unwrap();
+    let mod_manager = ModManager::new(Settings {
+        game_path: Some(dir.path().join("game")),
+        mod_storage_path: Some(dir.path().join("store")),
+        ..Settings::default()
+    }).unwrap();
+    let list = dir.path().join("game").join("Stalker2").join("ModManager").join("mod_list.json");
+    let entry = |name: &str, extra: &str| format!(
+        r#"{{"name":"{}","version":"1","author":"","description":"","nexus_mod_id":null,"installed_path":null,"enabled":false{}}}"#,
+        name, extra
+    );
+    let rules = r#","rules":[{"kind":"load_after","target":{"kind":"local","name":"Base"},"name":"Base"},{"kind":"load_after","target":{"kind":"local","name":"Gone"},"name":"Gone"}]"#;
+    let dependencies = r#","dependencies":[{"target":{"kind":"local","name":"UE4SS"},"name":"UE4SS"}]"#;
+    fs::create_dir_all(list.parent().unwrap()).unwrap();
+    fs::write(&list, format!("[{},{}]", entry("Base", ""), entry("Patch", &format!("{}{}", rules, dependencies)))).unwrap();
+
+    let mods = mod_manager.load_mod_list().unwrap();
+    assert_eq!(mods[1].rules.len(), 1);
+    assert!(mods[1].rules[0].target.matches(&mods[0]));
+    assert_eq!(mods[1].dependencies[0].target, DependencyTarget::Ue4ss);
+    assert!(mods[1].dependencies[0].matches(&ModInfo { kind: ModKind::Ue4ss, ..ModInfo::default() }));
+    assert_eq!(mod_manager.load_mod_list().unwrap()[1].rules, mods[1].rules);
+}
@@ -0,0 +1,11 @@
+use stalker2_mod_manager::mod_info::ModInfo;
+
+#[test]
+fn mods_are_outdated_only_behind_a_known_nexus_version() {
+    let mut mod_info = ModInfo { version: "1.2".to_string(), ..ModInfo::default() };
+    assert!(!mod_info.is_outdated());
+    mod_info.latest_version = Some("1.2".to_string());
+    assert!(!mod_info.is_outdated());
+    mod_info.latest_version = Some("1.10".to_string());
+    assert!(mod_info.is_outdated());
+}
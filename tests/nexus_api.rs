@@ -0,0 +1,508 @@
+use stalker2_mod_manager::http;
+use stalker2_mod_manager::nexus_api::{compare_versions, select_download_link, DownloadLink, EndorsementStatus, ModFeed, NexusClient};
+use std::time::Duration;
+
+const GAME: &str = "stalker2heartofchornobyl";
+
+fn client(server: &mockito::Server) -> NexusClient {
+    NexusClient::builder("test-key")
+        .base_url(&server.url())
+        .game_domain(GAME)
+        .user_agent("stalker2-mod-manager-tests")
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn get_mod_info_parses_response() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server.mock("GET", "/games/stalker2heartofchornobyl/mods/33")
+        .match_header("apikey", "test-key")
+        .match_header("user-agent", "stalker2-mod-manager-tests")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{
+            "name": "Better Flashlight",
+            "version": "1.2",
+            "author": "someone",
+            "description": "[b]Brighter[/b]",
+            "mod_id": 33,
+            "category_id": 4,
+            "status": "published",
+            "available": true,
+            "user": { "name": "someone", "member_id": 42 }
+        }"#)
+        .create_async()
+        .await;
+
+    let info = client(&server).get_mod_info(33).await.unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(info.id, 33);
+    assert_eq!(info.name, "Better Flashlight");
+    assert_eq!(info.version.as_deref(), Some("1.2"));
+    assert_eq!(info.user.name, "someone");
+}
+
+#[tokio::test]
+async fn get_mod_files_uses_first_id() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/33/files")
+        .with_status(200)
+        .with_body(r#"{
+            "files": [
+                {
+                    "id": [130, 1643320],
+                    "name": "Main File",
+                    "version": "1.2",
+                    "category_id": 1,
+                    "file_name": "BetterFlashlight-33-1-2.zip",
+                    "mod_version": "1.2"
+                }
+            ]
+        }"#)
+        .create_async()
+        .await;
+
+    let files = client(&server).get_mod_files(33).await.unwrap();
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].id(), 130);
+    assert_eq!(files[0].file_name, "BetterFlashlight-33-1-2.zip");
+}
+
+#[tokio::test]
+async fn download_mod_follows_download_link() {
+    let mut server = mockito::Server::new_async().await;
+    let cdn_uri = format!("{}/cdn/BetterFlashlight.pak", server.url());
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/33/files/130/download_link.json")
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("key".into(), "nxm-key".into()),
+            mockito::Matcher::UrlEncoded("expires".into(), "1700000000".into()),
+        ]))
+        .with_status(200)
+        .with_body(format!(
+            r#"[{{ "name": "Nexus CDN", "short_name": "Nexus CDN", "URI": "{}" }}]"#,
+            cdn_uri
+        ))
+        .create_async()
+        .await;
+    server.mock("GET", "/cdn/BetterFlashlight.pak")
+        .with_status(200)
+        .with_body("pak-bytes")
+        .create_async()
+        .await;
+
+    let data = client(&server)
+        .download_mod(33, 130, Some(("nxm-key".to_string(), 1700000000)))
+        .await
+        .unwrap();
+
+    assert_eq!(data, b"pak-bytes");
+}
+
+#[tokio::test]
+async fn download_link_forbidden_requires_premium() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/33/files/130/download_link.json")
+        .with_status(403)
+        .with_body(r#"{ "message": "You don't have permission to get download links from the API without visting nexusmods.com - this is for premium users only." }"#)
+        .create_async()
+        .await;
+
+    let err = client(&server).download_mod(33, 130, None).await.unwrap_err();
+
+    assert!(err.to_string().contains("Premium Nexus account required"));
+}
+
+#[tokio::test]
+async fn rate_limited_requests_report_retry_after() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/33")
+        .with_status(429)
+        .with_header("retry-after", "60")
+        .with_body(r#"{ "msg": "Your daily and hourly limits have been reached." }"#)
+        .create_async()
+        .await;
+
+    let err = client(&server).get_mod_info(33).await.unwrap_err();
+
+    assert!(err.to_string().contains("rate limit exceeded"));
+    assert!(err.to_string().contains("Retry after 60 seconds"));
+}
+
+#[tokio::test]
+async fn game_domain_is_configurable() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server.mock("GET", "/games/staging-domain/mods/1/files")
+        .with_status(200)
+        .with_body(r#"{ "files": [] }"#)
+        .create_async()
+        .await;
+
+    let client = NexusClient::builder("test-key")
+        .base_url(&format!("{}/", server.url()))
+        .game_domain("staging-domain")
+        .build()
+        .unwrap();
+    let files = client.get_mod_files(1).await.unwrap();
+
+    mock.assert_async().await;
+    assert!(files.is_empty());
+}
+
+#[tokio::test]
+async fn default_client_sends_application_headers() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server.mock("GET", "/games/stalker2heartofchornobyl/mods/1/files")
+        .match_header("user-agent", mockito::Matcher::Regex(format!(
+            r"^{}/{} \({}[^;]*; {}\) Rust/\d+\.\d+",
+            http::APP_NAME, http::APP_VERSION, std::env::consts::OS, std::env::consts::ARCH
+        )))
+        .match_header("application-name", http::APP_NAME)
+        .match_header("application-version", http::APP_VERSION)
+        .with_status(200)
+        .with_body(r#"{ "files": [] }"#)
+        .create_async()
+        .await;
+
+    let client = NexusClient::builder("test-key")
+        .base_url(&server.url())
+        .build()
+        .unwrap();
+    client.get_mod_files(1).await.unwrap();
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn download_uses_preferred_mirror_without_api_key() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/33/files/130/download_link.json")
+        .with_status(200)
+        .with_body(format!(
+            r#"[
+                {{ "name": "Nexus Global Content Delivery Network", "short_name": "Nexus CDN", "URI": "{0}/cdn/global.pak" }},
+                {{ "name": "Amsterdam", "short_name": "amsterdam", "URI": "{0}/cdn/amsterdam.pak" }}
+            ]"#,
+            server.url()
+        ))
+        .create_async()
+        .await;
+    // The mirror redirects to the actual storage node
+    server.mock("GET", "/cdn/amsterdam.pak")
+        .match_header("apikey", mockito::Matcher::Missing)
+        .match_header("application-name", http::APP_NAME)
+        .with_status(302)
+        .with_header("location", "/storage/amsterdam.pak")
+        .create_async()
+        .await;
+    server.mock("GET", "/storage/amsterdam.pak")
+        .with_status(200)
+        .with_body("amsterdam-bytes")
+        .create_async()
+        .await;
+
+    let client = NexusClient::builder("test-key")
+        .base_url(&server.url())
+        .preferred_cdn(Some("Amsterdam"))
+        .build()
+        .unwrap();
+    let data = client.download_mod(33, 130, None).await.unwrap();
+
+    assert_eq!(data, b"amsterdam-bytes");
+}
+
+#[test]
+fn select_download_link_falls_back_to_first() {
+    let links: Vec<DownloadLink> = serde_json::from_str(r#"[
+        { "name": "Nexus Global Content Delivery Network", "short_name": "Nexus CDN", "URI": "https://cdn/global" },
+        { "name": "Prague", "short_name": "prague", "URI": "https://cdn/prague" }
+    ]"#).unwrap();
+
+    assert_eq!(select_download_link(&links, Some("PRAGUE")).unwrap().uri, "https://cdn/prague");
+    assert_eq!(select_download_link(&links, Some("Singapore")).unwrap().uri, "https://cdn/global");
+    assert_eq!(select_download_link(&links, None).unwrap().uri, "https://cdn/global");
+    assert!(select_download_link(&[], Some("Prague")).is_none());
+}
+
+#[test]
+fn versions_compare_numerically_by_component() {
+    use std::cmp::Ordering;
+
+    assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+    assert_eq!(compare_versions("v1.2", "1.2"), Ordering::Equal);
+    assert_eq!(compare_versions("1.2", "1.2.1"), Ordering::Less);
+    assert_eq!(compare_versions("1.9b", "1.9a"), Ordering::Greater);
+    assert_eq!(compare_versions("1.10", "1.9b"), Ordering::Greater);
+    assert_eq!(compare_versions("1.9b", "1.9"), Ordering::Greater);
+    assert_eq!(compare_versions("2.0-beta2", "2.0-beta10"), Ordering::Less);
+    assert_eq!(compare_versions("2.0-Beta", "2.0-beta"), Ordering::Equal);
+}
+
+#[tokio::test]
+async fn changelogs_are_sorted_newest_first() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/33/changelogs.json")
+        .with_status(200)
+        .with_body(r#"{ "1.9": ["Old fix"], "1.10": ["New feature"], "1.2": ["First"] }"#)
+        .create_async()
+        .await;
+
+    let changelogs = client(&server).get_changelogs(33).await.unwrap();
+    let versions: Vec<&str> = changelogs.iter().map(|(v, _)| v.as_str()).collect();
+
+    assert_eq!(versions, vec!["1.10", "1.9", "1.2"]);
+    assert_eq!(changelogs[0].1, vec!["New feature".to_string()]);
+}
+
+#[tokio::test]
+async fn mod_requirements_come_from_graphql() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("POST", "/graphql")
+        .match_body(mockito::Matcher::PartialJsonString(
+            r#"{ "variables": { "ids": [{ "gameDomain": "stalker2heartofchornobyl", "modId": "33" }] } }"#.to_string()
+        ))
+        .with_status(200)
+        .with_body(r#"{
+            "data": { "legacyModsByDomain": { "nodes": [{ "modRequirements": { "nexusRequirements": { "nodes": [
+                { "modId": "7", "modName": "UE4SS", "url": "https://www.nexusmods.com/stalker2heartofchornobyl/mods/7", "notes": "", "externalRequirement": false },
+                { "modId": null, "modName": "Some Tool", "url": "https://example.com", "notes": "Optional", "externalRequirement": true }
+            ] } } }] } }
+        }"#)
+        .create_async()
+        .await;
+
+    let client = NexusClient::builder("test-key")
+        .base_url(&server.url())
+        .graphql_url(&format!("{}/graphql", server.url()))
+        .build()
+        .unwrap();
+    let requirements = client.get_mod_requirements(33).await.unwrap();
+
+    assert_eq!(requirements.len(), 2);
+    assert_eq!(requirements[0].nexus_mod_id(), Some(7));
+    assert_eq!(requirements[1].nexus_mod_id(), None);
+}
+
+#[tokio::test]
+async fn latest_versions_skip_unavailable_mods_and_stop_on_errors() {
+    let mut server = mockito::Server::new_async().await;
+    let info = |id: i32, version: &str, available: bool| format!(
+        r#"{{ "name": "Mod", "version": "{}", "mod_id": {}, "status": "published", "available": {} }}"#,
+        version, id, available
+    );
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/1")
+        .with_status(200)
+        .with_body(info(1, "2.0", true))
+        .create_async()
+        .await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/2")
+        .with_status(200)
+        .with_body(info(2, "3.0", false))
+        .create_async()
+        .await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/3")
+        .with_status(404)
+        .create_async()
+        .await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/4")
+        .with_status(401)
+        .create_async()
+        .await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/5")
+        .with_status(429)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    let versions = client.latest_versions(&[1, 2, 3]).await.unwrap();
+    assert_eq!(versions, vec![(1, "2.0".to_string())]);
+    assert!(client.latest_versions(&[1, 4]).await.is_err());
+    let err = client.latest_versions(&[5, 1]).await.unwrap_err();
+    assert!(err.to_string().contains("rate limit exceeded"));
+}
+
+#[tokio::test]
+async fn endorsements_post_the_version() {
+    let mut server = mockito::Server::new_async().await;
+    let endorse = server.mock("POST", "/games/stalker2heartofchornobyl/mods/33/endorse.json")
+        .match_header("apikey", "test-key")
+        .match_body(mockito::Matcher::UrlEncoded("version".into(), "1.2".into()))
+        .with_status(200)
+        .with_body(r#"{ "message": "Updated endorse status", "status": "Endorsed" }"#)
+        .create_async()
+        .await;
+    let abstain = server.mock("POST", "/games/stalker2heartofchornobyl/mods/34/abstain.json")
+        .match_body(mockito::Matcher::UrlEncoded("version".into(), "2.0".into()))
+        .with_status(200)
+        .create_async()
+        .await;
+    server.mock("POST", "/games/stalker2heartofchornobyl/mods/35/endorse.json")
+        .with_status(403)
+        .with_body(r#"{ "message": "NOT_DOWNLOADED_MOD" }"#)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    client.endorse_mod(33, "1.2").await.unwrap();
+    client.abstain_mod(34, "2.0").await.unwrap();
+    let err = client.endorse_mod(35, "1.0").await.unwrap_err();
+
+    endorse.assert_async().await;
+    abstain.assert_async().await;
+    assert!(err.to_string().contains("403"));
+}
+
+#[tokio::test]
+async fn tracking_sends_the_mod_and_game() {
+    let mut server = mockito::Server::new_async().await;
+    let query = mockito::Matcher::UrlEncoded("domain_name".into(), GAME.into());
+    let body = mockito::Matcher::UrlEncoded("mod_id".into(), "33".into());
+    let track = server.mock("POST", "/user/tracked_mods.json")
+        .match_query(query.clone())
+        .match_body(body.clone())
+        .with_status(201)
+        .create_async()
+        .await;
+    let untrack = server.mock("DELETE", "/user/tracked_mods.json")
+        .match_query(query)
+        .match_body(body)
+        .with_status(200)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    client.track_mod(33).await.unwrap();
+    client.untrack_mod(33).await.unwrap();
+
+    track.assert_async().await;
+    untrack.assert_async().await;
+}
+
+#[tokio::test]
+async fn tracked_mods_and_endorsements_are_limited_to_the_game() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/user/tracked_mods.json")
+        .with_status(200)
+        .with_body(r#"[
+            { "mod_id": 33, "domain_name": "stalker2heartofchornobyl" },
+            { "mod_id": 12, "domain_name": "skyrimspecialedition" }
+        ]"#)
+        .create_async()
+        .await;
+    server.mock("GET", "/user/endorsements.json")
+        .with_status(200)
+        .with_body(r#"[
+            { "mod_id": 33, "domain_name": "stalker2heartofchornobyl", "version": "1.2", "date": 0, "status": "Endorsed" },
+            { "mod_id": 34, "domain_name": "stalker2heartofchornobyl", "version": "1.0", "date": 0, "status": "Abstained" },
+            { "mod_id": 12, "domain_name": "skyrimspecialedition", "version": "1.0", "date": 0, "status": "Endorsed" }
+        ]"#)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    let tracked = client.get_tracked_mods().await.unwrap();
+    assert_eq!(tracked.iter().map(|t| t.mod_id).collect::<Vec<_>>(), [33]);
+    let endorsements = client.get_endorsements().await.unwrap();
+    assert_eq!(endorsements.len(), 2);
+    assert_eq!(endorsements[0].status, EndorsementStatus::Endorsed);
+    assert_eq!(endorsements[1].status, EndorsementStatus::Abstained);
+}
+
+#[tokio::test]
+async fn game_info_lists_the_categories() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/games/stalker2heartofchornobyl.json")
+        .match_header("apikey", "test-key")
+        .with_status(200)
+        .with_body(r#"{
+            "name": "S.T.A.L.K.E.R. 2: Heart of Chornobyl",
+            "categories": [
+                { "category_id": 1, "name": "Stalker 2", "parent_category": false },
+                { "category_id": 4, "name": "Weapons", "parent_category": 1 }
+            ]
+        }"#)
+        .create_async()
+        .await;
+
+    let game = client(&server).get_game_info().await.unwrap();
+
+    assert_eq!(game.name, "S.T.A.L.K.E.R. 2: Heart of Chornobyl");
+    let categories: Vec<(i32, &str)> = game.categories.iter().map(|c| (c.category_id, c.name.as_str())).collect();
+    assert_eq!(categories, [(1, "Stalker 2"), (4, "Weapons")]);
+}
+
+fn feed_entry(id: i32, category_id: i32, status: &str, available: bool) -> String {
+    format!(
+        r#"{{ "name": "Mod {0}", "mod_id": {0}, "category_id": {1}, "status": "{2}", "available": {3} }}"#,
+        id, category_id, status, available
+    )
+}
+
+#[tokio::test]
+async fn feeds_skip_hidden_and_removed_mods() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/trending.json")
+        .with_status(200)
+        .with_body(format!(
+            "[{}, {}, {}]",
+            feed_entry(1, 4, "published", true),
+            feed_entry(2, 4, "hidden", false),
+            feed_entry(3, 4, "removed", false)
+        ))
+        .create_async()
+        .await;
+    server.mock("GET", "/games/stalker2heartofchornobyl/mods/latest_added.json")
+        .with_status(500)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    let mods = client.get_mod_feed(ModFeed::Trending).await.unwrap();
+    assert_eq!(mods.iter().map(|m| m.id).collect::<Vec<_>>(), [1]);
+    let err = client.get_mod_feed(ModFeed::LatestAdded).await.unwrap_err();
+    assert!(err.to_string().contains("Latest Added"));
+}
+
+#[tokio::test]
+async fn category_listings_merge_the_feeds_without_duplicates() {
+    let mut server = mockito::Server::new_async().await;
+    let feeds = [
+        ("latest_added.json", vec![feed_entry(1, 4, "published", true), feed_entry(2, 5, "published", true)]),
+        ("latest_updated.json", vec![feed_entry(1, 4, "published", true), feed_entry(3, 4, "hidden", false)]),
+        ("trending.json", vec![feed_entry(4, 4, "published", true)]),
+    ];
+    for (feed, entries) in feeds {
+        server.mock("GET", format!("/games/stalker2heartofchornobyl/mods/{}", feed).as_str())
+            .with_status(200)
+            .with_body(format!("[{}]", entries.join(",")))
+            .create_async()
+            .await;
+    }
+
+    let mods = client(&server).get_mods_in_category(4).await.unwrap();
+
+    assert_eq!(mods.iter().map(|m| m.id).collect::<Vec<_>>(), [1, 4]);
+}
+
+#[tokio::test]
+async fn images_come_without_the_api_key() {
+    let mut server = mockito::Server::new_async().await;
+    server.mock("GET", "/images/thumb.jpg")
+        .match_header("apikey", mockito::Matcher::Missing)
+        .with_status(200)
+        .with_body("jpeg-bytes")
+        .create_async()
+        .await;
+    server.mock("GET", "/images/missing.jpg")
+        .with_status(404)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    let image = client.fetch_image(&format!("{}/images/thumb.jpg", server.url())).await.unwrap();
+    assert_eq!(image, b"jpeg-bytes");
+    assert!(client.fetch_image(&format!("{}/images/missing.jpg", server.url())).await.is_err());
+}
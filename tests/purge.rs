@@ -0,0 +1,42 @@
+use stalker2_mod_manager::mod_manager::ModManager;
+use stalker2_mod_manager::settings::Settings;
+use std::fs;
+
+#[test]
+fn purge_leaves_the_game_vanilla_and_redeploy_restores_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let game = dir.path().join("game");
+    let store = dir.path().join("store");
+    let mod_manager = ModManager::new(Settings {
+        game_path: Some(game.clone()),
+        mod_storage_path: Some(store.clone()),
+        ..Settings::default()
+    }).unwrap();
+
+    fs::write(store.join("enabled.pak"), "a").unwrap();
+    fs::write(store.join("disabled.pak"), "b").unwrap();
+    mod_manager.enable_mod(&store.join("enabled.pak")).unwrap();
+    let win64 = game.join("Stalker2").join("Binaries").join("Win64");
+    fs::create_dir_all(win64.join("ue4ss")).unwrap();
+    fs::write(win64.join("ue4ss").join("UE4SS.dll"), "dll").unwrap();
+    fs::write(win64.join("dwmapi.dll"), "proxy").unwrap();
+
+    let mut mods = mod_manager.reconcile(&[]).unwrap().apply(&[], false);
+    assert_eq!(mods.iter().filter(|m| m.enabled).count(), 2);
+
+    let issues = mod_manager.purge(&mut mods).unwrap();
+    assert!(issues.is_empty(), "{:?}", issues);
+    assert!(mod_manager.is_purged());
+    assert!(mods.iter().all(|m| !m.enabled));
+    assert!(!win64.join("ue4ss").exists());
+    assert!(!win64.join("dwmapi.dll").exists());
+    assert!(mod_manager.purge(&mut mods).is_err());
+
+    mod_manager.redeploy(&mut mods).unwrap();
+    assert!(!mod_manager.is_purged());
+    assert!(mod_manager.mods_path().join("enabled.pak").exists());
+    assert!(!mod_manager.mods_path().join("disabled.pak").exists());
+    assert_eq!(fs::read_to_string(win64.join("dwmapi.dll")).unwrap(), "proxy");
+    assert!(win64.join("ue4ss").join("UE4SS.dll").exists());
+    assert_eq!(mods.iter().filter(|m| m.enabled).count(), 2);
+}
@@ -0,0 +1,90 @@
+use stalker2_mod_manager::mod_manager::ModManager;
+use stalker2_mod_manager::reconcile::Change;
+use stalker2_mod_manager::settings::Settings;
+use std::fs;
+
+fn manager(dir: &std::path::Path) -> ModManager {
+    ModManager::new(Settings {
+        game_path: Some(dir.join("game")),
+        mod_storage_path: Some(dir.join("store")),
+        ..Settings::default()
+    }).unwrap()
+}
+
+#[test]
+fn reconcile_reports_what_changed_on_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let mod_manager = manager(dir.path());
+    let store = mod_manager.staging_path().to_path_buf();
+    fs::write(store.join("kept.pak"), "a").unwrap();
+    fs::write(store.join("gone.pak"), "b").unwrap();
+    mod_manager.enable_mod(&store.join("kept.pak")).unwrap();
+
+    let found = mod_manager.reconcile(&[]).unwrap();
+    assert_eq!(found.changes.len(), 2);
+    assert!(found.is_unambiguous());
+    let mods = found.apply(&[], false);
+    assert!(mod_manager.reconcile(&mods).unwrap().is_empty());
+
+    fs::remove_file(store.join("gone.pak")).unwrap();
+    fs::remove_file(mod_manager.mods_path().join("kept.pak")).unwrap();
+    // A different size than the missing pak, so it isn't taken for it renamed
+    fs::write(mod_manager.mods_path().join("new.pak"), "cc").unwrap();
+    let found = mod_manager.reconcile(&mods).unwrap();
+    assert!(!found.is_unambiguous());
+    assert!(found.changes.iter().any(|c| matches!(c, Change::EnabledMismatch { enabled_on_disk: false, .. })));
+
+    assert_eq!(found.apply(&mods, false).len(), 3);
+    let synced = found.apply(&mods, true);
+    let state: Vec<(&str, bool)> = synced.iter().map(|m| (m.name.as_str(), m.enabled)).collect();
+    assert_eq!(state, [("kept", false), ("new", true)]);
+}
+
+#[test]
+fn renamed_paks_keep_their_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let mod_manager = manager(dir.path());
+    let store = mod_manager.staging_path().to_path_buf();
+    fs::write(store.join("guns.pak"), "guns").unwrap();
+    fs::write(store.join("armor.pak"), "armor").unwrap();
+    mod_manager.enable_mod(&store.join("guns.pak")).unwrap();
+    let mods = mod_manager.reconcile(&[]).unwrap().apply(&[], false);
+    let guns = mods.iter().find(|mod_info| mod_info.name == "guns").unwrap().id;
+
+    // Renamed in the store, and taken out of `~mods` by hand
+    fs::rename(store.join("guns.pak"), store.join("zz_guns.pak")).unwrap();
+    fs::remove_file(mod_manager.mods_path().join("guns.pak")).unwrap();
+    let found = mod_manager.reconcile(&mods).unwrap();
+    assert!(found.is_unambiguous());
+    let mods = found.apply(&mods, false);
+    assert_eq!(mods.len(), 2);
+    let entry = mods.iter().find(|mod_info| mod_info.id == guns).unwrap();
+    assert_eq!(entry.installed_path.as_deref(), Some(store.join("zz_guns.pak").as_path()));
+    assert!(!entry.enabled);
+    assert!(mod_manager.reconcile(&mods).unwrap().is_empty());
+}
+
+#[test]
+fn renames_are_not_guessed_between_paks_of_the_same_size() {
+    let dir = tempfile::tempdir().unwrap();
+    let mod_manager = manager(dir.path());
+    let store = mod_manager.staging_path().to_path_buf();
+    fs::write(store.join("a.pak"), "same").unwrap();
+    fs::write(store.join("b.pak"), "same").unwrap();
+    let mods = mod_manager.reconcile(&[]).unwrap().apply(&[], false);
+
+    fs::rename(store.join("a.pak"), store.join("c.pak")).unwrap();
+    fs::rename(store.join("b.pak"), store.join("d.pak")).unwrap();
+    let found = mod_manager.reconcile(&mods).unwrap();
+    assert_eq!(found.changes.iter().filter(|c| matches!(c, Change::Missing { .. })).count(), 2);
+    assert_eq!(found.changes.iter().filter(|c| matches!(c, Change::Added(_))).count(), 2);
+}
+
+#[test]
+fn loading_the_list_writes_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    let mod_manager = manager(dir.path());
+    fs::write(mod_manager.mods_path().join("loose.pak"), "a").unwrap();
+    assert!(mod_manager.load_mod_list().unwrap().is_empty());
+    assert!(!dir.path().join("game").join("Stalker2").join("ModManager").join("mod_list.json").exists());
+}
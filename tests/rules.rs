@@ -0,0 +1,75 @@
+use stalker2_mod_manager::dependencies::DependencyTarget;
+use stalker2_mod_manager::mod_info::{ModInfo, ModKind};
+use stalker2_mod_manager::rules::{self, ModRule, RuleKind, RuleViolation};
+use std::path::PathBuf;
+
+fn pak(file: &str, enabled: bool) -> ModInfo {
+    ModInfo {
+        name: file.trim_end_matches(".pak").to_string(),
+        installed_path: Some(PathBuf::from(file)),
+        enabled,
+        ..ModInfo::default()
+    }
+}
+
+fn add_rule(mods: &mut [ModInfo], index: usize, kind: RuleKind, other: usize) {
+    let rule = ModRule {
+        kind,
+        target: DependencyTarget::for_mod(&mods[other]),
+        name: mods[other].name.clone(),
+    };
+    mods[index].rules.push(rule);
+}
+
+#[test]
+fn paks_load_in_file_name_order_ignoring_case() {
+    let mut lua = pak("0_script", true);
+    lua.kind = ModKind::Lua;
+    let mods = vec![pak("b.pak", true), pak("A.pak", true), pak("c.pak", false), lua];
+    assert_eq!(rules::load_order(&mods), [1, 0]);
+}
+
+#[test]
+fn incompatible_mods_are_reported_once_and_block_enabling() {
+    let mut mods = vec![pak("a.pak", true), pak("b.pak", false)];
+    add_rule(&mut mods, 0, RuleKind::IncompatibleWith, 1);
+    add_rule(&mut mods, 1, RuleKind::IncompatibleWith, 0);
+    assert!(rules::validate(&mods).is_empty());
+
+    let violations = rules::check_enable(&mods, 1);
+    assert_eq!(violations.len(), 1);
+    assert!(violations[0].is_blocking());
+    assert!(violations[0].involves(mods[0].id) && violations[0].involves(mods[1].id));
+    assert_eq!(violations[0].to_string(), "a is incompatible with b");
+}
+
+#[test]
+fn load_after_rules_are_broken_by_an_earlier_file_name() {
+    let mut mods = vec![pak("a.pak", true), pak("b.pak", true), pak("c.pak", true)];
+    add_rule(&mut mods, 0, RuleKind::LoadAfter, 1);
+    add_rule(&mut mods, 2, RuleKind::LoadAfter, 1);
+
+    let violations = rules::validate(&mods);
+    assert_eq!(violations.len(), 1);
+    assert!(matches!(&violations[0], RuleViolation::LoadOrder { mod_id, after_id, .. }
+        if *mod_id == mods[0].id && *after_id == mods[1].id));
+    assert!(!violations[0].is_blocking());
+    assert!(!violations[0].involves(mods[2].id));
+    assert_eq!(violations[0].to_string(), "a must load after b");
+
+    // Only paks have a load order
+    mods[1].kind = ModKind::Lua;
+    assert!(rules::validate(&mods).is_empty());
+}
+
+#[test]
+fn check_enable_only_reports_violations_of_that_mod() {
+    let mut mods = vec![pak("a.pak", true), pak("b.pak", true), pak("c.pak", false)];
+    add_rule(&mut mods, 0, RuleKind::LoadAfter, 1);
+    add_rule(&mut mods, 2, RuleKind::IncompatibleWith, 1);
+
+    let violations = rules::check_enable(&mods, 2);
+    assert_eq!(violations.len(), 1);
+    assert!(violations[0].is_blocking());
+    assert!(rules::check_enable(&mods, 5).is_empty());
+}
@@ -0,0 +1,72 @@
+use stalker2_mod_manager::saves::{self, Snapshot, SnapshotMod};
+use std::fs;
+
+fn snapshot(created: u64) -> Snapshot {
+    Snapshot {
+        path: Default::default(),
+        created,
+        reason: "Before enabling Better Guns".to_string(),
+        profile: Some("Hardcore".to_string()),
+        mods: vec![SnapshotMod { name: "Better Guns".to_string(), version: "1.2".to_string(), enabled: false }],
+    }
+}
+
+#[test]
+fn snapshots_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let saves_dir = dir.path().join("SaveGames");
+    let backups = dir.path().join("Backups");
+    fs::create_dir_all(saves_dir.join("76561198000000000")).unwrap();
+    fs::write(saves_dir.join("76561198000000000").join("quick.sav"), b"quick").unwrap();
+    fs::write(saves_dir.join("settings.sav"), b"settings").unwrap();
+
+    let created = saves::create(&saves_dir, &backups, snapshot(100)).unwrap();
+    let second = saves::create(&saves_dir, &backups, snapshot(100)).unwrap();
+    assert_ne!(created.path, second.path);
+
+    let read = saves::read(&created.path).unwrap();
+    assert_eq!(read.reason, "Before enabling Better Guns");
+    assert_eq!(read.profile.as_deref(), Some("Hardcore"));
+    assert_eq!(read.mods[0].name, "Better Guns");
+    assert_eq!(saves::list(&backups).len(), 2);
+
+    fs::remove_dir_all(&saves_dir).unwrap();
+    fs::create_dir_all(&saves_dir).unwrap();
+    fs::write(saves_dir.join("new.sav"), b"new").unwrap();
+
+    saves::restore(&created.path, &saves_dir).unwrap();
+    assert_eq!(fs::read(saves_dir.join("76561198000000000").join("quick.sav")).unwrap(), b"quick");
+    assert_eq!(fs::read(saves_dir.join("settings.sav")).unwrap(), b"settings");
+    assert!(!saves_dir.join("new.sav").exists());
+    assert!(!saves_dir.with_extension("old").exists());
+    assert!(!saves_dir.with_extension("restore").exists());
+}
+
+#[test]
+fn failed_restores_keep_the_current_saves() {
+    let dir = tempfile::tempdir().unwrap();
+    let saves_dir = dir.path().join("SaveGames");
+    fs::create_dir_all(&saves_dir).unwrap();
+    fs::write(saves_dir.join("current.sav"), b"current").unwrap();
+
+    let damaged = dir.path().join("saves-1.zip");
+    fs::write(&damaged, b"not a zip").unwrap();
+    assert!(saves::restore(&damaged, &saves_dir).is_err());
+
+    // A valid archive without a snapshot description is refused too
+    let foreign = dir.path().join("saves-2.zip");
+    let mut zip = zip::ZipWriter::new(fs::File::create(&foreign).unwrap());
+    zip.start_file("readme.txt", zip::write::FileOptions::default()).unwrap();
+    zip.finish().unwrap();
+    assert!(saves::restore(&foreign, &saves_dir).is_err());
+
+    assert_eq!(fs::read(saves_dir.join("current.sav")).unwrap(), b"current");
+    assert!(!saves_dir.with_extension("restore").exists());
+}
+
+#[test]
+fn missing_save_folders_are_not_snapshotted() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(saves::create(&dir.path().join("SaveGames"), &dir.path().join("Backups"), snapshot(1)).is_err());
+    assert!(saves::list(&dir.path().join("Backups")).is_empty());
+}
@@ -0,0 +1,80 @@
+use stalker2_mod_manager::mod_manager::ModManager;
+use stalker2_mod_manager::settings::Settings;
+use stalker2_mod_manager::staging::{deploy_file, is_deployment_of, DeployMethod};
+use std::fs;
+
+fn manager(game: &std::path::Path, store: &std::path::Path, method: DeployMethod) -> ModManager {
+    ModManager::new(Settings {
+        game_path: Some(game.to_path_buf()),
+        mod_storage_path: Some(store.to_path_buf()),
+        deploy_method: method,
+        ..Settings::default()
+    }).unwrap()
+}
+
+#[test]
+fn deployments_are_recognised() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("mod.pak");
+    fs::write(&source, "pak").unwrap();
+
+    for (method, name) in [(DeployMethod::Hardlink, "hard.pak"), (DeployMethod::Symlink, "sym.pak"), (DeployMethod::Copy, "copy.pak")] {
+        let target = dir.path().join("~mods").join(name);
+        assert_eq!(deploy_file(&source, &target, method).unwrap(), method);
+        assert!(is_deployment_of(&target, &source).unwrap());
+    }
+
+    let edited = dir.path().join("edited.pak");
+    fs::write(&edited, "changed").unwrap();
+    assert!(!is_deployment_of(&edited, &source).unwrap());
+}
+
+#[test]
+fn enabling_deploys_from_the_store_and_disabling_keeps_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let game = dir.path().join("game");
+    let store = dir.path().join("store");
+    let mod_manager = manager(&game, &store, DeployMethod::Hardlink);
+    fs::write(store.join("mod.pak"), "pak").unwrap();
+    fs::write(store.join("mod.utoc"), "utoc").unwrap();
+
+    mod_manager.enable_mod(&store.join("mod.pak")).unwrap();
+    assert!(mod_manager.mods_path().join("mod.pak").exists());
+    assert!(mod_manager.mods_path().join("mod.utoc").exists());
+    assert!(!mod_manager.mods_path().join("mod.pak.partial").exists());
+
+    mod_manager.disable_mod(&store.join("mod.pak")).unwrap();
+    assert!(!mod_manager.mods_path().join("mod.pak").exists());
+    assert!(!mod_manager.mods_path().join("mod.utoc").exists());
+    assert_eq!(fs::read_to_string(store.join("mod.pak")).unwrap(), "pak");
+    assert_eq!(fs::read_to_string(store.join("mod.utoc")).unwrap(), "utoc");
+}
+
+#[test]
+fn disabling_a_pak_changed_by_hand_keeps_both_copies() {
+    let dir = tempfile::tempdir().unwrap();
+    let game = dir.path().join("game");
+    let store = dir.path().join("store");
+    let mod_manager = manager(&game, &store, DeployMethod::Copy);
+    fs::write(store.join("mod.pak"), "v1").unwrap();
+    mod_manager.enable_mod(&store.join("mod.pak")).unwrap();
+
+    fs::write(mod_manager.mods_path().join("mod.pak"), "v2").unwrap();
+    fs::write(mod_manager.mods_path().join("loose.pak"), "loose").unwrap();
+    mod_manager.disable_mod(&store.join("mod.pak")).unwrap();
+    mod_manager.disable_mod(&mod_manager.mods_path().join("loose.pak")).unwrap();
+
+    assert_eq!(fs::read_to_string(store.join("mod.pak")).unwrap(), "v1");
+    assert_eq!(fs::read_to_string(store.join("mod.modified.pak")).unwrap(), "v2");
+    assert_eq!(fs::read_to_string(store.join("loose.pak")).unwrap(), "loose");
+    assert_eq!(fs::read_dir(mod_manager.mods_path()).unwrap().count(), 0);
+
+    // A second edit doesn't replace the first
+    mod_manager.enable_mod(&store.join("mod.pak")).unwrap();
+    fs::write(mod_manager.mods_path().join("mod.pak"), "v3").unwrap();
+    mod_manager.disable_mod(&store.join("mod.pak")).unwrap();
+    assert_eq!(fs::read_to_string(store.join("mod.modified.pak")).unwrap(), "v2");
+    assert_eq!(fs::read_to_string(store.join("mod.modified-2.pak")).unwrap(), "v3");
+}
+
+
@@ -0,0 +1,79 @@
+use stalker2_mod_manager::mod_info::ModKind;
+use stalker2_mod_manager::mod_manager::ModManager;
+use stalker2_mod_manager::settings::Settings;
+use stalker2_mod_manager::ue4ss::{self, ArchiveKind};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+fn write_zip(path: &Path, files: &[&str]) {
+    let mut zip = zip::ZipWriter::new(fs::File::create(path).unwrap());
+    for name in files {
+        zip.start_file(*name, zip::write::FileOptions::default()).unwrap();
+        zip.write_all(b"-- content").unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn archives_are_told_apart_by_their_layout() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("archive.zip");
+    let cases: [(&[&str], ArchiveKind); 4] = [
+        (&["dwmapi.dll", "ue4ss/UE4SS.dll", "ue4ss/Mods/mods.txt"], ArchiveKind::Runtime),
+        (&["Release/MyMod/Scripts/main.lua"], ArchiveKind::Mods),
+        (&["NativeMod/dlls/main.dll", "NativeMod/enabled.txt"], ArchiveKind::Mods),
+        (&["Mod/Mod.pak", "Mod/main.lua"], ArchiveKind::Other),
+    ];
+    for (files, kind) in cases {
+        write_zip(&archive, files);
+        assert_eq!(ue4ss::classify_archive(&archive).unwrap(), kind, "{:?}", files);
+    }
+}
+
+#[test]
+fn every_mod_of_an_archive_is_installed_and_listed() {
+    let dir = tempfile::tempdir().unwrap();
+    let mod_manager = ModManager::new(Settings {
+        game_path: Some(dir.path().join("game")),
+        mod_storage_path: Some(dir.path().join("store")),
+        ..Settings::default()
+    }).unwrap();
+    let archive = dir.path().join("Pack.zip");
+    write_zip(&archive, &["Pack/First/Scripts/main.lua", "Pack/Second/Scripts/main.lua", "Pack/readme.txt"]);
+
+    let installed = mod_manager.install_ue4ss_archive(&archive).unwrap().unwrap();
+    let names: Vec<&str> = installed.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["First", "Second"]);
+    for mod_info in &installed {
+        assert_eq!(mod_info.kind, ModKind::Lua);
+        assert!(mod_info.enabled);
+        assert!(mod_manager.is_enabled_on_disk(mod_info));
+    }
+}
+
+#[test]
+fn toggling_a_mod_updates_mods_txt_and_enabled_txt() {
+    let dir = tempfile::tempdir().unwrap();
+    let mods = dir.path();
+    fs::create_dir_all(mods.join("MyMod").join("Scripts")).unwrap();
+    fs::write(mods.join("mods.txt"), "; comment\r\nBPModLoaderMod : 1\r\nKeybinds : 1\r\n").unwrap();
+
+    ue4ss::set_mod_enabled(mods, "MyMod", true).unwrap();
+    assert_eq!(
+        fs::read_to_string(mods.join("mods.txt")).unwrap(),
+        "; comment\r\nBPModLoaderMod : 1\r\nMyMod : 1\r\nKeybinds : 1\r\n"
+    );
+    assert!(mods.join("MyMod").join("enabled.txt").exists());
+    assert!(ue4ss::is_mod_enabled(mods, "MyMod"));
+
+    ue4ss::set_mod_enabled(mods, "MyMod", false).unwrap();
+    assert!(fs::read_to_string(mods.join("mods.txt")).unwrap().contains("MyMod : 0\r\n"));
+    assert!(!mods.join("MyMod").join("enabled.txt").exists());
+    assert!(!ue4ss::is_mod_enabled(mods, "MyMod"));
+
+    // A marker left by hand enables the mod whatever mods.txt says
+    fs::write(mods.join("MyMod").join("enabled.txt"), "").unwrap();
+    assert!(ue4ss::is_mod_enabled(mods, "MyMod"));
+    assert!(ue4ss::set_mod_enabled(mods, "Missing", true).is_err());
+}
@@ -0,0 +1,55 @@
+#![cfg(not(windows))]
+
+use stalker2_mod_manager::user_data::{detect, heroic_prefixes, prefix_user_data, STEAM_APP_ID};
+use std::fs;
+
+#[test]
+fn steam_games_use_the_compatdata_prefix_of_their_library() {
+    let library = tempfile::tempdir().unwrap();
+    let game = library.path().join("steamapps").join("common").join("S.T.A.L.K.E.R. 2");
+    let user_data = library.path().join("steamapps").join("compatdata").join(STEAM_APP_ID)
+        .join("pfx").join("drive_c").join("users").join("steamuser")
+        .join("AppData").join("Local").join("Stalker2");
+    fs::create_dir_all(&game).unwrap();
+    fs::create_dir_all(&user_data).unwrap();
+
+    assert_eq!(detect(&game), Some(user_data));
+}
+
+#[test]
+fn wine_games_use_the_prefix_they_are_installed_in() {
+    let prefix = tempfile::tempdir().unwrap();
+    let game = prefix.path().join("drive_c").join("Games").join("S.T.A.L.K.E.R. 2");
+    let user_data = prefix.path().join("drive_c").join("users").join("player")
+        .join("AppData").join("Local").join("Stalker2");
+    fs::create_dir_all(&game).unwrap();
+    fs::create_dir_all(&user_data).unwrap();
+
+    assert_eq!(detect(&game), Some(user_data));
+}
+
+#[test]
+fn heroic_prefixes_are_read_from_the_game_configs() {
+    let config = tempfile::tempdir().unwrap();
+    let prefix = tempfile::tempdir().unwrap();
+    let user_data = prefix.path().join("pfx").join("drive_c").join("users").join("steamuser")
+        .join("AppData").join("Local").join("Stalker2");
+    fs::create_dir_all(&user_data).unwrap();
+
+    let game_config = serde_json::json!({ "c04ba25a": { "winePrefix": prefix.path(), "wineVersion": {} } });
+    fs::write(config.path().join("c04ba25a.json"), game_config.to_string()).unwrap();
+    fs::write(config.path().join("broken.json"), "{").unwrap();
+
+    let prefixes = heroic_prefixes(config.path());
+    assert_eq!(prefixes, vec![prefix.path().to_path_buf()]);
+    assert_eq!(prefix_user_data(&prefixes[0]), vec![user_data]);
+}
+
+#[test]
+fn missing_prefixes_are_not_detected() {
+    let dir = tempfile::tempdir().unwrap();
+    let game = dir.path().join("drive_c").join("Games").join("S.T.A.L.K.E.R. 2");
+    fs::create_dir_all(&game).unwrap();
+
+    assert!(prefix_user_data(dir.path()).is_empty());
+}